@@ -0,0 +1,192 @@
+use {
+    std::future::Future,
+    std::marker::PhantomData,
+    std::pin::Pin,
+    std::task::Context,
+    std::task::Poll,
+
+    actix_web::FromRequest,
+    actix_web::HttpRequest,
+    actix_web::dev::Payload,
+    actix_web::error,
+    futures_core::Stream,
+    serde::de::DeserializeOwned,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonStatus,
+};
+
+
+
+/// an `actix-web` extractor that repairs a json request body, then deserializes it into `T`.
+///
+/// # remarks
+///
+/// the body is streamed through a [`JsonBuilder`](./struct.JsonBuilder.html) created with the options in the app's
+/// [`RepairedJsonConfig`](./struct.RepairedJsonConfig.html), or the default options if there is none. input that
+/// cannot be repaired is rejected with `400 Bad Request` and a message that includes its offset, without reading the
+/// rest of the body.
+///
+/// requires the `actix` feature.
+///
+/// # examples
+///
+/// ```
+/// # extern crate actix_web;
+/// #
+/// # use actix_web::FromRequest;
+/// # use actix_web::test::TestRequest;
+/// # use arya::{RepairedJson, RepairedJsonConfig};
+/// # use std::future::Future;
+/// # use std::pin::pin;
+/// # use std::task::{Context, Poll, Waker};
+/// #
+/// # fn main() {
+/// #
+/// let mut config = RepairedJsonConfig::default();
+///
+/// config.options.lenient.literals = true;
+/// config.complete                = true;
+///
+/// let request                = TestRequest::default().app_data(config).set_payload("[true, False, None");
+/// let (request, mut payload) = request.to_http_parts();
+///
+/// let result = RepairedJson::<Vec<Option<bool>>>::from_request(&request, &mut payload);
+/// let result = match pin!(result).poll(&mut Context::from_waker(Waker::noop())) {
+///     Poll::Ready(x) => x,
+///     Poll::Pending  => unreachable!(),
+/// };
+///
+/// assert_eq!(result.unwrap().0, vec![Some(true), Some(false), None]);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RepairedJson<T>(pub T);
+
+impl<T> RepairedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for RepairedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> std::ops::DerefMut for RepairedJson<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for RepairedJson<T> {
+    type Error  = actix_web::Error;
+    type Future = RepairedJsonFuture<T>;
+
+    fn from_request(request: &HttpRequest, payload: &mut Payload) -> RepairedJsonFuture<T> {
+        let config = request.app_data::<RepairedJsonConfig>().cloned().unwrap_or_default();
+
+        RepairedJsonFuture {
+            payload:  payload.take(),
+            builder:  Box::new(JsonBuilder::with_options(config.options)),
+            complete: config.complete,
+            limit:    config.limit,
+            offset:   0,
+            marker:   PhantomData,
+        }
+    }
+}
+
+
+
+/// configures [`RepairedJson`](./struct.RepairedJson.html). register it with `App::app_data()`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct RepairedJsonConfig {
+    /// the options the body's builder is created with, including which lenient repairs are made.
+    pub options:  JsonBuilderOptions,
+
+    /// complete a body that ends before its json object is complete, instead of rejecting it.
+    pub complete: bool,
+
+    /// the maximum length of the body, in bytes. longer bodies are rejected with `413 Payload Too Large`.
+    pub limit:    usize,
+}
+
+impl Default for RepairedJsonConfig {
+    fn default() -> RepairedJsonConfig {
+        RepairedJsonConfig {
+            options:  JsonBuilderOptions::default(),
+            complete: false,
+            limit:    2 * 1024 * 1024,
+        }
+    }
+}
+
+
+
+/// the future returned by [`RepairedJson`](./struct.RepairedJson.html)'s `FromRequest` implementation.
+pub struct RepairedJsonFuture<T> {
+    payload:  Payload,
+    builder:  Box<JsonBuilder>,
+    complete: bool,
+    limit:    usize,
+    offset:   usize,
+    marker:   PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Future for RepairedJsonFuture<T> {
+    type Output = Result<RepairedJson<T>, actix_web::Error>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            match Pin::new(&mut this.payload).poll_next(context) {
+                Poll::Pending => {
+                    return Poll::Pending;
+                },
+                Poll::Ready(Some(Ok(chunk))) => {
+                    if this.offset + chunk.len() > this.limit {
+                        let e = JsonError::at(JsonErrorKind::TooLarge, this.limit);
+
+                        return Poll::Ready(Err(error::ErrorPayloadTooLarge(e)));
+                    }
+
+                    if let Err(e) = this.builder.update_at(&chunk, &mut this.offset) {
+                        return Poll::Ready(Err(error::ErrorBadRequest(e)));
+                    }
+                },
+                Poll::Ready(Some(Err(e))) => {
+                    return Poll::Ready(Err(e.into()));
+                },
+                Poll::Ready(None) => {
+                    break;
+                },
+            }
+        }
+
+        let builder = std::mem::replace(&mut *this.builder, JsonBuilder::new());
+        let data    = match (builder.status(), this.complete) {
+            (JsonStatus::Valid, _)       => builder.bytes(),
+            (JsonStatus::Continue, true) => builder.completed_bytes(),
+            (JsonStatus::Continue, _)    => Err(JsonErrorKind::Invalid.into()),
+        };
+
+        let data = match data {
+            Ok(x)  => x,
+            Err(e) => return Poll::Ready(Err(error::ErrorBadRequest(e.with_offset(this.offset)))),
+        };
+
+        let value = serde_json::from_slice(&data).map_err(error::ErrorBadRequest);
+
+        Poll::Ready(value.map(RepairedJson))
+    }
+}
@@ -0,0 +1,166 @@
+use {
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+};
+
+
+
+/// a streaming base64 decoder, for json that is transported base64 encoded.
+///
+/// # remarks
+///
+/// decoded bytes are passed to a sink as they become available, so the decoder can be chained directly into
+/// [`JsonBuilder::update()`](./struct.JsonBuilder.html#method.update) or
+/// [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update) without buffering the decoded document.
+///
+/// both the standard and the url-safe alphabets are accepted, padding is optional, and ascii whitespace is ignored.
+/// the input must already be unescaped: if the base64 text is itself inside a json string, escapes such as `\/` must be
+/// removed first.
+///
+/// after an error, the decoder must be [`reset()`](#method.reset) before it is used again.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBase64Decoder, JsonBuilder};
+/// #
+/// # fn main() {
+/// #
+/// let mut decoder = JsonBase64Decoder::new();
+/// let mut builder = JsonBuilder::new();
+///
+/// // base64 for `{"a": [1, 2]}`, split at an arbitrary point.
+/// decoder.decode("eyJhIjogWzEs", |bytes| builder.update(bytes)).unwrap();
+/// decoder.decode("IDJdfQ==", |bytes| builder.update(bytes)).unwrap();
+/// decoder.finish(|bytes| builder.update(bytes)).unwrap();
+///
+/// assert_eq!(builder.string().unwrap(), r#"{"a": [1, 2]}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonBase64Decoder {
+    // the sextets of the current, incomplete quantum.
+    quantum: [u8; 4],
+    length:  usize,
+
+    // the number of padding characters that may still follow, once padding has begun.
+    padding: Option<usize>,
+}
+
+impl JsonBase64Decoder {
+    pub fn new() -> JsonBase64Decoder {
+        JsonBase64Decoder {
+            quantum: [0; 4],
+            length:  0,
+            padding: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.length  = 0;
+        self.padding = None;
+    }
+
+    /// decodes `encoded`, passing the decoded bytes to `sink`.
+    ///
+    /// an incomplete quantum at the end of `encoded` is kept until the next call to `decode()` or `finish()`.
+    pub fn decode(
+        &mut self,
+        encoded:  impl JsonSource,
+        mut sink: impl FnMut(&[u8]) -> Result<(), JsonError>) -> Result<(), JsonError>
+    {
+        let mut decoded = [0; 96];
+        let mut length  = 0;
+
+        for &character in encoded.stream() {
+            if character.is_ascii_whitespace() {
+                continue;
+            }
+
+            match (character, self.padding) {
+                (b'=', Some(0)) => return Err(JsonErrorKind::Base64.into()),
+                (b'=', Some(n)) => self.padding = Some(n - 1),
+                (b'=', None)    => {
+                    if self.length < 2 {
+                        return Err(JsonErrorKind::Base64.into());
+                    }
+
+                    length      += self.flush(&mut decoded[length..]);
+                    self.padding = Some(4 - self.length - 1);
+                    self.length  = 0;
+                },
+                (_, Some(_)) => return Err(JsonErrorKind::Base64.into()),
+                (_, None)    => {
+                    self.quantum[self.length] = sextet(character)?;
+                    self.length += 1;
+
+                    if self.length == 4 {
+                        length     += self.flush(&mut decoded[length..]);
+                        self.length = 0;
+                    }
+                },
+            }
+
+            if length + 3 > decoded.len() {
+                sink(&decoded[..length])?;
+                length = 0;
+            }
+        }
+
+        match length {
+            0 => Ok(()),
+            _ => sink(&decoded[..length]),
+        }
+    }
+
+    /// finishes decoding, passing any bytes held back from an unpadded final quantum to `sink`.
+    ///
+    /// the decoder is reset afterwards, and may be used to decode another stream.
+    pub fn finish(&mut self, mut sink: impl FnMut(&[u8]) -> Result<(), JsonError>) -> Result<(), JsonError> {
+        let mut decoded = [0; 3];
+
+        let result = match self.length {
+            0 => Ok(()),
+            1 => Err(JsonErrorKind::Base64.into()),
+            _ => {
+                let length = self.flush(&mut decoded);
+                sink(&decoded[..length])
+            },
+        };
+
+        self.reset();
+        result
+    }
+
+
+
+    // writes the bytes of the current quantum to `output`, returning the number of bytes written.
+    fn flush(&self, output: &mut [u8]) -> usize {
+        let [a, b, c, d] = self.quantum;
+
+        let bytes = [
+            a << 2 | b >> 4,
+            b << 4 | c >> 2,
+            c << 6 | d,
+        ];
+
+        let length = self.length - 1;
+
+        output[..length].copy_from_slice(&bytes[..length]);
+        length
+    }
+}
+
+
+
+fn sextet(character: u8) -> Result<u8, JsonError> {
+    match character {
+        b'A'..=b'Z'  => Ok(character - b'A'),
+        b'a'..=b'z'  => Ok(character - b'a' + 26),
+        b'0'..=b'9'  => Ok(character - b'0' + 52),
+        b'+' | b'-'  => Ok(62),
+        b'/' | b'_'  => Ok(63),
+        _            => Err(JsonErrorKind::Base64.into()),
+    }
+}
@@ -0,0 +1,686 @@
+use {
+    arya::JsonBuilder,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::build,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::strings,
+};
+
+
+
+impl JsonBuilder {
+    /// completes this json object like `completed_bytes()`, then encodes it as cbor (rfc 8949).
+    ///
+    /// # remarks
+    ///
+    /// requires the `cbor` feature. numbers without a fraction or exponent are encoded as integers if they fit in 64
+    /// bits, and every other number as the smallest float that holds it exactly, or as a double.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": [1, -2, 0.5, "b", true, null"#).unwrap();
+    ///
+    /// assert_eq!(builder.completed_cbor().unwrap(), vec![
+    ///     0xa1, 0x61, b'a',
+    ///     0x86, 0x01, 0x21, 0xfa, 0x3f, 0x00, 0x00, 0x00, 0x61, b'b', 0xf5, 0xf6,
+    /// ]);
+    /// # }
+    /// ```
+    #[cfg(feature = "cbor")]
+    pub fn completed_cbor(self) -> Result<Vec<u8>, JsonError> {
+        let json       = self.completed_bytes()?;
+        let mut output = Vec::with_capacity(json.len());
+
+        for item in items(&json)? {
+            match item {
+                Item::Array(count)  => cbor_head(&mut output, 4, count as u64),
+                Item::Object(count) => cbor_head(&mut output, 5, count as u64),
+                Item::String(text)  => {
+                    cbor_head(&mut output, 3, text.len() as u64);
+                    output.extend_from_slice(&text);
+                },
+                Item::Integer(x) if x >= 0 => cbor_head(&mut output, 0, x as u64),
+                Item::Integer(x)           => cbor_head(&mut output, 1, !(x as u64)),
+                Item::Unsigned(x)          => cbor_head(&mut output, 0, x),
+                Item::Float(x)             => {
+                    match x as f32 as f64 == x {
+                        true  => { output.push(0xfa); output.extend_from_slice(&(x as f32).to_be_bytes()); },
+                        false => { output.push(0xfb); output.extend_from_slice(&x.to_be_bytes()); },
+                    }
+                },
+                Item::Boolean(false) => output.push(0xf4),
+                Item::Boolean(true)  => output.push(0xf5),
+                Item::Null           => output.push(0xf6),
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// completes this json object like `completed_bytes()`, then encodes it as messagepack.
+    ///
+    /// # remarks
+    ///
+    /// requires the `msgpack` feature. numbers are encoded like in `completed_cbor()`.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": [1, -2, 0.5, "b", true, null"#).unwrap();
+    ///
+    /// assert_eq!(builder.completed_msgpack().unwrap(), vec![
+    ///     0x81, 0xa1, b'a',
+    ///     0x96, 0x01, 0xfe, 0xca, 0x3f, 0x00, 0x00, 0x00, 0xa1, b'b', 0xc3, 0xc0,
+    /// ]);
+    /// # }
+    /// ```
+    #[cfg(feature = "msgpack")]
+    pub fn completed_msgpack(self) -> Result<Vec<u8>, JsonError> {
+        let json       = self.completed_bytes()?;
+        let mut output = Vec::with_capacity(json.len());
+
+        for item in items(&json)? {
+            match item {
+                Item::Array(count)  => msgpack_head(&mut output, count, 0x90, 16, [0, 0xdc, 0xdd]),
+                Item::Object(count) => msgpack_head(&mut output, count, 0x80, 16, [0, 0xde, 0xdf]),
+                Item::String(text)  => {
+                    msgpack_head(&mut output, text.len(), 0xa0, 32, [0xd9, 0xda, 0xdb]);
+                    output.extend_from_slice(&text);
+                },
+                Item::Integer(x) if x >= 0 => msgpack_unsigned(&mut output, x as u64),
+                Item::Integer(x)           => msgpack_negative(&mut output, x),
+                Item::Unsigned(x)          => msgpack_unsigned(&mut output, x),
+                Item::Float(x)             => {
+                    match x as f32 as f64 == x {
+                        true  => { output.push(0xca); output.extend_from_slice(&(x as f32).to_be_bytes()); },
+                        false => { output.push(0xcb); output.extend_from_slice(&x.to_be_bytes()); },
+                    }
+                },
+                Item::Boolean(false) => output.push(0xc2),
+                Item::Boolean(true)  => output.push(0xc3),
+                Item::Null           => output.push(0xc0),
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// creates a builder from a cbor (rfc 8949) document, converted into json text.
+    ///
+    /// # remarks
+    ///
+    /// requires the `cbor` feature. byte strings become base64url strings, map keys that are not strings become the
+    /// text of their json value, tags are dropped, and non-finite floats and `undefined` become `null`. other simple
+    /// values, and input that is not exactly one cbor data item, fail with `JsonErrorKind::Binary`. arrays, maps and
+    /// tags nested more than 512 deep fail with `JsonErrorKind::Exceeded`.
+    ///
+    /// like any json object, the document must be an object or array.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let cbor    = [0xa2, 0x61, b'a', 0x82, 0x01, 0x20, 0x01, 0xf9, 0x3e, 0x00];
+    /// let builder = JsonBuilder::from_cbor(&cbor[..]).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{"a":[1,-1],"1":1.5}"#);
+    /// # }
+    /// ```
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(source: impl JsonSource) -> Result<JsonBuilder, JsonError> {
+        from_binary(source.stream(), cbor_value)
+    }
+
+    /// creates a builder from a messagepack document, converted into json text.
+    ///
+    /// # remarks
+    ///
+    /// requires the `msgpack` feature. values are converted like in `from_cbor()`. extension types fail with
+    /// `JsonErrorKind::Binary`.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let msgpack = [0x82, 0xa1, b'a', 0x92, 0x01, 0xff, 0x01, 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0];
+    /// let builder = JsonBuilder::from_msgpack(&msgpack[..]).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{"a":[1,-1],"1":1.5}"#);
+    /// # }
+    /// ```
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack(source: impl JsonSource) -> Result<JsonBuilder, JsonError> {
+        from_binary(source.stream(), msgpack_value)
+    }
+}
+
+
+
+// a value in a json document. containers hold the number of elements or members they contain, and are followed by
+// them. object members are a key `String` followed by their value.
+enum Item {
+    Array(usize),
+    Object(usize),
+    String(Vec<u8>),
+    Integer(i64),
+    Unsigned(u64),
+    Float(f64),
+    Boolean(bool),
+    Null,
+}
+
+// flattens the complete json document `data` into its values, in document order.
+fn items(data: &[u8]) -> Result<Vec<Item>, JsonError> {
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut items  = vec![];
+    let mut error  = None;
+
+    // the index of every open container in `items`.
+    let mut open = vec![];
+
+    for &character in data {
+        reader.update(character, |event| {
+            let value = match event {
+                Event::Begin(JsonValueKind::Object, _) => Item::Object(0),
+                Event::Begin(_, _)                     => Item::Array(0),
+                Event::End(_)                          => {
+                    open.pop();
+                    return;
+                },
+                Event::Key(range, _) => {
+                    match strings::decode_prefix(&data[range]) {
+                        Ok((x, _)) => items.push(Item::String(x)),
+                        Err(e)     => error = Some(e),
+                    }
+
+                    return;
+                },
+                Event::Scalar(kind, range) => {
+                    match scalar(kind, &data[range]) {
+                        Ok(x)  => x,
+                        Err(e) => {
+                            error = Some(e);
+                            return;
+                        },
+                    }
+                },
+            };
+
+            if let Some(&parent) = open.last() {
+                match &mut items[parent] {
+                    Item::Array(count) | Item::Object(count) => *count += 1,
+                    _                                        => {},
+                }
+            }
+
+            if let Item::Array(_) | Item::Object(_) = value {
+                open.push(items.len());
+            }
+
+            items.push(value);
+        })?;
+    }
+
+    match error {
+        Some(e) => Err(e),
+        None    => Ok(items),
+    }
+}
+
+fn scalar(kind: JsonValueKind, text: &[u8]) -> Result<Item, JsonError> {
+    match kind {
+        JsonValueKind::String  => strings::decode_prefix(text).map(|(x, _)| Item::String(x)),
+        JsonValueKind::Boolean => Ok(Item::Boolean(text == b"true")),
+        JsonValueKind::Null    => Ok(Item::Null),
+        _                      => {
+            // the verifier has already checked the syntax of `text`, so it is ascii and a valid number.
+            let text     = std::str::from_utf8(text).map_err(|_| JsonError::from(JsonErrorKind::Invalid))?;
+            let fraction = text.contains(['.', 'e', 'E']);
+
+            if !fraction {
+                if let Ok(x) = text.parse::<i64>() {
+                    return Ok(Item::Integer(x));
+                }
+
+                if let Ok(x) = text.parse::<u64>() {
+                    return Ok(Item::Unsigned(x));
+                }
+            }
+
+            text.parse::<f64>().map(Item::Float).map_err(|_| JsonError::from(JsonErrorKind::Invalid))
+        },
+    }
+}
+
+
+
+#[cfg(feature = "cbor")]
+fn cbor_head(output: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+
+    match value {
+        0..=23                => output.push(major | value as u8),
+        24..=0xff             => output.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xffff        => { output.push(major | 25); output.extend_from_slice(&(value as u16).to_be_bytes()); },
+        0x10000..=0xffff_ffff => { output.push(major | 26); output.extend_from_slice(&(value as u32).to_be_bytes()); },
+        _                     => { output.push(major | 27); output.extend_from_slice(&value.to_be_bytes()); },
+    }
+}
+
+// writes the header of a string, array or map of `length`: a fix header of `fix | length` if it is less than `limit`,
+// otherwise the 8, 16 or 32 bit header from `wide`. arrays and maps have no 8 bit header, so it is never chosen for
+// them.
+#[cfg(feature = "msgpack")]
+fn msgpack_head(output: &mut Vec<u8>, length: usize, fix: u8, limit: usize, wide: [u8; 3]) {
+    if length < limit {
+        output.push(fix | length as u8);
+    } else if length <= 0xff && wide[0] != 0 {
+        output.extend_from_slice(&[wide[0], length as u8]);
+    } else if length <= 0xffff {
+        output.push(wide[1]);
+        output.extend_from_slice(&(length as u16).to_be_bytes());
+    } else {
+        output.push(wide[2]);
+        output.extend_from_slice(&(length as u32).to_be_bytes());
+    }
+}
+
+#[cfg(feature = "msgpack")]
+fn msgpack_unsigned(output: &mut Vec<u8>, value: u64) {
+    match value {
+        0..=0x7f              => output.push(value as u8),
+        0x80..=0xff           => output.extend_from_slice(&[0xcc, value as u8]),
+        0x100..=0xffff        => { output.push(0xcd); output.extend_from_slice(&(value as u16).to_be_bytes()); },
+        0x10000..=0xffff_ffff => { output.push(0xce); output.extend_from_slice(&(value as u32).to_be_bytes()); },
+        _                     => { output.push(0xcf); output.extend_from_slice(&value.to_be_bytes()); },
+    }
+}
+
+#[cfg(feature = "msgpack")]
+fn msgpack_negative(output: &mut Vec<u8>, value: i64) {
+    match value {
+        -32..=-1               => output.push(value as i8 as u8),
+        -0x80..=-33            => output.extend_from_slice(&[0xd0, value as i8 as u8]),
+        -0x8000..=-0x81        => { output.push(0xd1); output.extend_from_slice(&(value as i16).to_be_bytes()); },
+        -0x8000_0000..=-0x8001 => { output.push(0xd2); output.extend_from_slice(&(value as i32).to_be_bytes()); },
+        _                      => { output.push(0xd3); output.extend_from_slice(&value.to_be_bytes()); },
+    }
+}
+
+
+
+// the deepest nesting of arrays, maps and cbor tags that binary input may have, so that decoding cannot overflow the
+// stack.
+const MAXIMUM_DEPTH: usize = 512;
+
+// reads binary input one value at a time.
+struct Reader<'a> {
+    data:   &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.offset).cloned()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], JsonError> {
+        let end  = self.offset.checked_add(length).ok_or(JsonErrorKind::Binary)?;
+        let data = self.data.get(self.offset..end).ok_or(JsonErrorKind::Binary)?;
+
+        self.offset = end;
+        Ok(data)
+    }
+
+    fn byte(&mut self) -> Result<u8, JsonError> {
+        self.take(1).map(|x| x[0])
+    }
+
+    // reads a big endian unsigned integer of `length` bytes.
+    fn unsigned(&mut self, length: usize) -> Result<u64, JsonError> {
+        Ok(self.take(length)?.iter().fold(0, |x, y| x << 8 | *y as u64))
+    }
+
+    fn length(&mut self, length: usize) -> Result<usize, JsonError> {
+        let length = self.unsigned(length)?;
+
+        // a length can never be larger than the input that remains.
+        match length as usize <= self.data.len() - self.offset {
+            true  => Ok(length as usize),
+            false => Err(JsonErrorKind::Binary.into()),
+        }
+    }
+}
+
+// converts the single binary value in `data` into a json builder, with `value` decoding one value.
+fn from_binary(
+    data:  &[u8],
+    value: fn(&mut Reader, &mut Vec<u8>, usize) -> Result<(), JsonError>) -> Result<JsonBuilder, JsonError>
+{
+    let mut reader = Reader { data, offset: 0 };
+    let mut json   = Vec::with_capacity(data.len() * 2);
+
+    value(&mut reader, &mut json, 0)?;
+
+    if reader.offset != data.len() {
+        return Err(JsonErrorKind::Binary.into());
+    }
+
+    let mut builder = JsonBuilder::new();
+
+    builder.update(json)?;
+    Ok(builder)
+}
+
+// decodes a map key with `value`, converting it into a json string if it is not one already.
+fn binary_key(
+    reader: &mut Reader,
+    output: &mut Vec<u8>,
+    depth:  usize,
+    value:  fn(&mut Reader, &mut Vec<u8>, usize) -> Result<(), JsonError>) -> Result<(), JsonError>
+{
+    let mut key = vec![];
+
+    value(reader, &mut key, depth)?;
+
+    match key.first() {
+        Some(b'"') => output.extend_from_slice(&key),
+        _          => write_text(output, &key)?,
+    }
+
+    Ok(())
+}
+
+fn write_text(output: &mut Vec<u8>, text: &[u8]) -> Result<(), JsonError> {
+    let text = std::str::from_utf8(text).map_err(|_| JsonError::from(JsonErrorKind::Utf8))?;
+
+    build::write_string(text, |x| {
+        output.extend_from_slice(x);
+        Ok(())
+    })
+}
+
+// writes `bytes` as a base64url string without padding, as rfc 8949 recommends for byte strings.
+fn write_bytes(output: &mut Vec<u8>, bytes: &[u8]) {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    output.push(b'"');
+
+    for chunk in bytes.chunks(3) {
+        let x = chunk.iter().enumerate().fold(0u32, |x, (i, y)| x | (*y as u32) << (16 - i * 8));
+
+        for i in 0..=chunk.len() {
+            output.push(ALPHABET[(x >> (18 - i * 6)) as usize & 0x3f]);
+        }
+    }
+
+    output.push(b'"');
+}
+
+fn write_float(output: &mut Vec<u8>, value: f64) {
+    match value.is_finite() {
+        true  => output.extend_from_slice(format!("{:?}", value).as_bytes()),
+        false => output.extend_from_slice(b"null"),
+    }
+}
+
+
+
+#[cfg(feature = "cbor")]
+fn cbor_value(reader: &mut Reader, output: &mut Vec<u8>, depth: usize) -> Result<(), JsonError> {
+    let initial = reader.byte()?;
+    let major   = initial >> 5;
+    let info    = initial & 0x1f;
+
+    if major == 7 {
+        match info {
+            20      => output.extend_from_slice(b"false"),
+            21      => output.extend_from_slice(b"true"),
+            22 | 23 => output.extend_from_slice(b"null"),
+            25      => write_float(output, half(reader.unsigned(2)? as u16)),
+            26      => write_float(output, f32::from_bits(reader.unsigned(4)? as u32) as f64),
+            27      => write_float(output, f64::from_bits(reader.unsigned(8)?)),
+            _       => return Err(JsonErrorKind::Binary.into()),
+        }
+
+        return Ok(());
+    }
+
+    // the argument, or `None` for an indefinite length.
+    let argument = match info {
+        0..=23 => Some(info as u64),
+        24     => Some(reader.unsigned(1)?),
+        25     => Some(reader.unsigned(2)?),
+        26     => Some(reader.unsigned(4)?),
+        27     => Some(reader.unsigned(8)?),
+        31     => None,
+        _      => return Err(JsonErrorKind::Binary.into()),
+    };
+
+    match (major, argument) {
+        (0, Some(x)) => {
+            output.extend_from_slice(x.to_string().as_bytes());
+        },
+        (1, Some(x)) => {
+            output.extend_from_slice((-1 - x as i128).to_string().as_bytes());
+        },
+        (2, _) | (3, _) => {
+            let mut text = vec![];
+
+            match argument {
+                Some(x) => {
+                    text.extend_from_slice(reader.take(x as usize)?);
+                },
+                None => {
+                    // an indefinite length string is a series of definite length strings of the same type.
+                    while reader.peek() != Some(0xff) {
+                        match reader.byte()? {
+                            x if x >> 5 == major && x & 0x1f <= 27 => {
+                                let length = match x & 0x1f {
+                                    x @ 0..=23 => x as usize,
+                                    24         => reader.length(1)?,
+                                    25         => reader.length(2)?,
+                                    26         => reader.length(4)?,
+                                    _          => reader.length(8)?,
+                                };
+
+                                text.extend_from_slice(reader.take(length)?);
+                            },
+                            _ => {
+                                return Err(JsonErrorKind::Binary.into());
+                            },
+                        }
+                    }
+
+                    reader.byte()?;
+                },
+            }
+
+            match major {
+                2 => write_bytes(output, &text),
+                _ => write_text(output, &text)?,
+            }
+        },
+        (4, _) | (5, _) => {
+            if depth == MAXIMUM_DEPTH {
+                return Err(JsonErrorKind::Exceeded.into());
+            }
+
+            let object = major == 5;
+            let mut i  = 0;
+
+            output.push(if object { b'{' } else { b'[' });
+
+            loop {
+                let end = match argument {
+                    Some(x) => i == x,
+                    None    => reader.peek() == Some(0xff),
+                };
+
+                if end {
+                    break;
+                }
+
+                if i > 0 {
+                    output.push(b',');
+                }
+
+                if object {
+                    binary_key(reader, output, depth + 1, cbor_value)?;
+                    output.push(b':');
+                }
+
+                cbor_value(reader, output, depth + 1)?;
+                i += 1;
+            }
+
+            if argument.is_none() {
+                reader.byte()?;
+            }
+
+            output.push(if object { b'}' } else { b']' });
+        },
+        (6, Some(_)) => {
+            // a tag wraps the value after it, so a chain of them nests as deeply as a chain of arrays.
+            if depth == MAXIMUM_DEPTH {
+                return Err(JsonErrorKind::Exceeded.into());
+            }
+
+            cbor_value(reader, output, depth + 1)?;
+        },
+        _ => {
+            return Err(JsonErrorKind::Binary.into());
+        },
+    }
+
+    Ok(())
+}
+
+// converts an ieee 754 half precision float.
+#[cfg(feature = "cbor")]
+fn half(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+
+    let value = match exponent {
+        0  => mantissa * 2f64.powi(-24),
+        31 => if mantissa == 0.0 { std::f64::INFINITY } else { std::f64::NAN },
+        _  => (mantissa + 1024.0) * 2f64.powi(exponent as i32 - 25),
+    };
+
+    match bits & 0x8000 {
+        0 => value,
+        _ => -value,
+    }
+}
+
+
+
+#[cfg(feature = "msgpack")]
+fn msgpack_value(reader: &mut Reader, output: &mut Vec<u8>, depth: usize) -> Result<(), JsonError> {
+    let initial = reader.byte()?;
+
+    // the kind of container and its length, if this is one.
+    let container = match initial {
+        0x00..=0x7f => {
+            output.extend_from_slice(initial.to_string().as_bytes());
+            None
+        },
+        0xe0..=0xff => {
+            output.extend_from_slice((initial as i8).to_string().as_bytes());
+            None
+        },
+        0xcc..=0xcf => {
+            let x = reader.unsigned(1 << (initial - 0xcc))?;
+
+            output.extend_from_slice(x.to_string().as_bytes());
+            None
+        },
+        0xd0..=0xd3 => {
+            let length = 1 << (initial - 0xd0);
+            let shift  = 64 - length * 8;
+            let x      = (reader.unsigned(length)? << shift) as i64 >> shift;
+
+            output.extend_from_slice(x.to_string().as_bytes());
+            None
+        },
+        0xc0 => { output.extend_from_slice(b"null");  None },
+        0xc2 => { output.extend_from_slice(b"false"); None },
+        0xc3 => { output.extend_from_slice(b"true");  None },
+        0xca => { write_float(output, f32::from_bits(reader.unsigned(4)? as u32) as f64); None },
+        0xcb => { write_float(output, f64::from_bits(reader.unsigned(8)?)); None },
+        0xa0..=0xbf | 0xd9..=0xdb | 0xc4..=0xc6 => {
+            let length = match initial {
+                0xa0..=0xbf => (initial & 0x1f) as usize,
+                0xd9 | 0xc4 => reader.length(1)?,
+                0xda | 0xc5 => reader.length(2)?,
+                _           => reader.length(4)?,
+            };
+
+            let data = reader.take(length)?;
+
+            match initial {
+                0xc4..=0xc6 => write_bytes(output, data),
+                _           => write_text(output, data)?,
+            }
+
+            None
+        },
+        0x90..=0x9f => Some((false, (initial & 0x0f) as usize)),
+        0x80..=0x8f => Some((true, (initial & 0x0f) as usize)),
+        0xdc        => Some((false, reader.length(2)?)),
+        0xdd        => Some((false, reader.length(4)?)),
+        0xde        => Some((true, reader.length(2)?)),
+        0xdf        => Some((true, reader.length(4)?)),
+        _           => return Err(JsonErrorKind::Binary.into()),
+    };
+
+    if let Some((object, length)) = container {
+        if depth == MAXIMUM_DEPTH {
+            return Err(JsonErrorKind::Exceeded.into());
+        }
+
+        output.push(if object { b'{' } else { b'[' });
+
+        for i in 0..length {
+            if i > 0 {
+                output.push(b',');
+            }
+
+            if object {
+                binary_key(reader, output, depth + 1, msgpack_value)?;
+                output.push(b':');
+            }
+
+            msgpack_value(reader, output, depth + 1)?;
+        }
+
+        output.push(if object { b'}' } else { b']' });
+    }
+
+    Ok(())
+}
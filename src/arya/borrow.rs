@@ -0,0 +1,98 @@
+use {
+    std::mem,
+    std::ops::Deref,
+    std::ops::DerefMut,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+};
+
+
+
+/// a [`JsonBuilder`](./struct.JsonBuilder.html) that writes into a borrowed buffer, for embedders that keep their
+/// buffers in place - in a struct or a pool that cannot hand them out by value.
+///
+/// # remarks
+///
+/// this dereferences to the builder it wraps, so every method of `JsonBuilder` that does not consume it can be called
+/// on it. `buffer` is cleared, and its allocation is moved into the builder until it is given back: by `completed()`,
+/// which leaves the completed json object in it, or when this builder is dropped, which leaves the json object as it
+/// was built so far, like `JsonBuilder::into_buffer()`. if completing fails, `buffer` is left empty.
+///
+/// the buffer is still a `Vec<u8>` from the global allocator, and it grows there if the json object outgrows it.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonBorrowedBuilder;
+/// #
+/// # fn main() {
+/// #
+/// let mut buffer = Vec::with_capacity(1024);
+///
+/// {
+///     let mut builder = JsonBorrowedBuilder::new(&mut buffer);
+///
+///     builder.update(r#"{ "a": [1, 2"#).unwrap();
+///     builder.completed().unwrap();
+/// }
+///
+/// assert_eq!(buffer, br#"{ "a": [1, 2]}"#.to_vec());
+/// assert!(buffer.capacity() >= 1024);
+/// # }
+/// ```
+pub struct JsonBorrowedBuilder<'a> {
+    // always `Some` until the buffer is given back.
+    builder: Option<JsonBuilder>,
+    buffer:  &'a mut Vec<u8>,
+}
+
+impl<'a> JsonBorrowedBuilder<'a> {
+    /// creates a builder that writes into `buffer`.
+    pub fn new(buffer: &'a mut Vec<u8>) -> JsonBorrowedBuilder<'a> {
+        JsonBorrowedBuilder::with_options(JsonBuilderOptions::default(), buffer)
+    }
+
+    /// creates a builder with `options` that writes into `buffer`.
+    pub fn with_options(options: JsonBuilderOptions, buffer: &'a mut Vec<u8>) -> JsonBorrowedBuilder<'a> {
+        let mut builder = JsonBuilder::with_options(options);
+
+        builder.set_buffer(mem::take(buffer));
+
+        JsonBorrowedBuilder {
+            builder: Some(builder),
+            buffer,
+        }
+    }
+
+    /// completes the json object, as `JsonBuilder::completed_bytes()` does, and writes it into the borrowed buffer.
+    pub fn completed(mut self) -> Result<(), JsonError> {
+        let builder = self.builder.take().expect("the builder is only taken once");
+
+        *self.buffer = builder.completed_bytes()?;
+        Ok(())
+    }
+}
+
+impl<'a> Deref for JsonBorrowedBuilder<'a> {
+    type Target = JsonBuilder;
+
+    fn deref(&self) -> &JsonBuilder {
+        self.builder.as_ref().expect("the builder is only taken once")
+    }
+}
+
+impl<'a> DerefMut for JsonBorrowedBuilder<'a> {
+    fn deref_mut(&mut self) -> &mut JsonBuilder {
+        self.builder.as_mut().expect("the builder is only taken once")
+    }
+}
+
+impl<'a> Drop for JsonBorrowedBuilder<'a> {
+    fn drop(&mut self) {
+        if let Some(builder) = self.builder.take() {
+            *self.buffer = builder.into_buffer();
+        }
+    }
+}
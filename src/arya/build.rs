@@ -1,18 +1,159 @@
 use {
     hina,
 
+    std::borrow::Cow,
+    std::io,
+    std::io::Read,
+    std::io::Write,
+    std::mem,
+    std::ops::Range,
+    std::rc::Rc,
+    std::sync::Arc,
+    std::sync::Mutex,
+
+    arya,
+    arya::JsonBigNumbers,
+    arya::JsonBookkeeping,
+    arya::JsonCancel,
+    arya::JsonCandidate,
+    arya::JsonClosers,
+    arya::JsonCoercion,
+    arya::JsonDefault,
+    arya::JsonElided,
     arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonForbiddenAction,
+    arya::JsonForbiddenKeys,
+    arya::JsonIncomplete,
+    arya::JsonLenient,
+    arya::JsonMemoryUsage,
+    arya::JsonNonFinite,
+    arya::JsonOptionsBuilder,
+    arya::JsonRepair,
+    arya::JsonRepairKind,
+    arya::JsonRepairReport,
+    arya::JsonShape,
     arya::JsonStatus,
+    arya::JsonStrategy,
+    arya::JsonStrings,
+    arya::JsonTrailing,
     arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::JsonWhitespace,
+    arya::coerce::Coercer,
+    arya::defaults,
+    arya::events,
+    arya::forbidden,
+    arya::patch,
+    arya::reorder,
+    arya::shape,
+    arya::table::Token,
+    arya::truncate,
+    arya::verify::Mark,
+};
+
+#[cfg(feature = "metrics")]
+use {
+    arya::metrics,
+};
+
+#[cfg(feature = "unicode")]
+use {
+    arya::unicode,
 };
 
 
 
 /// expanded options for constructing a [`JsonBuilder`](./struct.JsonBuilder.html) instance.
+///
+/// # remarks
+///
+/// options can only be created from `default()`, a preset, or a
+/// [`JsonOptionsBuilder`](./struct.JsonOptionsBuilder.html), which checks them. fields that are set directly are not
+/// checked until [`validate()`](#method.validate) is called.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub struct JsonBuilderOptions {
-    maximum_depth:    usize,
-    initial_capacity: usize,
+    /// the maximum number of nested objects and arrays.
+    pub maximum_depth:    usize,
+
+    /// the number of bytes to reserve for the json object up front.
+    pub initial_capacity: usize,
+
+    /// how non-standard whitespace between tokens is handled.
+    pub whitespace:       JsonWhitespace,
+
+    /// the maximum length of a string value. see
+    /// [`JsonVerifierOptions`](./struct.JsonVerifierOptions.html#structfield.maximum_string_length).
+    pub maximum_string_length: usize,
+
+    /// the maximum length of an object key. see
+    /// [`JsonVerifierOptions`](./struct.JsonVerifierOptions.html#structfield.maximum_key_length).
+    pub maximum_key_length:    usize,
+
+    /// the maximum length of a number. see
+    /// [`JsonVerifierOptions`](./struct.JsonVerifierOptions.html#structfield.maximum_number_length).
+    pub maximum_number_length: usize,
+
+    /// how an incomplete key is completed.
+    pub incomplete_keys:       JsonIncomplete,
+
+    /// how an incomplete string value is completed.
+    pub incomplete_strings:    JsonIncomplete,
+
+    /// what replaces an incomplete value of an object member that completion drops.
+    pub elided_members:        JsonElided,
+
+    /// what replaces an incomplete array element that completion drops.
+    pub elided_elements:       JsonElided,
+
+    /// the repairs that may be made to invalid input.
+    pub lenient:               JsonLenient,
+
+    /// the rules for converting string values into other types of values, such as `"42"` into `42`.
+    pub coercions:             Vec<JsonCoercion>,
+
+    /// how numbers that lose precision when parsed as an `i64` or `f64` are handled.
+    pub big_numbers:           JsonBigNumbers,
+
+    /// the maximum number of bytes that completing a json object may insert - closing tokens, replacements for
+    /// incomplete values, and the members added by `defaults` or a template. completing it fails with
+    /// `JsonErrorKind::CompletionTooLarge` if more are needed.
+    pub maximum_inserted:      usize,
+
+    /// the maximum number of bytes that completing an incomplete json object may drop from its end, such as an
+    /// incomplete value. completing it fails with `JsonErrorKind::CompletionTooLarge` if more would be dropped.
+    pub maximum_truncated:     usize,
+
+    /// where the closing tokens appended to complete a json object are placed.
+    pub closers:               JsonClosers,
+
+    /// the members that are added to a completed json object if they are missing.
+    pub defaults:              Vec<JsonDefault>,
+
+    /// the expected shape of the json object, which completion uses to decide how to complete an incomplete value.
+    pub shape:                 Option<JsonShape>,
+
+    /// how bytes after a complete json object are handled.
+    pub trailing:              JsonTrailing,
+
+    /// the object keys that are reported or stripped, such as `__proto__`.
+    pub forbidden_keys:        Option<JsonForbiddenKeys>,
+
+    /// keep the json object byte for byte: `completed_bytes()`, `completed_string()`, `completed_snapshot()` and
+    /// `completed_cow()` fail with `JsonErrorKind::Modified` instead of returning a json object that differs from the
+    /// input. see [`JsonBuilder::was_modified()`](./struct.JsonBuilder.html#method.was_modified).
+    pub round_trip:            bool,
+
+    /// rewrite the keys of a completed json object in unicode normalization form c (nfc), so that keys that only
+    /// differ in how their characters are composed - such as `"é"` and `"e\u0301"` - become the same key. this applies
+    /// to `completed_bytes()`, `completed_string()`, `completed_snapshot()`, `completed_cow()` and
+    /// `complete_candidates()`, and to what `completed_bytes()` writes to a sink. keys are normalized before members
+    /// with forbidden keys are stripped, so a forbidden key is found however its characters are composed.
+    ///
+    /// use [`lint()`](./fn.lint.html) to find keys that only look alike. requires the `unicode` feature.
+    #[cfg(feature = "unicode")]
+    pub normalize_keys:        bool,
 }
 
 impl Default for JsonBuilderOptions {
@@ -20,6 +161,152 @@ impl Default for JsonBuilderOptions {
         JsonBuilderOptions {
             maximum_depth:    std::usize::MAX,
             initial_capacity: 512,
+            whitespace:       JsonWhitespace::Permissive,
+
+            maximum_string_length: std::usize::MAX,
+            maximum_key_length:    std::usize::MAX,
+            maximum_number_length: std::usize::MAX,
+
+            incomplete_keys:       JsonIncomplete::Drop,
+            incomplete_strings:    JsonIncomplete::Drop,
+            elided_members:        JsonElided::Drop,
+            elided_elements:       JsonElided::Drop,
+
+            lenient:               JsonLenient::default(),
+            coercions:             vec![],
+            big_numbers:           JsonBigNumbers::Ignore,
+
+            maximum_inserted:      std::usize::MAX,
+            maximum_truncated:     std::usize::MAX,
+            closers:               JsonClosers::Inline,
+            defaults:              vec![],
+            shape:                 None,
+            trailing:              JsonTrailing::Reject,
+            forbidden_keys:        None,
+            round_trip:            false,
+
+            #[cfg(feature = "unicode")]
+            normalize_keys:        false,
+        }
+    }
+}
+
+impl JsonBuilderOptions {
+    /// returns a [`JsonOptionsBuilder`](./struct.JsonOptionsBuilder.html) that starts from the default options.
+    pub fn builder() -> JsonOptionsBuilder {
+        JsonOptionsBuilder::new()
+    }
+
+    /// options for input that must already be standard rfc 8259 json: only standard whitespace is accepted, no repairs
+    /// are made, and objects and arrays may be nested at most 128 deep.
+    pub fn strict() -> JsonBuilderOptions {
+        JsonBuilderOptions {
+            maximum_depth: 128,
+            whitespace:    JsonWhitespace::Strict,
+            ..Default::default()
+        }
+    }
+
+    /// options for hand-written or machine-mangled json: every repair is made, `NaN` and `Infinity` become `null`,
+    /// unicode whitespace between tokens is normalized, and control bytes between tokens are skipped.
+    pub fn lenient() -> JsonBuilderOptions {
+        JsonBuilderOptions {
+            whitespace: JsonWhitespace::Normalize,
+            lenient:    JsonLenient {
+                brackets:    true,
+                commas:      true,
+                colons:      true,
+                literals:    true,
+                non_finite:  JsonNonFinite::Null,
+                punctuation: true,
+                control:     true,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// options for json generated by a language model, which is often cut off part way through: like `lenient()`, but
+    /// an incomplete string value is kept rather than dropped, and objects and arrays may be nested at most 128 deep.
+    pub fn llm() -> JsonBuilderOptions {
+        JsonBuilderOptions {
+            maximum_depth:      128,
+            incomplete_strings: JsonIncomplete::Keep,
+            ..JsonBuilderOptions::lenient()
+        }
+    }
+
+    /// options for json with a fixed schema whose tail is often cut off, such as telemetry: `template` is a complete
+    /// example document, and completion aligns the json object to it.
+    ///
+    /// # remarks
+    ///
+    /// each member of `template` becomes a default - so that members missing from a completed object are filled in
+    /// with the template's values, in the template's order - and `template` also becomes the expected
+    /// [`shape`](#structfield.shape), where an array has the shape of its first element. see
+    /// [`JsonDefault`](./struct.JsonDefault.html) and [`JsonShape`](./enum.JsonShape.html).
+    ///
+    /// fails with `JsonErrorKind::Invalid` if `template` is not a complete json object.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonBuilderOptions};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let template = r#"{ "host": "", "cpu": { "user": 0.0, "system": 0.0 }, "ok": true }"#;
+    /// let options  = JsonBuilderOptions::template(template).unwrap();
+    ///
+    /// let mut builder = JsonBuilder::with_options(options);
+    ///
+    /// builder.update(r#"{ "host": "db-1", "cpu": { "user": 0.25, "sys"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     builder.completed_string().unwrap(),
+    ///     r#"{ "host": "db-1", "cpu": { "user": 0.25,"system":0.0},"ok":true}"#);
+    /// # }
+    /// ```
+    pub fn template(template: impl JsonSource) -> Result<JsonBuilderOptions, JsonError> {
+        let template = template.stream();
+        let values   = patch::values(template)?;
+
+        let mut verifier = JsonVerifier::new();
+
+        for &character in template {
+            verifier.update(character)?;
+        }
+
+        if verifier.status() != JsonStatus::Valid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        Ok(JsonBuilderOptions {
+            defaults: defaults::from_template(template, &values)?,
+            shape:    Some(shape::from_template(&values)),
+            ..Default::default()
+        })
+    }
+}
+
+
+
+// a callback for completed json objects, and an output sink.
+type Callback = Box<dyn FnMut(&[u8]) + Send>;
+type Sink     = Box<dyn Write + Send>;
+
+// a value that is only ever used through `&mut`, so that a builder holding a callback or sink that is `Send` but not
+// `Sync` is still `Sync`. the lock is never taken - `get()` borrows straight through it.
+struct Exclusive<T>(Mutex<T>);
+
+impl<T> Exclusive<T> {
+    fn new(value: T) -> Exclusive<T> {
+        Exclusive(Mutex::new(value))
+    }
+
+    fn get(&mut self) -> &mut T {
+        match self.0.get_mut() {
+            Ok(x)  => x,
+            Err(e) => e.into_inner(),
         }
     }
 }
@@ -34,6 +321,13 @@ impl Default for JsonBuilderOptions {
 /// underlying json object invalid will cause the [`JsonBuilder`](./struct.JsonBuilder.html) to remain invalid, even if
 /// more characters are added to it later.
 ///
+/// repairs and completions only ever insert, replace or drop tokens - they never move content. every byte of the
+/// output that came from the input appears in the same order it was read in, so object members keep the order they
+/// were written in. sorting keys is opt-in, with `completed_sorted_keys()`.
+///
+/// a builder is `Send` and `Sync`. its sink and `on_complete()` callback only need to be `Send`, since they are only
+/// ever used through `&mut self`.
+///
 /// # examples
 /// ```
 /// # use arya::JsonBuilder;
@@ -66,6 +360,66 @@ pub struct JsonBuilder {
     data:     Vec<u8>,
     invalid:  bool,
     verifier: JsonVerifier,
+
+    // the start of a non-standard whitespace sequence that will be normalized once complete.
+    normalize: bool,
+    pending:   Vec<u8>,
+
+    // the output sink, and the number of bytes already written to it.
+    sink:      Option<Exclusive<Sink>>,
+    written:   usize,
+
+    // how incomplete keys and string values are completed, and what replaces incomplete member values and elements.
+    keys:      JsonIncomplete,
+    strings:   JsonIncomplete,
+    members:   Option<Vec<u8>>,
+    elements:  Option<Vec<u8>>,
+
+    // the repairs that may be made, and the repairs that have been made - along with the kinds of those made during
+    // the current update, which are reported to the metrics recorder once it ends.
+    lenient:   JsonLenient,
+    repairs:   Vec<JsonRepair>,
+    #[cfg(feature = "metrics")]
+    tally:     Vec<JsonRepairKind>,
+
+    // the start of a literal that will be repaired once complete, and the state before the last minus sign in case it
+    // turns out to begin `-Infinity`.
+    literal:   Vec<u8>,
+    minus:     Option<Mark>,
+
+    // the start of unicode punctuation that will be repaired once complete, and the quote that closes the current
+    // string, if it was opened by a curly quote.
+    punctuation: Vec<u8>,
+    smart:       Option<&'static [u8]>,
+
+    // rewrites string values that are converted into other types of values, if any rules were given.
+    coercer:     Option<Box<Coercer>>,
+
+    // whether the input was changed without recording a repair - by normalizing whitespace or ignoring trailing bytes -
+    // and whether completing this json object fails if it was changed at all.
+    modified:    bool,
+    round_trip:  bool,
+
+    // the maximum number of bytes that completion may insert and truncate.
+    inserted:    usize,
+    truncated:   usize,
+
+    // where the closing tokens appended by completion are placed, the members added to the completed object, the
+    // shape it is expected to have, the keys that are reported or stripped, and whether keys are normalized - which
+    // only the `unicode` feature can enable.
+    closers:     JsonClosers,
+    defaults:    Vec<JsonDefault>,
+    shape:       Option<JsonShape>,
+    forbidden:   Option<JsonForbiddenKeys>,
+    nfc:         bool,
+
+    // how bytes after a complete json object are handled, and the deadline and cancellation token for updates.
+    trailing:    JsonTrailing,
+    cancel:      Option<JsonCancel>,
+
+    // the callback for completed json objects, and whether it has been called for the current one.
+    complete:    Option<Exclusive<Callback>>,
+    notified:    bool,
 }
 
 impl JsonBuilder {
@@ -73,7 +427,47 @@ impl JsonBuilder {
         JsonBuilder {
             data:     vec![],
             invalid:  false,
-            verifier: JsonVerifier::new()
+            verifier: JsonVerifier::new(),
+
+            normalize: false,
+            pending:   vec![],
+
+            sink:      None,
+            written:   0,
+
+            keys:      JsonIncomplete::Drop,
+            strings:   JsonIncomplete::Drop,
+            members:   None,
+            elements:  None,
+
+            lenient:   JsonLenient::default(),
+            repairs:   vec![],
+            #[cfg(feature = "metrics")]
+            tally:     vec![],
+
+            literal:   vec![],
+            minus:     None,
+
+            punctuation: vec![],
+            smart:       None,
+
+            coercer:     None,
+
+            modified:    false,
+            round_trip:  false,
+
+            inserted:    std::usize::MAX,
+            truncated:   std::usize::MAX,
+            closers:     JsonClosers::Inline,
+            defaults:    vec![],
+            shape:       None,
+            forbidden:   None,
+            nfc:         false,
+            trailing:    JsonTrailing::Reject,
+            cancel:      None,
+
+            complete:    None,
+            notified:    false,
         }
     }
 
@@ -85,18 +479,369 @@ impl JsonBuilder {
         JsonBuilder::with_options(JsonBuilderOptions { initial_capacity, ..Default::default() })
     }
 
+    /// creates a builder with [`JsonBuilderOptions::strict()`](./struct.JsonBuilderOptions.html#method.strict).
+    pub fn strict() -> JsonBuilder {
+        JsonBuilder::with_options(JsonBuilderOptions::strict())
+    }
+
+    /// creates a builder with [`JsonBuilderOptions::lenient()`](./struct.JsonBuilderOptions.html#method.lenient).
+    pub fn lenient() -> JsonBuilder {
+        JsonBuilder::with_options(JsonBuilderOptions::lenient())
+    }
+
+    /// creates a builder with [`JsonBuilderOptions::llm()`](./struct.JsonBuilderOptions.html#method.llm).
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::llm();
+    ///
+    /// builder.update("{“name”：“annie”, \"tags\": [\"a\" \"b\"], \"ok\": True, \"bio\": \"lorem ip").unwrap();
+    ///
+    /// assert_eq!(
+    ///     builder.completed_string().unwrap(),
+    ///     r#"{"name":"annie", "tags": ["a", "b"], "ok": true, "bio": "lorem ip"}"#);
+    /// # }
+    /// ```
+    pub fn llm() -> JsonBuilder {
+        JsonBuilder::with_options(JsonBuilderOptions::llm())
+    }
+
     pub fn with_options(options: JsonBuilderOptions) -> JsonBuilder {
+        // normalization happens here, so the verifier only ever sees standard whitespace.
+        let normalize = options.whitespace == JsonWhitespace::Normalize;
+        let verifier  = JsonVerifierOptions {
+            maximum_depth: options.maximum_depth,
+            whitespace:    if normalize { JsonWhitespace::Strict } else { options.whitespace },
+
+            maximum_string_length: options.maximum_string_length,
+            maximum_key_length:    options.maximum_key_length,
+            maximum_number_length: options.maximum_number_length,
+
+            // bytes after a complete json object are handled here, since they may begin a new builder.
+            trailing:              JsonTrailing::Reject,
+            strings:               JsonStrings::Eager,
+            bookkeeping:           JsonBookkeeping::Full,
+        };
+
+        #[cfg(feature = "unicode")]
+        let nfc = options.normalize_keys;
+        #[cfg(not(feature = "unicode"))]
+        let nfc = false;
+
         JsonBuilder {
             data:     Vec::with_capacity(options.initial_capacity),
             invalid:  false,
-            verifier: JsonVerifier::with_maximum_depth(options.maximum_depth),
+            verifier: JsonVerifier::with_options(verifier),
+
+            normalize,
+            pending:   vec![],
+
+            sink:      None,
+            written:   0,
+
+            keys:      options.incomplete_keys,
+            strings:   options.incomplete_strings,
+            members:   elided(&options.elided_members),
+            elements:  elided(&options.elided_elements),
+
+            lenient:   options.lenient,
+            repairs:   vec![],
+            #[cfg(feature = "metrics")]
+            tally:     vec![],
+
+            literal:   vec![],
+            minus:     None,
+
+            punctuation: vec![],
+            smart:       None,
+
+            coercer:     Coercer::new(options.coercions, options.big_numbers, options.shape.is_some()).map(Box::new),
+
+            modified:    false,
+            round_trip:  options.round_trip,
+
+            inserted:    options.maximum_inserted,
+            truncated:   options.maximum_truncated,
+            closers:     options.closers,
+            defaults:    options.defaults,
+            shape:       options.shape,
+            forbidden:   options.forbidden_keys,
+            nfc,
+            trailing:    options.trailing,
+            cancel:      None,
+
+            complete:    None,
+            notified:    false,
         }
     }
 
+    /// creates a builder that also writes the json object to `sink` as it is built.
+    ///
+    /// # remarks
+    ///
+    /// a byte is written to `sink` once no completion could drop it - usually at the end of each value. when this
+    /// builder is finished with `bytes()` or `completed_bytes()`, the remaining bytes (and for `completed_bytes()`,
+    /// the closing tokens) are written and `sink` is flushed. `completed_snapshot()` and `completed_truncated()` never
+    /// write to `sink`.
+    ///
+    /// builders that strip members with forbidden keys, normalize keys or add default members may change any part of
+    /// the json object once it is complete, so they write nothing to `sink` until they are finished - and then write
+    /// what it was changed to. see `set_sink()`.
+    ///
+    /// if writing fails, this builder becomes invalid and `JsonErrorKind::Io` is returned.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// # use std::sync::{Arc, Mutex};
+    /// # use std::io::Write;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct Shared(Arc<Mutex<Vec<u8>>>);
+    /// #
+    /// # impl Write for Shared {
+    /// #     fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> { self.0.lock().unwrap().write(bytes) }
+    /// #     fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #
+    /// let sink        = Shared(Arc::new(Mutex::new(vec![])));
+    /// let mut builder = JsonBuilder::with_sink(sink.clone());
+    ///
+    /// builder.update(r#"{ "a": [1, 2], "b": "lorem"#).unwrap();
+    /// assert_eq!(*sink.0.lock().unwrap(), br#"{ "a": [1, 2]"#.to_vec());
+    ///
+    /// builder.completed_bytes().unwrap();
+    /// assert_eq!(*sink.0.lock().unwrap(), br#"{ "a": [1, 2]}"#.to_vec());
+    /// # }
+    /// ```
+    pub fn with_sink(sink: impl Write + Send + 'static) -> JsonBuilder {
+        let mut builder = JsonBuilder::new();
+
+        builder.set_sink(sink);
+        builder
+    }
+
+    /// creates a builder that writes into `buffer`, reusing its allocation instead of allocating a new one.
+    ///
+    /// # remarks
+    ///
+    /// `buffer` is cleared first. the methods that consume this builder, such as `bytes()` and `completed_bytes()`,
+    /// return it, so embedders that pool their buffers can hand the same allocation from one builder to the next.
+    ///
+    /// the buffer is always a `Vec<u8>` from the global allocator, and it grows there if the json object outgrows it.
+    /// builders cannot use a custom allocator. a buffer that cannot be handed over by value, such as one kept in place
+    /// in a struct, can be lent to a [`JsonBorrowedBuilder`](./struct.JsonBorrowedBuilder.html) instead.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let buffer      = Vec::with_capacity(1024);
+    /// let mut builder = JsonBuilder::with_buffer(buffer);
+    ///
+    /// builder.update(r#"{ "a": [1, 2"#).unwrap();
+    ///
+    /// let buffer = builder.completed_bytes().unwrap();
+    ///
+    /// assert_eq!(buffer, br#"{ "a": [1, 2]}"#.to_vec());
+    /// assert!(buffer.capacity() >= 1024);
+    /// # }
+    /// ```
+    pub fn with_buffer(buffer: Vec<u8>) -> JsonBuilder {
+        let mut builder = JsonBuilder::new();
+
+        builder.set_buffer(buffer);
+        builder
+    }
+
+    // clears `buffer` and writes into it from now on, in place of the buffer this builder has.
+    crate fn set_buffer(&mut self, mut buffer: Vec<u8>) {
+        buffer.clear();
+        self.data = buffer;
+    }
+
+    /// consumes this builder and returns its buffer as is, without completing it and even if it is invalid, so that its
+    /// allocation can be reused.
+    pub fn into_buffer(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// calls `callback` with the json object whenever it becomes complete, so that it does not have to be polled with
+    /// `status()` after every update.
+    ///
+    /// # remarks
+    ///
+    /// `callback` is called once per json object, as soon as the update that completes it returns, without any
+    /// surrounding whitespace - trailing whitespace does not call it again. it is kept across `reset()`, so a builder
+    /// that is reset after each document calls it for every one of them.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// # use std::sync::{Arc, Mutex};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let documents   = Arc::new(Mutex::new(vec![]));
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// let shared = documents.clone();
+    ///
+    /// builder.on_complete(move |x| shared.lock().unwrap().push(x.to_vec()));
+    ///
+    /// builder.update(r#"{ "a": [1, "#).unwrap();
+    /// assert!(documents.lock().unwrap().is_empty());
+    ///
+    /// builder.update(r#"2] } "#).unwrap();
+    /// builder.reset();
+    /// builder.update(r#"[3]"#).unwrap();
+    ///
+    /// assert_eq!(*documents.lock().unwrap(), vec![br#"{ "a": [1, 2] }"#.to_vec(), b"[3]".to_vec()]);
+    /// # }
+    /// ```
+    pub fn on_complete(&mut self, callback: impl FnMut(&[u8]) + Send + 'static) {
+        self.complete = Some(Exclusive::new(Box::new(callback)));
+    }
+
+    /// sets the deadline and cancellation token that are checked while updating this json object, which fail an
+    /// update with `JsonErrorKind::Cancelled`. see [`JsonCancel`](./struct.JsonCancel.html).
+    ///
+    /// # remarks
+    ///
+    /// like other errors, a cancelled `update()` makes this builder invalid. `update_partial()` does not, and returns
+    /// how much of its input was applied, so the update can be resumed later. the deadline and token are kept across
+    /// `reset()`.
+    pub fn set_cancel(&mut self, cancel: JsonCancel) {
+        self.cancel = Some(cancel);
+    }
+
+    /// sets the output that this json object is written to as it is built, like
+    /// [`with_sink()`](#method.with_sink) - for builders created with other options.
+    ///
+    /// # remarks
+    ///
+    /// the whole json object is written to `sink`, including what was built before it was set.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonBuilderOptions, JsonForbiddenAction, JsonForbiddenKeys};
+    /// # use std::sync::{Arc, Mutex};
+    /// # use std::io::Write;
+    /// #
+    /// # #[derive(Clone)]
+    /// # struct Shared(Arc<Mutex<Vec<u8>>>);
+    /// #
+    /// # impl Write for Shared {
+    /// #     fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> { self.0.lock().unwrap().write(bytes) }
+    /// #     fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut options = JsonBuilderOptions::default();
+    ///
+    /// options.forbidden_keys = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Strip));
+    ///
+    /// let sink        = Shared(Arc::new(Mutex::new(vec![])));
+    /// let mut builder = JsonBuilder::with_options(options);
+    ///
+    /// builder.set_sink(sink.clone());
+    /// builder.update(r#"{ "a": 1, "__proto__": { "b": 2 }, "c": [3"#).unwrap();
+    ///
+    /// // members may still be stripped, so nothing is written until the builder is finished.
+    /// assert!(sink.0.lock().unwrap().is_empty());
+    ///
+    /// builder.completed_bytes().unwrap();
+    /// assert_eq!(*sink.0.lock().unwrap(), br#"{ "a": 1, "c": [3]}"#.to_vec());
+    /// # }
+    /// ```
+    pub fn set_sink(&mut self, sink: impl Write + Send + 'static) {
+        self.sink    = Some(Exclusive::new(Box::new(sink)));
+        self.written = 0;
+    }
+
     pub fn len(&self) -> usize {
         self.data.len()
     }
 
+    /// returns the heap memory held by this builder, so that long-lived builders - such as one per connection - can be
+    /// monitored.
+    ///
+    /// # remarks
+    ///
+    /// this counts the json object, everything this builder keeps while building it, and its options - such as its
+    /// coercion rules, shape, defaults and forbidden keys. the memory held by its sink and completion callback is not
+    /// counted, since they may be of any type.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::with_capacity(64);
+    ///
+    /// builder.update(format!("[{}]", "1, ".repeat(10_000) + "2")).unwrap();
+    /// builder.reset();
+    ///
+    /// assert!(builder.memory_usage().buffer >= 30_000);
+    ///
+    /// builder.shrink();
+    ///
+    /// assert_eq!(builder.memory_usage().buffer, 0);
+    /// # }
+    /// ```
+    pub fn memory_usage(&self) -> JsonMemoryUsage {
+        let verifier = self.verifier.memory_usage();
+
+        let elided    = |x: &Option<Vec<u8>>| x.as_ref().map_or(0, Vec::capacity);
+        let coercer   = self.coercer.as_ref().map_or(0, |x| mem::size_of::<Coercer>() + x.memory_usage());
+        let shape     = self.shape.as_ref().map_or(0, JsonShape::memory_usage);
+        let defaults  = self.defaults.iter().map(|x| x.pointer.capacity() + x.value.capacity()).sum::<usize>();
+        let forbidden = self.forbidden.as_ref().map_or(0, |x| {
+            x.keys.capacity() * mem::size_of::<String>() + x.keys.iter().map(String::capacity).sum::<usize>()
+        });
+
+        let other = self.pending.capacity()
+            + self.literal.capacity()
+            + self.punctuation.capacity()
+            + self.repairs.capacity() * mem::size_of::<JsonRepair>()
+            + elided(&self.members)
+            + elided(&self.elements)
+            + coercer
+            + shape
+            + self.defaults.capacity() * mem::size_of::<JsonDefault>() + defaults
+            + forbidden
+            + verifier.other;
+
+        JsonMemoryUsage { buffer: self.data.capacity(), stack: verifier.stack, other }
+    }
+
+    /// releases the memory this builder reserved but is not using, so that a long-lived builder does not keep the
+    /// memory of the largest json object it was ever given. this is most useful after `reset()`.
+    pub fn shrink(&mut self) {
+        self.data.shrink_to_fit();
+        self.pending.shrink_to_fit();
+        self.literal.shrink_to_fit();
+        self.punctuation.shrink_to_fit();
+        self.repairs.shrink_to_fit();
+        self.verifier.shrink();
+    }
+
     pub fn status(&self) -> JsonStatus {
         self.verifier.status()
     }
@@ -105,97 +850,2029 @@ impl JsonBuilder {
         self.invalid = false;
 
         self.data.clear();
+        self.pending.clear();
         self.verifier.reset();
+        self.repairs.clear();
+        self.literal.clear();
+        self.punctuation.clear();
+        self.minus    = None;
+        self.smart    = None;
+        self.written  = 0;
+        self.modified = false;
+        self.notified = false;
+
+        if let Some(coercer) = &mut self.coercer {
+            coercer.reset();
+        }
     }
 
     pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!("update", offset = self.data.len()).entered();
+
         if self.invalid {
-            Err(JsonError::Invalid)
+            Err(JsonErrorKind::Invalid.into())
         } else {
-            for character in source.stream() {
-                match self.verifier.update(*character) {
-                    Ok(()) => {
-                        self.data.push(*character);
-                    },
-                    Err(e) => {
-                        self.invalid = true;
-                        return Err(e);
-                    },
-                }
+            let (_length, result) = self.apply_all(source.stream());
+
+            #[cfg(feature = "metrics")]
+            self.record_metrics(_length, result.as_ref().err().map(|x| x.kind()));
+
+            if let Err(e) = result {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(offset = self.data.len(), error = ?e, "builder invalidated");
+
+                self.invalid = true;
+                return Err(e);
             }
 
-            Ok(())
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                processed = source.stream().len(),
+                status    = ?self.verifier.status(),
+                depth     = self.verifier.depth(),
+                repairs   = self.repairs.len(),
+                "updated json");
+
+            self.forward()
+        }
+    }
+
+    /// applies the utf8 encoding of `character` to this json object.
+    pub fn update_char(&mut self, character: char) -> Result<(), JsonError> {
+        let mut buffer = [0; 4];
+
+        self.update(character.encode_utf8(&mut buffer).as_bytes())
+    }
+
+    /// applies `characters` to this json object, for text that has already been decoded.
+    pub fn update_chars(&mut self, characters: impl IntoIterator<Item = char>) -> Result<(), JsonError> {
+        self.update(characters.into_iter().collect::<String>())
+    }
+
+    /// returns every repair made to the input so far, in order.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonBuilderOptions, JsonRepair, JsonRepairKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut options = JsonBuilderOptions::default();
+    ///
+    /// options.lenient.brackets = true;
+    ///
+    /// let mut builder = JsonBuilder::with_options(options);
+    ///
+    /// builder.update(r#"{ "a": [1, 2} ]"#).unwrap();
+    ///
+    /// assert_eq!(builder.repairs(), &[
+    ///     JsonRepair { offset: 12, kind: JsonRepairKind::Bracket { found: b'}', replacement: b']' } },
+    ///     JsonRepair { offset: 14, kind: JsonRepairKind::Bracket { found: b']', replacement: b'}' } },
+    /// ]);
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{ "a": [1, 2] }"#);
+    /// # }
+    /// ```
+    pub fn repairs(&self) -> &[JsonRepair] {
+        &self.repairs
+    }
+
+    /// returns how much this json object was changed so far, and would be changed by completing it, including a
+    /// confidence score that consumers can use to set aside repairs that are too large to trust.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonBuilderOptions};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut options = JsonBuilderOptions::default();
+    ///
+    /// options.lenient.commas = true;
+    ///
+    /// let mut builder = JsonBuilder::with_options(options);
+    ///
+    /// builder.update(r#"{ "a": [1 2], "b": "lor"#).unwrap();
+    ///
+    /// let report = builder.report().unwrap();
+    ///
+    /// assert_eq!((report.length, report.inserted, report.truncated, report.repairs), (24, 1, 11, 1));
+    /// assert!(report.confidence > 0.4 && report.confidence < 0.5);
+    /// # }
+    /// ```
+    pub fn report(&self) -> Result<JsonRepairReport, JsonError> {
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        let (inserted, truncated) = match self.verifier.status() {
+            JsonStatus::Valid    => (0, 0),
+            JsonStatus::Continue => {
+                let (until, tokens) = self.completion()?;
+
+                (tokens.len(), self.data.len() - until)
+            },
+        };
+
+        Ok(JsonRepairReport::new(self.data.len(), inserted, truncated, self.repairs.len()))
+    }
+
+    /// returns `true` if the completed json object differs from the input it was built from, so that callers can skip
+    /// rewriting payloads that have not changed.
+    ///
+    /// # remarks
+    ///
+    /// a json object is modified if any repair was made to it, its whitespace was normalized, bytes other than
+    /// whitespace after it were ignored, it is incomplete or invalid, or completing it strips, normalizes or adds
+    /// members. otherwise, `completed_bytes()` returns the input byte for byte, in this builder's own buffer rather
+    /// than a copy of it.
+    ///
+    /// [`JsonBuilderOptions::round_trip`](./struct.JsonBuilderOptions.html#structfield.round_trip) makes completing a
+    /// modified json object fail instead.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::lenient();
+    ///
+    /// builder.update(r#"{ "a": [1, 2] }"#).unwrap();
+    /// assert!(!builder.was_modified());
+    ///
+    /// builder.reset();
+    /// builder.update(r#"{ "a": [1 2] }"#).unwrap();
+    /// assert!(builder.was_modified());
+    /// # }
+    /// ```
+    pub fn was_modified(&self) -> bool {
+        self.modified_before_amend() || self.amends()
+    }
+
+    /// completes a copy of this json object with each [`JsonStrategy`](./enum.JsonStrategy.html), and returns the
+    /// distinct results ranked from best to worst - so callers can pick one rather than trust a single heuristic.
+    ///
+    /// # remarks
+    ///
+    /// each candidate is scored by how much of the input it preserves, how many bytes it inserts, and how many values
+    /// it closes part way through. see [`JsonCandidate`](./struct.JsonCandidate.html). strategies that produce the
+    /// same json object as a better one are left out, as are strategies that need more changes than this builder
+    /// allows - if every strategy does, `JsonErrorKind::CompletionTooLarge` is returned.
+    ///
+    /// this builder's own options for incomplete keys, strings and values are not used, but its other options - such
+    /// as its closers and defaults - are. like `completed_snapshot()`, this never writes to a sink.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonStrategy};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": [1, 2], "b": "lorem ipsum dolor"#).unwrap();
+    ///
+    /// let candidates = builder.complete_candidates().unwrap();
+    ///
+    /// assert_eq!(candidates[0].strategy, JsonStrategy::Keep);
+    /// assert_eq!(candidates[0].data, br#"{ "a": [1, 2], "b": "lorem ipsum dolor"}"#.to_vec());
+    ///
+    /// assert_eq!(candidates[1].strategy, JsonStrategy::Null);
+    /// assert_eq!(candidates[1].data, br#"{ "a": [1, 2], "b": null}"#.to_vec());
+    ///
+    /// assert_eq!(candidates[2].strategy, JsonStrategy::Drop);
+    /// assert_eq!(candidates[2].data, br#"{ "a": [1, 2]}"#.to_vec());
+    /// # }
+    /// ```
+    pub fn complete_candidates(&self) -> Result<Vec<JsonCandidate>, JsonError> {
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        let strategies = [
+            (JsonStrategy::Drop, JsonIncomplete::Drop, JsonIncomplete::Drop, None),
+            (JsonStrategy::Keep, JsonIncomplete::Keep, JsonIncomplete::Keep, None),
+            (JsonStrategy::Null, JsonIncomplete::Drop, JsonIncomplete::Drop, Some(&b"null"[..])),
+        ];
+
+        let mut candidates: Vec<JsonCandidate> = vec![];
+
+        for &(strategy, keys, strings, replacement) in &strategies {
+            let (until, tokens) = match self.verifier.status() {
+                JsonStatus::Valid    => (self.data.len(), vec![]),
+                JsonStatus::Continue => match self.completion_with(keys, strings, replacement, replacement) {
+                    Ok(x)                                            => x,
+                    Err(e) if e == JsonErrorKind::CompletionTooLarge => continue,
+                    Err(e)                                           => return Err(e),
+                },
+            };
+
+            let length  = self.data.len();
+            let report  = JsonRepairReport::new(length, tokens.len(), length - until, self.repairs.len());
+            let partial = (until == length && self.verifier.in_string()) as usize;
+
+            let mut data = self.data[..until].to_vec();
+
+            data.extend(tokens);
+
+            let data = match self.amend(data, report.inserted) {
+                Ok((x, _))                                       => x,
+                Err(e) if e == JsonErrorKind::CompletionTooLarge => continue,
+                Err(e)                                           => return Err(e),
+            };
+
+            if candidates.iter().all(|x| x.data != data) {
+                let score = (report.confidence - 0.25 * partial as f64).max(0.0);
+
+                candidates.push(JsonCandidate { strategy, data, report, partial, score });
+            }
+        }
+
+        if candidates.is_empty() {
+            return Err(JsonErrorKind::CompletionTooLarge.into());
+        }
+
+        // the sort is stable, so candidates with the same score stay in the order of their strategies.
+        candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(candidates)
+    }
+
+    /// describes how this json object was changed so far, and would be changed by completing it, as an
+    /// [rfc 6902](https://tools.ietf.org/html/rfc6902) json patch - so that audit systems can record how a payload was
+    /// altered in a standard format.
+    ///
+    /// # remarks
+    ///
+    /// the patch applies to the values that were read from the input:
+    ///
+    /// - a value changed by a literal, coercion or big number repair, or a string value that completion closed, is
+    ///   `replace`d with its new value.
+    /// - a value that completion inserted, such as the `null` given to an incomplete key, is `add`ed.
+    /// - a value that the input ended part way through, and that completion dropped, is `remove`d.
+    /// - a member stripped because its key is forbidden is `remove`d, and a member added by a default is `add`ed. a
+    ///   member whose key normalization changed is removed, and added again under its normalized key.
+    ///
+    /// paths are built from the unescaped keys, so `"\u0061"` is the key `a`. lenient repairs do not move any value,
+    /// so the paths are the same in the input that was given to this builder. repairs to the punctuation between values
+    /// - brackets, commas, colons and unicode punctuation - do not change any value, so they are not described. see
+    /// [`repairs()`](#method.repairs) for every repair.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::lenient();
+    ///
+    /// builder.update(r#"{ "a": True, "b": [1 2], "c": "lor"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     builder.repair_patch().unwrap(),
+    ///     r#"[{ "op": "replace", "path": "/a", "value": true }, { "op": "remove", "path": "/c" }]"#);
+    /// # }
+    /// ```
+    pub fn repair_patch(&self) -> Result<String, JsonError> {
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        let (until, tokens) = match self.verifier.status() {
+            JsonStatus::Valid    => (self.data.len(), vec![]),
+            JsonStatus::Continue => self.completion()?,
+        };
+
+        let inserted   = tokens.len();
+        let mut output = self.data[..until].to_vec();
+
+        output.extend(tokens);
+
+        let amended = self.amend(output.clone(), inserted)?.0;
+        let patch   = patch::repair_patch(&self.data, until, &output, &amended, &self.repairs)?;
+
+        String::from_utf8(patch).map_err(|_| JsonError::from(JsonErrorKind::Utf8))
+    }
+
+    /// returns the json pointers of the numbers that lose precision when parsed, in the order they were found, if
+    /// [`JsonBuilderOptions::big_numbers`](./struct.JsonBuilderOptions.html#structfield.big_numbers) looks for them.
+    pub fn big_numbers(&self) -> &[String] {
+        match &self.coercer {
+            Some(coercer) => coercer.big_numbers(),
+            None          => &[],
+        }
+    }
+
+    /// returns the json pointers of the members whose keys are forbidden by
+    /// [`JsonBuilderOptions::forbidden_keys`](./struct.JsonBuilderOptions.html#structfield.forbidden_keys), in the order
+    /// they were found.
+    pub fn forbidden_keys(&self) -> Vec<String> {
+        match &self.forbidden {
+            Some(forbidden) => forbidden::find(&self.data, &forbidden.keys).0,
+            None            => vec![],
+        }
+    }
+
+    /// applies as much of `source` as possible, returning the number of bytes that were accepted.
+    ///
+    /// # remarks
+    ///
+    /// unlike `update()`, a rejected byte does not invalidate this builder. like
+    /// [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), the rejected byte is simply not applied,
+    /// so the caller can retry from exactly that point, or skip ahead and try again.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonErrorKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// assert_eq!(builder.update_partial(r#"{ "a": 1 ] }"#), (9, Err(JsonErrorKind::Invalid.into())));
+    /// assert_eq!(builder.update_partial(" }"), (2, Ok(())));
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{ "a": 1  }"#);
+    /// # }
+    /// ```
+    pub fn update_partial(&mut self, source: impl JsonSource) -> (usize, Result<(), JsonError>) {
+        if self.invalid {
+            return (0, Err(JsonErrorKind::Invalid.into()));
         }
+
+        let (length, result) = self.apply_all(source.stream());
+
+        #[cfg(feature = "metrics")]
+        self.record_metrics(length, None);
+
+        (length, self.forward().and(result))
     }
 
-    pub fn bytes(self) -> Result<Vec<u8>, JsonError> {
+    pub fn bytes(mut self) -> Result<Vec<u8>, JsonError> {
         match self.invalid {
-            true  => Err(JsonError::Invalid),
-            false => Ok(self.data),
+            true  => Err(JsonErrorKind::Invalid.into()),
+            false => {
+                let data = mem::take(&mut self.data);
+                let data = self.strip(data);
+
+                self.finish(&data)?;
+                Ok(data)
+            },
         }
     }
 
     pub fn string(self) -> Result<String, JsonError> {
         let data = self.bytes()?;
 
-        String::from_utf8(data).map_err(|_| JsonError::Utf8)
+        String::from_utf8(data).map_err(|_| JsonError::from(JsonErrorKind::Utf8))
     }
 
     pub fn completed_bytes(mut self) -> Result<Vec<u8>, JsonError> {
         if self.invalid {
-            Err(JsonError::Invalid)
+            Err(JsonErrorKind::Invalid.into())
         } else {
+            self.check_round_trip()?;
+
+            let mut inserted = 0;
+
             if self.verifier.status() == JsonStatus::Continue {
-                let (until, tokens) = self.verifier.complete();
+                let (until, tokens) = self.completion()?;
+
+                #[cfg(feature = "tracing")]
+                let length = self.data.len();
+
+                inserted = tokens.len();
 
                 self.data.truncate(until);
                 self.data.extend(tokens);
+
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    truncated = length - until,
+                    appended  = self.data.len() - until,
+                    "repaired incomplete json");
             }
 
-            Ok(self.data)
+            let data            = mem::take(&mut self.data);
+            let (data, amended) = self.amend(data, inserted)?;
+
+            self.check_amended(amended)?;
+            self.finish(&data)?;
+            Ok(data)
         }
     }
 
     pub fn completed_string(self) -> Result<String, JsonError> {
         let data = self.completed_bytes()?;
 
-        String::from_utf8(data).map_err(|_| JsonError::Utf8)
+        String::from_utf8(data).map_err(|_| JsonError::from(JsonErrorKind::Utf8))
     }
-}
 
+    /// returns a completed copy of this json object, like `completed_bytes()`, without consuming this builder.
+    ///
+    /// # remarks
+    ///
+    /// the synthetic closing tokens only exist in the returned copy, so more data may be added to this builder
+    /// afterwards. this makes it suitable for progressively rendering a stream that has not finished yet.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": [1, 2"#).unwrap();
+    /// assert_eq!(builder.completed_snapshot().unwrap(), br#"{ "a": [1, 2]}"#.to_vec());
+    ///
+    /// builder.update(r#", 3], "b": tr"#).unwrap();
+    /// assert_eq!(builder.completed_snapshot().unwrap(), br#"{ "a": [1, 2, 3]}"#.to_vec());
+    ///
+    /// builder.update("ue }").unwrap();
+    /// assert_eq!(builder.string().unwrap(), r#"{ "a": [1, 2, 3], "b": true }"#);
+    /// # }
+    /// ```
+    pub fn completed_snapshot(&self) -> Result<Vec<u8>, JsonError> {
+        if self.invalid {
+            Err(JsonErrorKind::Invalid.into())
+        } else {
+            self.check_round_trip()?;
 
+            let (data, inserted) = match self.verifier.status() {
+                JsonStatus::Valid    => (self.data.clone(), 0),
+                JsonStatus::Continue => {
+                    let (until, tokens) = self.completion()?;
+                    let mut data        = self.data[..until].to_vec();
+                    let inserted        = tokens.len();
 
-/// utf8 byte streams for arya's json parsers.
-pub trait JsonSource {
-    fn stream(&self) -> &[u8];
-}
+                    data.extend(tokens);
+                    (data, inserted)
+                },
+            };
 
-impl JsonSource for u8 {
-    fn stream(&self) -> &[u8] {
-        hina::as_slice(&self)
+            let (data, amended) = self.amend(data, inserted)?;
+
+            self.check_amended(amended)?;
+            Ok(data)
+        }
     }
-}
 
-impl JsonSource for &[u8] {
-    fn stream(&self) -> &[u8] {
-        &self
+    pub fn completed_snapshot_string(&self) -> Result<String, JsonError> {
+        let data = self.completed_snapshot()?;
+
+        String::from_utf8(data).map_err(|_| JsonError::from(JsonErrorKind::Utf8))
     }
-}
 
-impl JsonSource for Vec<u8> {
-    fn stream(&self) -> &[u8] {
-        &self
+    /// returns this json object completed like `completed_snapshot()`, as the part of it that is kept - borrowed from
+    /// this builder rather than copied - and the closing tokens completion appends to it. this suits proxies that
+    /// forward messages, which are most often already complete.
+    ///
+    /// # remarks
+    ///
+    /// the completed json object is the first part followed by the second. only amending it - if defaults add missing
+    /// members, members with forbidden keys are stripped, or keys are normalized - copies it, since that may change
+    /// bytes anywhere in it: the first part is then the whole amended object, and the second is empty. like
+    /// `completed_snapshot()`, this does not consume this builder or write to its sink.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// # use std::borrow::Cow;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": [1, 2"#).unwrap();
+    /// assert_eq!(builder.completed_cow().unwrap(), (Cow::Borrowed(&br#"{ "a": [1, 2"#[..]), b"]}".to_vec()));
+    ///
+    /// builder.update("] }").unwrap();
+    /// assert_eq!(builder.completed_cow().unwrap(), (Cow::Borrowed(&br#"{ "a": [1, 2] }"#[..]), vec![]));
+    /// # }
+    /// ```
+    pub fn completed_cow(&self) -> Result<(Cow<'_, [u8]>, Vec<u8>), JsonError> {
+        self.check_round_trip()?;
+
+        let (data, tokens) = self.completed_parts()?;
+
+        if !self.holds_back() || (self.verifier.status() == JsonStatus::Valid && !self.amends()) {
+            return Ok((Cow::Borrowed(data), tokens));
+        }
+
+        let inserted        = tokens.len();
+        let (data, amended) = self.amend([data, &tokens].concat(), inserted)?;
+
+        self.check_amended(amended)?;
+        Ok((Cow::Owned(data), vec![]))
     }
-}
 
-impl JsonSource for &str {
-    fn stream(&self) -> &[u8] {
-        self.as_bytes()
+    /// returns this json object as it is, like `bytes()`, borrowing it instead of consuming this builder. this only
+    /// copies to strip members with forbidden keys, and does not write to the sink.
+    pub fn bytes_cow(&self) -> Result<Cow<'_, [u8]>, JsonError> {
+        match self.invalid {
+            true  => Err(JsonErrorKind::Invalid.into()),
+            false => Ok(self.strip_cow(&self.data)),
+        }
     }
-}
 
-impl JsonSource for String {
-    fn stream(&self) -> &[u8] {
-        self.as_bytes()
+    /// completes this json object like `completed_bytes()`, then elides values until the completed object is no
+    /// larger than `maximum_length` bytes.
+    ///
+    /// # remarks
+    ///
+    /// the most deeply nested values are elided first, and of those at the same depth, the last first - so a long
+    /// nested value is elided before a short member that follows it. the outermost container is never elided.
+    ///
+    /// an elided value is handled like an incomplete one, following `elided_members` and `elided_elements`: with
+    /// `JsonElided::Drop`, the member or element is removed whole along with its separator, and emptied containers
+    /// are kept. otherwise the value is replaced, such as with `null` for `JsonElided::Null` - but only if that makes
+    /// it shorter.
+    ///
+    /// the completed object is read once, so this takes time linear in its size, plus sorting its values by depth.
+    ///
+    /// fails with `JsonErrorKind::TooLarge` if not even the outermost container fits in `maximum_length` bytes.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "name": "annie", "tags": ["a", "b", "c"], "bio": "lorem ipsum"#).unwrap();
+    ///
+    /// assert_eq!(builder.completed_truncated(35).unwrap(), br#"{ "name": "annie", "tags": ["a"]}"#.to_vec());
+    ///
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{"a":{"b":"lorem ipsum dolor sit amet"},"c":1}"#).unwrap();
+    ///
+    /// assert_eq!(builder.completed_truncated(20).unwrap(), br#"{"a":{},"c":1}"#.to_vec());
+    /// # }
+    /// ```
+    pub fn completed_truncated(mut self, maximum_length: usize) -> Result<Vec<u8>, JsonError> {
+        self.sink = None;
+
+        let members  = self.members.clone();
+        let elements = self.elements.clone();
+        let data     = self.completed_bytes()?;
+
+        if data.len() <= maximum_length {
+            return Ok(data);
+        }
+
+        let truncated = truncate::truncate(&data, maximum_length, members.as_deref(), elements.as_deref())?;
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(maximum_length, elided = data.len() - truncated.len(), "truncated json to fit");
+
+        Ok(truncated)
+    }
+
+    /// completes this json object like `completed_bytes()`, then sorts the members of every object by key.
+    ///
+    /// # remarks
+    ///
+    /// this is the only way to reorder the members of an object - see the remarks on
+    /// [`JsonBuilder`](./struct.JsonBuilder.html). it is useful for diff-friendly output, such as config files.
+    ///
+    /// keys are compared by their unescaped text, and members with the same key keep their order. the whitespace
+    /// between members stays where it was, so a pretty-printed object stays pretty-printed. like
+    /// `completed_truncated()`, this never writes to a sink.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "b": 1, "a": { "z": [3, 2], "y": 4 }, "c": tr"#).unwrap();
+    ///
+    /// assert_eq!(builder.completed_sorted_keys().unwrap(), br#"{ "a": { "y": 4, "z": [3, 2] }, "b": 1}"#.to_vec());
+    /// # }
+    /// ```
+    pub fn completed_sorted_keys(mut self) -> Result<Vec<u8>, JsonError> {
+        self.sink = None;
+
+        let data = self.completed_bytes()?;
+
+        reorder::sort_keys(&data)
+    }
+
+    /// completes this json object like `completed_bytes()`, and also returns the ranges of the completed bytes that did
+    /// not come from the input, so that differential viewers can highlight exactly what was invented.
+    ///
+    /// # remarks
+    ///
+    /// the ranges are in order, and do not overlap or touch. they cover the bytes appended by completion (such as
+    /// closing tokens and inserted `null`s), the bytes inserted or replaced by lenient repairs, the values changed by
+    /// literal, coercion and big number repairs, and the default members that were added. bytes that were dropped
+    /// from the input have no range, and a key that was normalized is covered by a range. like `completed_truncated()`,
+    /// this never writes to a sink.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::lenient();
+    ///
+    /// builder.update(r#"{ "a": [1 2], "b": None, "c": "lor"#).unwrap();
+    ///
+    /// let (data, ranges) = builder.completed_annotated().unwrap();
+    ///
+    /// assert_eq!(data, br#"{ "a": [1, 2], "b": null}"#.to_vec());
+    /// assert_eq!(ranges, vec![9..10, 20..25]);
+    /// # }
+    /// ```
+    pub fn completed_annotated(mut self) -> Result<(Vec<u8>, Vec<Range<usize>>), JsonError> {
+        self.sink = None;
+
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        self.check_round_trip()?;
+
+        let (until, tokens) = match self.verifier.status() {
+            JsonStatus::Valid    => (self.data.len(), vec![]),
+            JsonStatus::Continue => self.completion()?,
+        };
+
+        let inserted        = tokens.len();
+        let mut data        = self.data[..until].to_vec();
+        let mut synthesized = vec![false; until + inserted];
+
+        data.extend(tokens);
+
+        for repair in self.repairs.iter().filter(|x| x.offset < until) {
+            let length = match repair.kind {
+                // a control byte is only kept, as a space, directly after a number. otherwise nothing is left of it.
+                JsonRepairKind::Control { .. } => {
+                    let number = repair.offset > 0 && data[repair.offset - 1].is_ascii_digit();
+
+                    (number && data[repair.offset] == b' ') as usize
+                },
+                JsonRepairKind::Literal | JsonRepairKind::Coercion | JsonRepairKind::BigNumber => {
+                    scalar_length(&data[repair.offset..])
+                },
+                _ => 1,
+            };
+
+            for x in &mut synthesized[repair.offset..(repair.offset + length).min(until)] {
+                *x = true;
+            }
+        }
+
+        for x in &mut synthesized[until..] {
+            *x = true;
+        }
+
+        // amended like `amend()`, marking the keys that were normalized and the default members that were added.
+        let mut amended = false;
+
+        #[cfg(feature = "unicode")]
+        {
+            if self.nfc {
+                let keys = unicode::normalized_keys(&data)?;
+
+                amended |= !keys.is_empty();
+
+                let keys           = keys.into_iter().map(|(range, key)| (range, key.into_bytes()));
+                let (output, mask) = splice(&data, &synthesized, keys);
+
+                data        = output;
+                synthesized = mask;
+            }
+        }
+
+        let stripped = self.stripped(&data);
+
+        if !stripped.is_empty() {
+            data        = forbidden::remove(&data, &stripped);
+            synthesized = forbidden::remove(&synthesized, &stripped);
+            amended     = true;
+        }
+
+        if !self.defaults.is_empty() {
+            let length     = data.len();
+            let insertions = defaults::insertions(&data, &self.defaults)?.into_iter().map(|(x, member)| (x..x, member));
+
+            let (output, mask) = splice(&data, &synthesized, insertions);
+
+            data        = output;
+            synthesized = mask;
+            amended    |= data.len() != length;
+
+            if inserted + (data.len() - length) > self.inserted {
+                return Err(JsonErrorKind::CompletionTooLarge.into());
+            }
+        }
+
+        self.check_amended(amended)?;
+
+        let mut ranges: Vec<Range<usize>> = vec![];
+
+        for (i, _) in synthesized.iter().enumerate().filter(|(_, x)| **x) {
+            match ranges.last_mut() {
+                Some(range) if range.end == i => range.end = i + 1,
+                _                             => ranges.push(i..i + 1),
+            }
+        }
+
+        Ok((data, ranges))
+    }
+
+
+
+    /// inserts `fragment` at the current position if and only if it is exactly one complete json value.
+    ///
+    /// # remarks
+    ///
+    /// unlike `update()`, an invalid fragment does not invalidate this builder - the fragment is checked before any
+    /// of it is applied, and this builder is left untouched if it is rejected.
+    ///
+    /// no separators are written. to insert a value after other values, use `value()` instead.
+    ///
+    /// like every method that writes structure, this fails with `JsonErrorKind::Invalid` while lenient repairs or
+    /// whitespace normalization are holding back the end of the last update - such as a partial literal `Tru`.
+    pub fn insert_raw_value(&mut self, fragment: impl JsonSource) -> Result<(), JsonError> {
+        self.check_writable()?;
+
+        self.verifier = self.verifier.splice_value(fragment.stream())?;
+        self.data.extend_from_slice(fragment.stream());
+
+        self.coerce()?;
+        self.forward()
+    }
+
+    /// appends `fragment` at the current position without verifying it, for callers that know it is exactly one
+    /// complete json value - such as the output of a trusted serializer.
+    ///
+    /// # remarks
+    ///
+    /// only the position is checked: a value must be allowed to begin here. the fragment itself is checked only when
+    /// debug assertions are enabled, so an invalid fragment otherwise goes unnoticed until `verify_all()` is called,
+    /// and leaves this builder producing invalid json until then. no separators are written.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": "#).unwrap();
+    /// builder.push_unchecked(r#"[1, 2, { "b": null }]"#).unwrap();
+    /// builder.update(r#", "c": [3"#).unwrap();
+    ///
+    /// assert!(builder.verify_all().is_ok());
+    /// assert_eq!(builder.completed_string().unwrap(), r#"{ "a": [1, 2, { "b": null }], "c": [3]}"#);
+    /// # }
+    /// ```
+    pub fn push_unchecked(&mut self, fragment: impl JsonSource) -> Result<(), JsonError> {
+        let fragment = fragment.stream();
+
+        self.check_writable()?;
+
+        debug_assert!(
+            self.verifier.splice_value(fragment).is_ok(),
+            "`push_unchecked()` was given an invalid fragment.");
+
+        self.verifier.skip_value(fragment.len())?;
+        self.data.extend_from_slice(fragment);
+
+        self.coerce()?;
+        self.forward()
+    }
+
+    /// verifies every byte of this json object from the start, including any appended by `push_unchecked()`.
+    ///
+    /// # remarks
+    ///
+    /// if this json object is not a valid prefix of a json object, the error and the offset it was found at are
+    /// returned, and this builder is invalidated.
+    pub fn verify_all(&mut self) -> Result<(), JsonError> {
+        if self.invalid {
+            return Err(JsonError::at(JsonErrorKind::Invalid, 0));
+        }
+
+        let mut verifier = self.verifier.clone();
+
+        verifier.reset();
+
+        let mut result = self.data.iter().enumerate().try_for_each(|(offset, &character)| {
+            verifier.update(character).map_err(|error| error.with_offset(offset))
+        });
+
+        // values that were not complete, or that ended past their container, leave the depth out of step.
+        if result.is_ok() && verifier.depth() != self.verifier.depth() {
+            result = Err(JsonError::at(JsonErrorKind::Invalid, self.data.len()));
+        }
+
+        if result.is_err() {
+            self.invalid = true;
+        }
+
+        result
+    }
+
+    /// begins a json object at the current position, writing a separator first if one is needed.
+    pub fn begin_object(&mut self) -> Result<(), JsonError> {
+        self.separated(b"{")
+    }
+
+    /// ends the innermost json object.
+    pub fn end_object(&mut self) -> Result<(), JsonError> {
+        self.splice(b"}")
+    }
+
+    /// begins a json array at the current position, writing a separator first if one is needed.
+    pub fn begin_array(&mut self) -> Result<(), JsonError> {
+        self.separated(b"[")
+    }
+
+    /// ends the innermost json array.
+    pub fn end_array(&mut self) -> Result<(), JsonError> {
+        self.splice(b"]")
+    }
+
+    /// writes `key` as the next key of the innermost json object, writing a separator first if one is needed.
+    pub fn key(&mut self, key: &str) -> Result<(), JsonError> {
+        let mut bytes = vec![];
+
+        write_string(key, |x| {
+            bytes.extend_from_slice(x);
+            Ok(())
+        })?;
+        bytes.push(b':');
+
+        self.separated(&bytes)
+    }
+
+    /// writes `fragment` as the next value, writing a separator first if one is needed.
+    ///
+    /// like `insert_raw_value()`, `fragment` must be exactly one complete json value, and this builder is left
+    /// untouched if it is rejected.
+    pub fn value(&mut self, fragment: impl JsonSource) -> Result<(), JsonError> {
+        self.check_writable()?;
+
+        let separator    = self.verifier.needs_separator();
+        let mut verifier = self.verifier.clone();
+
+        if separator {
+            verifier.update(b',')?;
+        }
+
+        self.verifier = verifier.splice_value(fragment.stream())?;
+
+        if separator {
+            self.data.push(b',');
+        }
+
+        self.data.extend_from_slice(fragment.stream());
+
+        self.coerce()?;
+        self.forward()
+    }
+
+
+
+    // applies `source` like `update_partial()`, advancing `offset` past the bytes that were accepted, and reporting a
+    // rejected byte at its offset in the whole input.
+    crate fn update_at(&mut self, source: &[u8], offset: &mut usize) -> Result<(), JsonError> {
+        let (length, result) = self.update_partial(source);
+
+        *offset += length;
+
+        result.map_err(|error| error.with_offset(*offset))
+    }
+
+    // applies the bytes of `source` until one is rejected, returning the number that were applied. the deadline and
+    // cancellation token are checked between runs of bytes, rather than for each one.
+    fn apply_all(&mut self, source: &[u8]) -> (usize, Result<(), JsonError>) {
+        let mut applied = 0;
+
+        while applied < source.len() {
+            let run = match &mut self.cancel {
+                Some(cancel) => match cancel.run(source.len() - applied) {
+                    Ok(run) => run,
+                    Err(e)  => return (applied, Err(e)),
+                },
+                None => source.len() - applied,
+            };
+
+            for character in &source[applied..applied + run] {
+                if let Err(e) = self.apply(*character) {
+                    return (applied, Err(e));
+                }
+
+                applied += 1;
+            }
+        }
+
+        (applied, Ok(()))
+    }
+
+    // reports an update of `length` bytes, the repairs made during it, and the error that invalidated this builder, if
+    // any, to the metrics recorder.
+    #[cfg(feature = "metrics")]
+    fn record_metrics(&mut self, length: usize, error: Option<JsonErrorKind>) {
+        let tally = &self.tally;
+
+        metrics::record(|x| {
+            x.processed(length);
+
+            for kind in tally {
+                x.repaired(*kind);
+            }
+
+            if let Some(e) = error {
+                x.failed(e);
+            }
+        });
+
+        self.tally.clear();
+    }
+
+    fn apply(&mut self, character: u8) -> Result<(), JsonError> {
+        if self.verifier.status() == JsonStatus::Valid && self.pending.is_empty() && self.punctuation.is_empty() {
+            match self.trailing {
+                JsonTrailing::Reject => {},
+                JsonTrailing::Ignore => {
+                    // whitespace after the json object is not part of it, so ignoring it does not modify it.
+                    if !b" \t\r\n".contains(&character) {
+                        self.modified = true;
+                    }
+
+                    return Ok(());
+                },
+                JsonTrailing::BeginNextDocument => {
+                    if character == b'{' || character == b'[' {
+                        self.forward()?;
+                        self.write_document()?;
+                        self.reset();
+                    }
+                },
+            }
+        }
+
+        let unicode = character >= 128 || !self.punctuation.is_empty();
+
+        if self.lenient.punctuation && unicode && (!self.verifier.in_string() || self.smart.is_some()) {
+            return self.apply_punctuation(character);
+        }
+
+        self.apply_character(character)?;
+
+        if self.smart.is_some() && !self.verifier.in_string() {
+            self.smart = None;
+        }
+
+        self.coerce()
+    }
+
+    // applies a character that may be part of unicode punctuation.
+    fn apply_punctuation(&mut self, character: u8) -> Result<(), JsonError> {
+        self.punctuation.push(character);
+
+        let sequence    = &self.punctuation[..];
+        let punctuation = match arya::repair::punctuation(sequence) {
+            Some(Some(x)) => x,
+            Some(None)    => return Ok(()),
+            None          => return self.replay_punctuation(),
+        };
+
+        let quote = match punctuation {
+            b'"' => arya::repair::closing_quote(sequence),
+            _    => &b""[..],
+        };
+
+        let replacement = match (self.smart, self.verifier.in_string()) {
+            // inside a string opened by a curly quote, only its closing quote is replaced.
+            (Some(closing), true) if closing == sequence => b'"',
+            (Some(_), true)                              => return self.replay_punctuation(),
+            (_, _)                                       => punctuation,
+        };
+
+        if !self.repair_with(&[replacement], JsonRepairKind::Punctuation) {
+            self.punctuation.pop();
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        self.smart = match (replacement, self.verifier.in_string()) {
+            (b'"', true) => Some(quote),
+            (_, _)       => None,
+        };
+
+        self.punctuation.clear();
+        Ok(())
+    }
+
+    // applies buffered bytes that turned out not to be unicode punctuation as they are.
+    fn replay_punctuation(&mut self) -> Result<(), JsonError> {
+        let sequence = std::mem::take(&mut self.punctuation);
+
+        for character in sequence {
+            self.apply_character(character)?;
+        }
+
+        Ok(())
+    }
+
+    fn apply_character(&mut self, character: u8) -> Result<(), JsonError> {
+        let whitespace = character >= 128 || character == 0x0b || character == 0x0c || !self.pending.is_empty();
+
+        if self.normalize && whitespace && !self.verifier.in_string() {
+            self.pending.push(character);
+
+            match arya::table::extra_whitespace(&self.pending) {
+                Some(true) => {
+                    if let Err(e) = self.verifier.update(b' ') {
+                        self.pending.pop();
+                        return Err(e);
+                    }
+
+                    self.pending.clear();
+                    self.data.push(b' ');
+
+                    self.modified = true;
+                },
+                Some(false) => {},
+                None => {
+                    self.pending.pop();
+                    return Err(JsonErrorKind::Invalid.into());
+                },
+            }
+        } else if !self.literal.is_empty() {
+            return self.apply_literal(character);
+        } else {
+            if character == b'-' && self.lenient.literals && !self.verifier.in_string() {
+                self.minus = Some(self.verifier.mark());
+            }
+
+            match self.verifier.update(character) {
+                Ok(())                           => self.data.push(character),
+                Err(_) if self.repair(character) => {},
+                Err(e)                           => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    // attempts to repair `character` after the verifier rejected it, returning `false` if no enabled repair applies.
+    fn repair(&mut self, character: u8) -> bool {
+        let brackets = self.lenient.brackets;
+        let commas   = self.lenient.commas && self.verifier.needs_separator();
+        let colons   = self.lenient.colons && self.verifier.token() == Token::Colon;
+        let literals = self.lenient.literals && arya::repair::literal(&[character], self.lenient.non_finite).is_some();
+        let control  = self.lenient.control && character < 0x20 && !self.verifier.in_string();
+
+        match character {
+            // a control byte ends a number like whitespace would, but is otherwise skipped.
+            _ if control && self.verifier.in_number() => {
+                self.repair_with(b" ", JsonRepairKind::Control { found: character })
+            },
+            _ if control && !events::is_scalar(self.verifier.token()) => {
+                self.repair_with(b"", JsonRepairKind::Control { found: character })
+            },
+            // `-Infinity` - undo the minus sign, and read it as part of the literal.
+            b'I' if literals && self.verifier.token() == Token::Minus => {
+                match self.minus.take() {
+                    Some(minus) => {
+                        self.verifier.rewind(minus);
+                        self.data.pop();
+                        self.literal.extend_from_slice(b"-I");
+
+                        true
+                    },
+                    None => false,
+                }
+            },
+            _ if literals && self.verifier.clone().update(b'n').is_ok() => {
+                self.literal.push(character);
+                true
+            },
+            // a literal that is also missing the separator before it, such as `[1 None]`.
+            _ if literals && (commas || colons) => {
+                let (separator, kind) = match commas {
+                    true  => (b',', JsonRepairKind::Comma),
+                    false => (b':', JsonRepairKind::Colon),
+                };
+
+                let mut verifier = self.verifier.clone();
+
+                if verifier.update(separator).and_then(|()| verifier.update(b'n')).is_err() {
+                    return false;
+                }
+
+                self.repair_separator(separator, b"", kind);
+                self.literal.push(character);
+
+                true
+            },
+            b']' if brackets => self.repair_with(b"}", JsonRepairKind::Bracket { found: character, replacement: b'}' }),
+            b'}' if brackets => self.repair_with(b"]", JsonRepairKind::Bracket { found: character, replacement: b']' }),
+            _    if commas   => self.repair_separator(b',', &[character], JsonRepairKind::Comma),
+            _    if colons   => self.repair_separator(b':', &[character], JsonRepairKind::Colon),
+            _                => false,
+        }
+    }
+
+    // applies a character that continues a literal that will be repaired.
+    fn apply_literal(&mut self, character: u8) -> Result<(), JsonError> {
+        self.literal.push(character);
+
+        match arya::repair::literal(&self.literal, self.lenient.non_finite) {
+            Some(Some(replacement)) => {
+                match self.repair_with(replacement, JsonRepairKind::Literal) {
+                    true  => {
+                        self.literal.clear();
+                        Ok(())
+                    },
+                    false => {
+                        self.literal.pop();
+                        Err(JsonErrorKind::Invalid.into())
+                    },
+                }
+            },
+            Some(None) => {
+                Ok(())
+            },
+            None => {
+                self.literal.pop();
+                Err(JsonErrorKind::Invalid.into())
+            },
+        }
+    }
+
+    // applies `bytes` in place of a rejected character if every one of them is accepted, recording the repair.
+    fn repair_with(&mut self, bytes: &[u8], kind: JsonRepairKind) -> bool {
+        let mut verifier = self.verifier.clone();
+
+        if bytes.iter().any(|x| verifier.update(*x).is_err()) {
+            return false;
+        }
+
+        #[cfg(feature = "metrics")]
+        self.tally.push(kind);
+
+        self.repairs.push(JsonRepair { offset: self.data.len(), kind });
+        self.verifier = verifier;
+        self.data.extend_from_slice(bytes);
+
+        true
+    }
+
+    // inserts a missing `separator` in front of the whitespace after the value or key before it, then applies `bytes`,
+    // if every one of them is accepted - recording the repair. whitespace that was already written to the sink stays in
+    // front of it.
+    fn repair_separator(&mut self, separator: u8, bytes: &[u8], kind: JsonRepairKind) -> bool {
+        let whitespace = self.data.iter().rev().take_while(|x| b" \t\r\n".contains(x)).count();
+        let offset     = (self.data.len() - whitespace).max(self.written);
+
+        let mut verifier = self.verifier.clone();
+
+        if verifier.update(separator).is_err() {
+            return false;
+        }
+
+        verifier.uncut(offset);
+
+        if bytes.iter().any(|x| verifier.update(*x).is_err()) {
+            return false;
+        }
+
+        if let Some(coercer) = &mut self.coercer {
+            if coercer.insert(offset, separator).is_err() {
+                return false;
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        self.tally.push(kind);
+
+        // repairs in the whitespace, such as control bytes that were replaced with spaces, move along with it.
+        for repair in self.repairs.iter_mut().filter(|x| x.offset >= offset) {
+            repair.offset += 1;
+        }
+
+        self.repairs.push(JsonRepair { offset, kind });
+        self.verifier = verifier;
+        self.data.insert(offset, separator);
+        self.data.extend_from_slice(bytes);
+
+        true
+    }
+
+    // rewrites the values that were just completed if they are converted into other types of values. the verifier
+    // only needs to learn the new length, since a complete value and the value it becomes leave it in the same state.
+    fn coerce(&mut self) -> Result<(), JsonError> {
+        #[cfg(feature = "metrics")]
+        let JsonBuilder { data, verifier, repairs, coercer, tally, .. } = self;
+
+        #[cfg(not(feature = "metrics"))]
+        let JsonBuilder { data, verifier, repairs, coercer, .. } = self;
+
+        match coercer {
+            Some(coercer) => coercer.update(data, |kind, offset, before, after| {
+                #[cfg(feature = "metrics")]
+                tally.push(kind);
+
+                repairs.push(JsonRepair { offset, kind });
+                verifier.resize(before, after);
+            }),
+            None => Ok(()),
+        }
+    }
+
+    // returns the ranges of `data` that hold members with forbidden keys, if they are stripped.
+    fn stripped(&self, data: &[u8]) -> Vec<Range<usize>> {
+        match &self.forbidden {
+            Some(forbidden) if forbidden.action == JsonForbiddenAction::Strip => forbidden::find(data, &forbidden.keys).1,
+            _                                                                 => vec![],
+        }
+    }
+
+    // removes the members with forbidden keys from `data`, if they are stripped.
+    fn strip(&self, data: Vec<u8>) -> Vec<u8> {
+        let ranges = self.stripped(&data);
+
+        match ranges.is_empty() {
+            true  => data,
+            false => forbidden::remove(&data, &ranges),
+        }
+    }
+
+    fn strip_cow<'a>(&self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        let ranges = self.stripped(data);
+
+        match ranges.is_empty() {
+            true  => Cow::Borrowed(data),
+            false => Cow::Owned(forbidden::remove(data, &ranges)),
+        }
+    }
+
+    // normalizes the keys of the completed json object `data`, strips the members with forbidden keys - so that a key
+    // is matched as it is written out - then adds the missing default members, failing if they take the bytes inserted
+    // past the maximum, along with the `inserted` bytes completion already inserted. also returns whether any of them
+    // changed `data`.
+    fn amend(&self, data: Vec<u8>, inserted: usize) -> Result<(Vec<u8>, bool), JsonError> {
+        #[cfg(feature = "unicode")]
+        let (data, normalized) = match self.nfc {
+            true  => match unicode::normalize_keys(&data)? {
+                Some(x) => (x, true),
+                None    => (data, false),
+            },
+            false => (data, false),
+        };
+
+        #[cfg(not(feature = "unicode"))]
+        let normalized = false;
+
+        let stripped = self.stripped(&data);
+        let amended  = normalized || !stripped.is_empty();
+
+        let data = match stripped.is_empty() {
+            true  => data,
+            false => forbidden::remove(&data, &stripped),
+        };
+
+        if self.defaults.is_empty() {
+            return Ok((data, amended));
+        }
+
+        let length  = data.len();
+        let data    = defaults::inject(data, &self.defaults)?;
+        let amended = amended || data.len() != length;
+
+        match inserted + (data.len() - length) > self.inserted {
+            true  => Err(JsonErrorKind::CompletionTooLarge.into()),
+            false => Ok((data, amended)),
+        }
+    }
+
+    // true if completing the complete json object in `self.data` normalizes, strips or adds members - checked in
+    // place, without amending a copy of it.
+    fn amends(&self) -> bool {
+        #[cfg(feature = "unicode")]
+        {
+            if self.nfc && unicode::normalized_keys(&self.data).map_or(true, |x| !x.is_empty()) {
+                return true;
+            }
+        }
+
+        if !self.stripped(&self.data).is_empty() {
+            return true;
+        }
+
+        match self.defaults.is_empty() {
+            true  => false,
+            false => defaults::insertions(&self.data, &self.defaults).map_or(true, |x| !x.is_empty()),
+        }
+    }
+
+    // true if this json object was modified before it is amended - everything `was_modified()` checks, other than
+    // `amends()`.
+    fn modified_before_amend(&self) -> bool {
+        let incomplete = self.verifier.status() == JsonStatus::Continue;
+
+        self.invalid || self.modified || !self.repairs.is_empty() || !self.pending.is_empty() || incomplete
+    }
+
+    // fails with `JsonErrorKind::Modified` if this valid json object must be kept byte for byte, but was modified
+    // before it is amended. what amending changes is checked by `check_amended()`, once it has been amended.
+    fn check_round_trip(&self) -> Result<(), JsonError> {
+        match self.round_trip && !self.invalid && self.modified_before_amend() {
+            true  => Err(JsonErrorKind::Modified.into()),
+            false => Ok(()),
+        }
+    }
+
+    // fails with `JsonErrorKind::Modified` if this json object must be kept byte for byte, but was `amended`.
+    fn check_amended(&self, amended: bool) -> Result<(), JsonError> {
+        match self.round_trip && amended {
+            true  => Err(JsonErrorKind::Modified.into()),
+            false => Ok(()),
+        }
+    }
+
+    fn separated(&mut self, bytes: &[u8]) -> Result<(), JsonError> {
+        match self.verifier.needs_separator() {
+            true  => self.splice(&[&b","[..], bytes].concat()),
+            false => self.splice(bytes),
+        }
+    }
+
+    // applies `bytes` to this builder only if every one of them is accepted.
+    fn splice(&mut self, bytes: &[u8]) -> Result<(), JsonError> {
+        self.check_writable()?;
+
+        let mut verifier = self.verifier.clone();
+
+        for character in bytes {
+            verifier.update(*character)?;
+        }
+
+        self.verifier = verifier;
+        self.data.extend_from_slice(bytes);
+
+        self.coerce()?;
+        self.forward()
+    }
+
+    // fails with `JsonErrorKind::Invalid` if this builder is invalid, or is holding back bytes from the last update - a
+    // partial literal, unicode punctuation or non-standard whitespace - which anything written now would end up in
+    // front of. every method that writes structure checks this first.
+    fn check_writable(&self) -> Result<(), JsonError> {
+        match self.invalid || !self.pending.is_empty() || !self.literal.is_empty() || !self.punctuation.is_empty() {
+            true  => Err(JsonErrorKind::Invalid.into()),
+            false => Ok(()),
+        }
+    }
+
+    // returns the length this json object should be truncated to, and the tokens that should then be appended to it,
+    // to complete it into a valid json object - or an error if that takes more changes than this builder allows.
+    fn completion(&self) -> Result<(usize, Vec<u8>), JsonError> {
+        self.completion_with(self.keys, self.strings, self.members.as_deref(), self.elements.as_deref())
+    }
+
+    // like `completion()`, but completes incomplete keys and string values with `keys` and `strings`, and replaces
+    // incomplete member values and elements with `members` and `elements`, instead of this builder's own options.
+    fn completion_with(
+        &self,
+        keys:     JsonIncomplete,
+        strings:  JsonIncomplete,
+        members:  Option<&[u8]>,
+        elements: Option<&[u8]>) -> Result<(usize, Vec<u8>), JsonError>
+    {
+        let verifier = &self.verifier;
+        let string   = verifier.in_string();
+        let key      = verifier.in_key();
+        let value    = verifier.in_object_value();
+
+        let shaped = self.shaped()?;
+        let suffix = match (verifier.token(), keys, strings) {
+            _ if shaped.is_some()                             => &b""[..],
+            (_, JsonIncomplete::Keep, _) if string && key     => &b"\":null"[..],
+            (_, _, JsonIncomplete::Keep) if string && !key    => &b"\""[..],
+            (Token::Colon, JsonIncomplete::Keep, _) if key    => &b":null"[..],
+            (Token::Value, JsonIncomplete::Keep, _) if value  => &b"null"[..],
+            _                                                 => &b""[..],
+        };
+
+        let elided = match (shaped, suffix.is_empty()) {
+            (Some(x), _)  => Some(x),
+            (None, true)  => self.elided(members, elements).map(|(length, x)| (length, x.to_vec())),
+            (None, false) => None,
+        };
+
+        let (until, tokens) = match (suffix.is_empty(), &elided) {
+            (true, Some((length, _))) => (self.data.len() - length, verifier.closing_tokens().collect()),
+            (true, None) => {
+                let (until, tokens) = verifier.complete();
+
+                (until, tokens.collect())
+            },
+            (false, _) => {
+                let mut until = self.data.len() - verifier.partial_escape();
+
+                if string {
+                    until -= incomplete_utf8(&self.data[..until]);
+                }
+
+                (until, suffix.iter().cloned().chain(verifier.closing_tokens()).collect::<Vec<_>>())
+            },
+        };
+
+        // a replacement goes after the whitespace before it, and before the closing tokens so that indenting them never
+        // looks inside it.
+        let kept = string && !suffix.is_empty();
+
+        let (until, mut tokens) = match (self.closers, elided.is_some()) {
+            (JsonClosers::Inline, _)       => (until, tokens),
+            (JsonClosers::Indented, false) => indent(&self.data, until, kept, tokens),
+            (JsonClosers::Indented, true)  => (until, indent(&self.data, until, kept, tokens).1),
+        };
+
+        if let Some((_, replacement)) = elided {
+            tokens.splice(0..0, replacement.iter().cloned());
+        }
+
+        match tokens.len() > self.inserted || self.data.len() - until > self.truncated {
+            true  => Err(JsonErrorKind::CompletionTooLarge.into()),
+            false => Ok((until, tokens)),
+        }
+    }
+
+    // returns the length of the incomplete value at the end of this json object and its replacement, if there is a
+    // replacement for it.
+    fn elided<'a>(&self, members: Option<&'a [u8]>, elements: Option<&'a [u8]>) -> Option<(usize, &'a [u8])> {
+        let verifier    = &self.verifier;
+        let replacement = match (verifier.in_object_value(), verifier.in_array()) {
+            (true, _) => members?,
+            (_, true) => elements?,
+            (_, _)    => return None,
+        };
+
+        Some((self.partial_value()?, replacement))
+    }
+
+    // returns the length of the incomplete value at the end of this json object and its replacement, if this builder
+    // was given a shape that decides it.
+    //
+    // the path of that value is found by the coercer, which follows the paths of the values as they are built.
+    fn shaped(&self) -> Result<Option<(usize, Vec<u8>)>, JsonError> {
+        let verifier = &self.verifier;
+
+        let (shape, coercer) = match (&self.shape, &self.coercer) {
+            (Some(shape), Some(coercer)) if !verifier.in_key() => (shape, coercer),
+            (_, _)                                             => return Ok(None),
+        };
+
+        let length = match self.partial_value() {
+            Some(length) => length,
+            None         => return Ok(None),
+        };
+
+        let value = &self.data[self.data.len() - length..];
+
+        let number = match verifier.token() {
+            Token::Fraction1 | Token::Exponent1 | Token::Exponent2 => true,
+            _                                                      => false,
+        };
+
+        let shaped = match shape.at(&coercer.next_path(&self.data)?) {
+            JsonShape::String if verifier.token() == Token::Value => Some((length, b"\"\"".to_vec())),
+            JsonShape::String if verifier.in_string() => {
+                let until = self.data.len() - verifier.partial_escape();
+                let until = until - incomplete_utf8(&self.data[..until]);
+
+                Some((self.data.len() - until, b"\"".to_vec()))
+            },
+            JsonShape::Object(_)                                  => Some((length, b"{}".to_vec())),
+            JsonShape::Array(_)                                   => Some((length, b"[]".to_vec())),
+            JsonShape::Boolean if value.starts_with(b"t")         => Some((length, b"true".to_vec())),
+            JsonShape::Boolean if value.starts_with(b"f")         => Some((length, b"false".to_vec())),
+            JsonShape::Number if number => {
+                // the digits before an incomplete fraction or exponent are a complete number.
+                let kept = value.len() - value.iter().rev().take_while(|x| b".eE+-".contains(x)).count();
+
+                Some((length, value[..kept].to_vec()))
+            },
+            _ => None,
+        };
+
+        Ok(shaped)
+    }
+
+    // returns the length of the incomplete value at the end of this json object, including an expected value that has
+    // not been started - or `None` if it does not end part way through a value.
+    fn partial_value(&self) -> Option<usize> {
+        let verifier = &self.verifier;
+        let length   = match verifier.token() {
+            Token::Value                                                            => 0,
+            Token::String | Token::Escape                                           => verifier.token_length() + 1,
+            Token::U1 | Token::U2 | Token::U3 | Token::U4                           => verifier.token_length() + 1,
+            Token::Minus | Token::Fraction1 | Token::Exponent1 | Token::Exponent2   => verifier.token_length(),
+            Token::TrueTr | Token::TrueTru | Token::TrueTrue                        => trailing_letters(&self.data),
+            Token::FalseFa | Token::FalseFal | Token::FalseFals | Token::FalseFalse => trailing_letters(&self.data),
+            Token::NullNu | Token::NullNul | Token::NullNull                        => trailing_letters(&self.data),
+            _                                                                       => return None,
+        };
+
+        Some(length)
+    }
+
+    // returns the part of this json object that its completion keeps, and the tokens that completion appends.
+    crate fn completed_parts(&self) -> Result<(&[u8], Vec<u8>), JsonError> {
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        match self.verifier.status() {
+            JsonStatus::Valid    => Ok((&self.data, vec![])),
+            JsonStatus::Continue => {
+                let (until, tokens) = self.completion()?;
+
+                Ok((&self.data[..until], tokens))
+            },
+        }
+    }
+
+    // returns the maximum depth of the json objects this builder accepts.
+    crate fn maximum_depth(&self) -> usize {
+        self.verifier.options().maximum_depth
+    }
+
+    // removes the bytes that no completion could drop from the start of this json object, along with the repairs made
+    // in them, so that they can be kept elsewhere. the remaining repairs are shifted to match.
+    crate fn drain_committed(&mut self) -> (Vec<u8>, Vec<JsonRepair>) {
+        let until = self.verifier.committed();
+        let bytes = self.data.drain(..until).collect();
+
+        let (drained, repairs): (Vec<_>, Vec<_>) = self.repairs.drain(..).partition(|x| x.offset < until);
+
+        self.repairs = repairs.into_iter().map(|x| JsonRepair { offset: x.offset - until, ..x }).collect();
+        self.written = self.written.saturating_sub(until);
+        self.verifier.resize(until, 0);
+
+        // the state before the last minus sign is only needed while the minus sign is the last token.
+        match (&mut self.minus, self.verifier.token()) {
+            (Some(minus), Token::Minus) => minus.resize(until, 0),
+            (_, _)                      => self.minus = None,
+        }
+
+        (bytes, drained)
+    }
+
+    // writes every byte that no completion could drop to the sink, and calls the completion callback if this json
+    // object has just become complete.
+    fn forward(&mut self) -> Result<(), JsonError> {
+        if !self.holds_back() {
+            self.write_through(self.verifier.committed())?;
+        }
+
+        if let Some(complete) = &mut self.complete {
+            if !self.notified && self.verifier.status() == JsonStatus::Valid {
+                self.notified = true;
+                (complete.get())(hina::trim(&self.data));
+            }
+        }
+
+        Ok(())
+    }
+
+    // true if completing this json object may strip, normalize or add members, which can change bytes anywhere in it -
+    // so nothing is written to the sink until it is finished.
+    fn holds_back(&self) -> bool {
+        let strips = match &self.forbidden {
+            Some(forbidden) => forbidden.action == JsonForbiddenAction::Strip,
+            None            => false,
+        };
+
+        strips || self.nfc || !self.defaults.is_empty()
+    }
+
+    // writes the rest of `data` - this json object, once completed and amended - to the sink, and flushes it.
+    fn finish(&mut self, data: &[u8]) -> Result<(), JsonError> {
+        self.write_rest(data)?;
+
+        if let Some(sink) = &mut self.sink {
+            sink.get().flush().map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+        }
+
+        #[cfg(feature = "metrics")]
+        metrics::record(|x| x.validated());
+
+        Ok(())
+    }
+
+    // writes the rest of this complete json object to the sink, before the next one begins.
+    fn write_document(&mut self) -> Result<(), JsonError> {
+        match self.sink.is_some() && self.holds_back() {
+            true  => self.amend(self.data.clone(), 0).and_then(|(x, _)| self.write_rest(&x)),
+            false => self.write_through(self.data.len()),
+        }
+    }
+
+    fn write_through(&mut self, until: usize) -> Result<(), JsonError> {
+        let data   = mem::take(&mut self.data);
+        let result = self.write_rest(&data[..until]);
+
+        self.data = data;
+        result
+    }
+
+    // writes the bytes of `data` that have not been written yet to the sink.
+    fn write_rest(&mut self, data: &[u8]) -> Result<(), JsonError> {
+        if let Some(sink) = &mut self.sink {
+            if data.len() > self.written {
+                if sink.get().write_all(&data[self.written..]).is_err() {
+                    self.invalid = true;
+                    return Err(JsonErrorKind::Io.into());
+                }
+
+                self.written = data.len();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+
+// replaces each range of `data` - in order, and not overlapping - with the bytes given for it, and marks those bytes
+// as synthesized in the copy of `synthesized` returned along with it.
+fn splice(
+    data:         &[u8],
+    synthesized:  &[bool],
+    replacements: impl IntoIterator<Item = (Range<usize>, Vec<u8>)>) -> (Vec<u8>, Vec<bool>)
+{
+    let mut output = Vec::with_capacity(data.len());
+    let mut mask   = Vec::with_capacity(data.len());
+    let mut start  = 0;
+
+    for (range, bytes) in replacements {
+        output.extend_from_slice(&data[start..range.start]);
+        output.extend_from_slice(&bytes);
+        mask.extend_from_slice(&synthesized[start..range.start]);
+        mask.extend(bytes.iter().map(|_| true));
+
+        start = range.end;
+    }
+
+    output.extend_from_slice(&data[start..]);
+    mask.extend_from_slice(&synthesized[start..]);
+
+    (output, mask)
+}
+
+// places each closing token in `tokens` on its own line, indented to the depth of the container it closes, if `data`
+// is pretty-printed. whitespace at the end of `data[..until]` is dropped, since the closing tokens begin a new line -
+// unless `data[..until]` ends inside a string that is kept, which that whitespace is part of.
+fn indent(data: &[u8], until: usize, string: bool, tokens: Vec<u8>) -> (usize, Vec<u8>) {
+    let newline = match data[..until].iter().position(|x| *x == b'\n') {
+        Some(i) => i,
+        None    => return (until, tokens),
+    };
+
+    // the indentation of the first indented line is taken as one level.
+    let unit = data[..until]
+        .split(|x| *x == b'\n')
+        .skip(1)
+        .map(|line| &line[..line.iter().take_while(|x| **x == b' ' || **x == b'\t').count()])
+        .find(|x| !x.is_empty());
+
+    let unit = match unit {
+        Some(unit) => unit,
+        None       => return (until, tokens),
+    };
+
+    let line_ending = match newline > 0 && data[newline - 1] == b'\r' {
+        true  => &b"\r\n"[..],
+        false => &b"\n"[..],
+    };
+
+    let until = match string {
+        true  => until,
+        false => until - data[..until].iter().rev().take_while(|x| b" \t\r\n".contains(x)).count(),
+    };
+
+    let mut depth  = tokens.iter().filter(|x| **x == b'}' || **x == b']').count();
+    let mut output = Vec::with_capacity(tokens.len() + depth * (line_ending.len() + depth * unit.len()));
+
+    for token in tokens {
+        if token == b'}' || token == b']' {
+            depth -= 1;
+
+            output.extend_from_slice(line_ending);
+
+            for _ in 0..depth {
+                output.extend_from_slice(unit);
+            }
+        }
+
+        output.push(token);
+    }
+
+    (until, output)
+}
+
+// writes `value` into `sink` as a quoted json string, escaping characters as needed.
+crate fn write_string(
+    value:    &str,
+    mut sink: impl FnMut(&[u8]) -> Result<(), JsonError>) -> Result<(), JsonError>
+{
+    const HEX: &[u8] = b"0123456789abcdef";
+
+    let bytes = value.as_bytes();
+    let mut start = 0;
+
+    sink(b"\"")?;
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        let unicode;
+        let escape: &[u8] = match byte {
+            b'"'        => b"\\\"",
+            b'\\'       => b"\\\\",
+            b'\n'       => b"\\n",
+            b'\r'       => b"\\r",
+            b'\t'       => b"\\t",
+            0x08        => b"\\b",
+            0x0c        => b"\\f",
+            0x00..=0x1f => {
+                unicode = [b'\\', b'u', b'0', b'0', HEX[(byte >> 4) as usize], HEX[(byte & 0xf) as usize]];
+                &unicode
+            },
+            _           => continue,
+        };
+
+        sink(&bytes[start..i])?;
+        sink(escape)?;
+
+        start = i + 1;
+    }
+
+    sink(&bytes[start..])?;
+    sink(b"\"")
+}
+
+
+
+// returns the number of bytes at the end of `data` that are the start of an incomplete utf8 sequence.
+fn incomplete_utf8(data: &[u8]) -> usize {
+    let continuations = data.iter().rev().take(3).take_while(|x| **x & 0xc0 == 0x80).count();
+
+    let expected = match data.len().checked_sub(continuations + 1).map(|i| data[i]) {
+        Some(x) if x & 0xe0 == 0xc0 => 2,
+        Some(x) if x & 0xf0 == 0xe0 => 3,
+        Some(x) if x & 0xf8 == 0xf0 => 4,
+        _                           => return 0,
+    };
+
+    match continuations + 1 < expected {
+        true  => continuations + 1,
+        false => 0,
+    }
+}
+
+
+
+// returns the length of the string, number or literal at the start of `data`.
+fn scalar_length(data: &[u8]) -> usize {
+    if data.first() != Some(&b'"') {
+        return data.iter().take_while(|x| !b",]} \t\r\n".contains(x)).count();
+    }
+
+    let mut escaped = false;
+
+    for (i, &character) in data.iter().enumerate().skip(1) {
+        match (escaped, character) {
+            (false, b'"')  => return i + 1,
+            (false, b'\\') => escaped = true,
+            (_, _)         => escaped = false,
+        }
+    }
+
+    data.len()
+}
+
+
+
+// returns the number of letters at the end of `data`, such as those of an incomplete literal.
+fn trailing_letters(data: &[u8]) -> usize {
+    data.iter().rev().take_while(|x| x.is_ascii_alphabetic()).count()
+}
+
+
+
+// returns the json value that replaces elided values, or `None` if they are dropped.
+fn elided(elided: &JsonElided) -> Option<Vec<u8>> {
+    match elided {
+        JsonElided::Drop        => None,
+        JsonElided::Null        => Some(b"null".to_vec()),
+        JsonElided::EmptyString => Some(b"\"\"".to_vec()),
+        JsonElided::Sentinel(x) => {
+            let mut bytes = vec![];
+
+            write_string(x, |x| {
+                bytes.extend_from_slice(x);
+                Ok(())
+            }).ok()?;
+
+            Some(bytes)
+        },
+    }
+}
+
+
+
+/// utf8 byte streams for arya's json parsers.
+///
+/// # remarks
+///
+/// this trait is object safe, so sources of different types can be kept together as `Box<dyn JsonSource>`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonSource};
+/// # use std::sync::Arc;
+/// #
+/// # fn main() {
+/// #
+/// let sources: Vec<Box<dyn JsonSource>> = vec![
+///     Box::new(*b"{ \"a\": "),
+///     Box::new(Arc::<[u8]>::from(&b"[1, 2"[..])),
+///     Box::new(String::from("] }")),
+/// ];
+///
+/// let mut builder = JsonBuilder::new();
+///
+/// for source in &sources {
+///     builder.update(source).unwrap();
+/// }
+///
+/// assert_eq!(builder.string().unwrap(), r#"{ "a": [1, 2] }"#);
+/// # }
+/// ```
+pub trait JsonSource {
+    fn stream(&self) -> &[u8];
+}
+
+impl JsonSource for u8 {
+    fn stream(&self) -> &[u8] {
+        hina::as_slice(&self)
+    }
+}
+
+impl JsonSource for [u8] {
+    fn stream(&self) -> &[u8] {
+        self
+    }
+}
+
+impl<const N: usize> JsonSource for [u8; N] {
+    fn stream(&self) -> &[u8] {
+        self
+    }
+}
+
+impl JsonSource for Vec<u8> {
+    fn stream(&self) -> &[u8] {
+        self
+    }
+}
+
+impl JsonSource for str {
+    fn stream(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl JsonSource for String {
+    fn stream(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<T: JsonSource + ?Sized> JsonSource for &T {
+    fn stream(&self) -> &[u8] {
+        (**self).stream()
+    }
+}
+
+impl<T: JsonSource + ?Sized> JsonSource for Box<T> {
+    fn stream(&self) -> &[u8] {
+        (**self).stream()
+    }
+}
+
+impl<T: JsonSource + ?Sized> JsonSource for Rc<T> {
+    fn stream(&self) -> &[u8] {
+        (**self).stream()
+    }
+}
+
+impl<T: JsonSource + ?Sized> JsonSource for Arc<T> {
+    fn stream(&self) -> &[u8] {
+        (**self).stream()
+    }
+}
+
+
+
+/// a [`JsonSource`](./trait.JsonSource.html) that reads its bytes from an `io::Read` one chunk at a time, into a
+/// buffer that is reused for every chunk.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonReadChunks};
+/// #
+/// # fn main() {
+/// #
+/// let reader     = &b"{ \"name\": \"annie\", \"tags\": [1, 2] }"[..];
+/// let mut chunks = JsonReadChunks::new(reader, vec![0; 8]);
+/// let mut builder = JsonBuilder::new();
+///
+/// while chunks.fill().unwrap() > 0 {
+///     builder.update(&chunks).unwrap();
+/// }
+///
+/// assert_eq!(builder.string().unwrap(), r#"{ "name": "annie", "tags": [1, 2] }"#);
+/// # }
+/// ```
+pub struct JsonReadChunks<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    length: usize,
+}
+
+impl<R: Read> JsonReadChunks<R> {
+    /// creates a source that reads from `reader` into `buffer`. each chunk is at most as long as `buffer`, or 8 KiB if
+    /// `buffer` is empty.
+    pub fn new(reader: R, mut buffer: Vec<u8>) -> JsonReadChunks<R> {
+        if buffer.is_empty() {
+            buffer.resize(8 * 1024, 0);
+        }
+
+        JsonReadChunks { reader, buffer, length: 0 }
+    }
+
+    /// replaces the current chunk with the next one, returning its length, or `0` at the end of the stream.
+    ///
+    /// interrupted reads are retried.
+    pub fn fill(&mut self) -> io::Result<usize> {
+        self.length = 0;
+
+        loop {
+            match self.reader.read(&mut self.buffer) {
+                Ok(length) => {
+                    self.length = length;
+                    return Ok(length);
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                },
+                Err(e) => {
+                    return Err(e);
+                },
+            }
+        }
+    }
+
+    /// returns the reader and the buffer.
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        (self.reader, self.buffer)
+    }
+}
+
+impl<R> JsonSource for JsonReadChunks<R> {
+    fn stream(&self) -> &[u8] {
+        &self.buffer[..self.length]
     }
 }
@@ -0,0 +1,108 @@
+use {
+    std::sync::Arc,
+    std::sync::atomic::AtomicBool,
+    std::sync::atomic::Ordering,
+    std::time::Instant,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+};
+
+
+
+/// a deadline and cancellation token for a [`JsonBuilder`](./struct.JsonBuilder.html), so that services can enforce
+/// latency budgets when reading adversarially large documents.
+///
+/// # remarks
+///
+/// they are checked every `interval` bytes - 64 KiB by default - across updates, and an update that is cancelled fails
+/// with `JsonErrorKind::Cancelled`. see [`JsonBuilder::set_cancel()`](./struct.JsonBuilder.html#method.set_cancel).
+///
+/// `wasm32-unknown-unknown` has no clock, so there the deadline is never checked.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonCancel, JsonErrorKind};
+/// # use std::sync::Arc;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// #
+/// # fn main() {
+/// #
+/// let token       = Arc::new(AtomicBool::new(false));
+/// let mut builder = JsonBuilder::new();
+///
+/// builder.set_cancel(JsonCancel::new().token(token.clone()).interval(4));
+/// builder.update(r#"{ "a": [1, 2"#).unwrap();
+///
+/// token.store(true, Ordering::Relaxed);
+///
+/// assert_eq!(builder.update_partial(r#", 3, 4, 5]"#), (3, Err(JsonErrorKind::Cancelled.into())));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonCancel {
+    deadline:  Option<Instant>,
+    token:     Option<Arc<AtomicBool>>,
+
+    // the number of bytes between checks, and the number left until the next one.
+    interval:  usize,
+    remaining: usize,
+}
+
+impl JsonCancel {
+    pub fn new() -> JsonCancel {
+        JsonCancel { deadline: None, token: None, interval: 64 * 1024, remaining: 64 * 1024 }
+    }
+
+    /// cancels updates once `deadline` has passed.
+    pub fn deadline(mut self, deadline: Instant) -> JsonCancel {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// cancels updates once `token` is set to true, such as from another thread.
+    pub fn token(mut self, token: Arc<AtomicBool>) -> JsonCancel {
+        self.token = Some(token);
+        self
+    }
+
+    /// checks the deadline and token every `interval` bytes. an `interval` of zero is treated as one.
+    pub fn interval(mut self, interval: usize) -> JsonCancel {
+        self.interval  = interval.max(1);
+        self.remaining = self.interval;
+        self
+    }
+
+    /// returns true if the deadline has passed or the token is set.
+    pub fn is_cancelled(&self) -> bool {
+        let clock    = cfg!(not(all(target_arch = "wasm32", target_os = "unknown")));
+        let deadline = clock && self.deadline.map_or(false, |x| Instant::now() >= x);
+        let token    = self.token.as_ref().map_or(false, |x| x.load(Ordering::Relaxed));
+
+        deadline || token
+    }
+
+    // counts the next run of at most `length` bytes, and returns its length - checking the deadline and token first if
+    // `interval` bytes have been counted since the last check. a run ends at the byte that is due the next check.
+    crate fn run(&mut self, length: usize) -> Result<usize, JsonError> {
+        if self.remaining == 1 {
+            if self.is_cancelled() {
+                return Err(JsonErrorKind::Cancelled.into());
+            }
+
+            self.remaining = self.interval.saturating_add(1);
+        }
+
+        let run = length.min(self.remaining - 1);
+
+        self.remaining -= run;
+        Ok(run)
+    }
+}
+
+impl Default for JsonCancel {
+    fn default() -> JsonCancel {
+        JsonCancel::new()
+    }
+}
@@ -0,0 +1,162 @@
+use {
+    std::mem,
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+};
+
+
+
+/// a json syntax validator for documents that are edited in place, such as in an editor.
+///
+/// # remarks
+///
+/// while validating, a snapshot of the verifier is kept every `interval` bytes. after an edit, `revalidate()` resumes
+/// from the last snapshot before the edit instead of from the start of the document, and stops as soon as the verifier
+/// is back in the state of a snapshot taken after the edit - so an edit to a small part of a large document is cheap to
+/// revalidate, wherever it is.
+///
+/// validation stops at the first error. every snapshot costs memory proportional to the nesting depth at that point.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonCheckpoints, JsonStatus};
+/// #
+/// # fn main() {
+/// #
+/// let mut document    = br#"{ "a": [1, 2, 3], "b": null }"#.to_vec();
+/// let mut checkpoints = JsonCheckpoints::new(8);
+///
+/// checkpoints.validate(&document[..]).unwrap();
+///
+/// // an edit at byte 23 only revalidates from byte 16.
+/// document[23] = b'x';
+/// assert_eq!(checkpoints.revalidate(&document[..], 23..24).unwrap_err().offset(), Some(23));
+///
+/// document.splice(23..27, b"true".iter().cloned());
+/// assert!(checkpoints.revalidate(&document[..], 23..27).is_ok());
+/// assert_eq!(checkpoints.status(), JsonStatus::Valid);
+///
+/// // once the array ends, the verifier is back in the state of the snapshot that was at byte 16 - and is now at byte
+/// // 17 - so the rest of the document is not validated again.
+/// document.splice(8..9, b"10".iter().cloned());
+/// assert!(checkpoints.revalidate(&document[..], 8..9).is_ok());
+/// assert_eq!(checkpoints.status(), JsonStatus::Valid);
+/// # }
+/// ```
+pub struct JsonCheckpoints {
+    interval:    usize,
+    initial:     JsonVerifier,
+    verifier:    JsonVerifier,
+
+    // the snapshots, each with the number of bytes of the last validated document applied to it, in order. the length
+    // of the last validated document, and whether it was validated to its end without an error.
+    checkpoints: Vec<(usize, JsonVerifier)>,
+    length:      usize,
+    complete:    bool,
+}
+
+impl JsonCheckpoints {
+    /// creates a validator that keeps a snapshot every `interval` bytes.
+    pub fn new(interval: usize) -> JsonCheckpoints {
+        JsonCheckpoints::with_options(interval, JsonVerifierOptions::default())
+    }
+
+    /// creates a validator with `options` that keeps a snapshot every `interval` bytes.
+    ///
+    /// an `interval` of 0 is taken as 1, a snapshot after every byte.
+    pub fn with_options(interval: usize, options: JsonVerifierOptions) -> JsonCheckpoints {
+        let verifier = JsonVerifier::with_options(options);
+
+        JsonCheckpoints {
+            interval:    interval.max(1),
+            initial:     verifier.clone(),
+            verifier,
+
+            checkpoints: vec![],
+            length:      0,
+            complete:    false,
+        }
+    }
+
+    /// the status of the document as of the last call to `validate()` or `revalidate()`.
+    pub fn status(&self) -> JsonStatus {
+        self.verifier.status()
+    }
+
+    /// validates `document` from the start.
+    pub fn validate(&mut self, document: impl JsonSource) -> Result<(), JsonError> {
+        let length = self.length;
+
+        self.revalidate(document, 0..length)
+    }
+
+    /// validates `document`, which is the last validated document with the bytes in the range `edited` replaced.
+    ///
+    /// # remarks
+    ///
+    /// `edited` is a range of the last validated document, not of `document` - an insertion is an empty range, and a
+    /// deletion is replaced with nothing.
+    pub fn revalidate(&mut self, document: impl JsonSource, edited: Range<usize>) -> Result<(), JsonError> {
+        let document = document.stream();
+
+        // the edit replaced the bytes from `start` to `before` in the last validated document with the bytes from
+        // `start` to `after` in this one.
+        let before = edited.end.min(self.length);
+        let start  = edited.start.min(before);
+        let after  = (document.len() + before).saturating_sub(self.length).max(start);
+
+        // the snapshots after the edit, moved to where their bytes are now. they can only be matched if the rest of the
+        // last validated document was validated, and so would be again.
+        let later = match self.complete {
+            true  => self.checkpoints.iter().filter(|x| x.0 >= before).cloned().collect(),
+            false => vec![],
+        };
+
+        let mut later = later.into_iter().map(|(x, y)| (x - before + after, y)).peekable();
+
+        self.checkpoints.retain(|x| x.0 <= start);
+        self.length   = document.len();
+        self.complete = false;
+
+        let (mut resumed, verifier) = match self.checkpoints.last() {
+            Some((x, y)) => (*x, y.clone()),
+            None         => (0, self.initial.clone()),
+        };
+
+        let last = mem::replace(&mut self.verifier, verifier);
+
+        for (i, &character) in document.iter().enumerate().skip(resumed) {
+            if let Err(error) = self.verifier.update(character) {
+                return Err(error.with_offset(i));
+            }
+
+            let applied = i + 1;
+
+            while later.peek().map_or(false, |x| x.0 < applied) {
+                later.next();
+            }
+
+            if later.peek().map_or(false, |x| x.0 == applied && self.verifier.resumes_as(&x.1)) {
+                self.checkpoints.extend(later);
+                self.verifier = last;
+                self.complete = true;
+
+                return Ok(());
+            }
+
+            if applied - resumed >= self.interval {
+                self.checkpoints.push((applied, self.verifier.clone()));
+                resumed = applied;
+            }
+        }
+
+        self.complete = true;
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+use {
+    std::mem,
+
+    arya::JsonError,
+    arya::JsonRepairKind,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::diagnostics,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::strings,
+};
+
+
+
+/// a rule that converts string values into another type of value, or repairs their text, as a
+/// [`JsonBuilder`](./struct.JsonBuilder.html) builds them.
+///
+/// # remarks
+///
+/// a string is only converted if its entire content is the value it would be converted to - `"42"` becomes `42`, but
+/// `" 42"` and `"42 apples"` are kept. each conversion or repair is recorded as a
+/// [`JsonRepairKind::Coercion`](./enum.JsonRepairKind.html#variant.Coercion) repair.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonCoercion, JsonCoercionKind};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.coercions.push(JsonCoercion::at("/items/*/price", JsonCoercionKind::Numbers));
+/// options.coercions.push(JsonCoercion::everywhere(JsonCoercionKind::Booleans));
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "items": [{ "id": "7", "price": "4.50", "sale": "TRUE" }] }"#).unwrap();
+///
+/// assert_eq!(builder.string().unwrap(), r#"{ "items": [{ "id": "7", "price": 4.50, "sale": true }] }"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonCoercion {
+    /// the json pointer ([rfc 6901](https://tools.ietf.org/html/rfc6901)) of the values to convert, or `None` to
+    /// convert values anywhere in the object.
+    ///
+    /// a `*` segment matches any key or array index. object keys are matched by their unescaped text, so `"a\/b"` and
+    /// `"a/b"` in the input both match the segment `a~1b`.
+    pub pointer: Option<String>,
+
+    /// the conversion to make.
+    pub kind:    JsonCoercionKind,
+}
+
+impl JsonCoercion {
+    /// creates a rule that converts values anywhere in the object.
+    pub fn everywhere(kind: JsonCoercionKind) -> JsonCoercion {
+        JsonCoercion { pointer: None, kind }
+    }
+
+    /// creates a rule that converts the values at `pointer`.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonBuilderOptions, JsonCoercion, JsonCoercionKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut options = JsonBuilderOptions::default();
+    ///
+    /// options.coercions.push(JsonCoercion::at("/a~1b", JsonCoercionKind::Numbers));
+    ///
+    /// let mut builder = JsonBuilder::with_options(options);
+    ///
+    /// builder.update(r#"{ "a\/b": "1", "a/b": "2", "a~1b": "3" }"#).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{ "a\/b": 1, "a/b": 2, "a~1b": "3" }"#);
+    /// # }
+    /// ```
+    pub fn at(pointer: impl Into<String>, kind: JsonCoercionKind) -> JsonCoercion {
+        JsonCoercion { pointer: Some(pointer.into()), kind }
+    }
+
+    // true if this rule applies to the value at the json pointer `path`.
+    fn matches(&self, path: &str) -> bool {
+        match &self.pointer {
+            Some(pointer) => matches(pointer, path),
+            None          => true,
+        }
+    }
+}
+
+
+
+// true if the json pointer `path` matches `pointer`, in which a `*` segment matches any key or array index.
+crate fn matches(pointer: &str, path: &str) -> bool {
+    let mut expected = pointer.split('/');
+    let mut actual   = path.split('/');
+
+    loop {
+        match (expected.next(), actual.next()) {
+            (Some(x), Some(y)) if x == "*" || x == y => {},
+            (None, None)                             => return true,
+            _                                        => return false,
+        }
+    }
+}
+
+
+
+/// the conversion made by a [`JsonCoercion`](./struct.JsonCoercion.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonCoercionKind {
+    /// a string containing a json number becomes that number, so that `"42"` becomes `42`.
+    Numbers,
+
+    /// a string containing `true` or `false`, in any case, becomes that boolean, so that `"True"` becomes `true`.
+    Booleans,
+
+    /// a string whose utf8 was mistakenly decoded as windows-1252 or latin-1 and encoded as utf8 again - mojibake such
+    /// as `"cafÃ©"` or `"donâ€™t"` - is decoded back, so that it becomes `"café"` or `"don’t"`.
+    ///
+    /// # remarks
+    ///
+    /// this is a heuristic. a string is only repaired if every non-ascii character in it maps back to a single byte,
+    /// and those bytes are valid utf8 - which text that was not double-encoded almost never is, so `"café"` is kept.
+    /// text that was double-encoded more than once is repaired until it no longer is. escape sequences are left as
+    /// they are.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonBuilderOptions, JsonCoercion, JsonCoercionKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut options = JsonBuilderOptions::default();
+    ///
+    /// options.coercions.push(JsonCoercion::everywhere(JsonCoercionKind::Mojibake));
+    ///
+    /// let mut builder = JsonBuilder::with_options(options);
+    ///
+    /// builder.update(r#"{ "city": "MÃ¼nchen", "note": "donâ€™t", "cafe": "café" }"#).unwrap();
+    ///
+    /// assert_eq!(builder.repairs().len(), 2);
+    /// assert_eq!(builder.string().unwrap(), r#"{ "city": "München", "note": "don’t", "cafe": "café" }"#);
+    /// # }
+    /// ```
+    Mojibake,
+}
+
+
+
+/// how numbers that lose precision when parsed as an `i64` or `f64` are handled by a
+/// [`JsonBuilder`](./struct.JsonBuilder.html).
+///
+/// # remarks
+///
+/// an integer is too big if it is outside the range of an `i64`. any other number is too big if the nearest `f64`
+/// has a different decimal value, such as `0.12345678901234567890` or `1e400`.
+///
+/// the json pointers of the numbers that were found can be inspected with
+/// [`JsonBuilder::big_numbers()`](./struct.JsonBuilder.html#method.big_numbers).
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBigNumbers, JsonBuilder, JsonBuilderOptions};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.big_numbers = JsonBigNumbers::String;
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "id": 12345678901234567890, "price": 4.5 }"#).unwrap();
+///
+/// assert_eq!(builder.big_numbers(), ["/id"]);
+/// assert_eq!(builder.string().unwrap(), r#"{ "id": "12345678901234567890", "price": 4.5 }"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonBigNumbers {
+    /// they are not looked for.
+    #[default]
+    Ignore,
+
+    /// they are left as they are, but their paths are reported.
+    Report,
+
+    /// they are replaced with a string containing the number, such as `"12345678901234567890"`, and their paths are
+    /// reported. each replacement is recorded as a
+    /// [`JsonRepairKind::BigNumber`](./enum.JsonRepairKind.html#variant.BigNumber) repair.
+    ///
+    /// a number at the very end of an incomplete object is not replaced, since it may still be missing digits.
+    String,
+}
+
+
+
+// follows the path of every value in the output of a `JsonBuilder`, rewriting string values that a rule converts and
+// finding numbers that are too big - and, for a builder with a shape, knowing the path of the value it ends at.
+//
+// it reads the builder's output rather than its input, so it never has to know about repairs: by the time a byte is
+// read here, it is final.
+crate struct Coercer {
+    rules:  Vec<JsonCoercion>,
+    big:    JsonBigNumbers,
+    found:  Vec<String>,
+    reader: EventReader,
+    read:   usize,
+
+    // one frame per open container: the length of its path, and the number of values seen. then the json pointer to
+    // the innermost container, and the key of the value currently being read.
+    frames: Vec<(usize, usize)>,
+    path:   String,
+    key:    Option<String>,
+}
+
+impl Coercer {
+    // creates a coercer, if there are any rules, big numbers are looked for, or `paths` are needed.
+    crate fn new(rules: Vec<JsonCoercion>, big: JsonBigNumbers, paths: bool) -> Option<Coercer> {
+        match rules.is_empty() && big == JsonBigNumbers::Ignore && !paths {
+            true  => None,
+            false => Some(Coercer {
+                rules,
+                big,
+                found:  vec![],
+                reader: EventReader::new(JsonVerifier::new()),
+                read:   0,
+
+                frames: vec![],
+                path:   String::new(),
+                key:    None,
+            }),
+        }
+    }
+
+    // returns the json pointers of the numbers found to be too big so far.
+    crate fn big_numbers(&self) -> &[String] {
+        &self.found
+    }
+
+    // returns the heap memory held by this coercer: its rules, the big numbers it found, and the paths it tracks.
+    crate fn memory_usage(&self) -> usize {
+        let rules = self.rules.iter().map(|x| x.pointer.as_ref().map_or(0, String::capacity)).sum::<usize>();
+        let found = self.found.iter().map(String::capacity).sum::<usize>();
+
+        self.rules.capacity() * mem::size_of::<JsonCoercion>() + rules
+            + self.found.capacity() * mem::size_of::<String>() + found
+            + self.frames.capacity() * mem::size_of::<(usize, usize)>()
+            + self.path.capacity()
+            + self.key.as_ref().map_or(0, String::capacity)
+            + self.reader.memory_usage()
+    }
+
+    // returns the json pointer of the value that `data` - the builder's output, which this coercer has read part of -
+    // ends part way through, or just before. only the bytes not read yet are read again, by a copy of its reader.
+    crate fn next_path(&self, data: &[u8]) -> Result<String, JsonError> {
+        let mut reader = self.reader.clone();
+        let mut frames = self.frames.clone();
+        let mut path   = self.path.clone();
+        let mut key    = self.key.clone();
+
+        for &character in &data[self.read.min(data.len())..] {
+            reader.update(character, |event| {
+                match event {
+                    Event::Begin(_, _) => {
+                        let length = path.len();
+
+                        push_value(&mut frames, &mut path, &mut key);
+                        frames.push((length, 0));
+                    },
+                    Event::End(_) => {
+                        if let Some((length, _)) = frames.pop() {
+                            path.truncate(length);
+                        }
+                    },
+                    Event::Key(_, text) => {
+                        key = Some(strings::decode_key(text));
+                    },
+                    Event::Scalar(_, _) => {
+                        let length = path.len();
+
+                        push_value(&mut frames, &mut path, &mut key);
+                        path.truncate(length);
+                    },
+                }
+            })?;
+        }
+
+        Ok(diagnostics::next_path(reader.verifier(), frames, path, key))
+    }
+
+    crate fn reset(&mut self) {
+        self.reader.reset();
+        self.found.clear();
+        self.frames.clear();
+        self.path.clear();
+
+        self.read = 0;
+        self.key  = None;
+    }
+
+    // accounts for `separator` having been inserted at `offset`, in front of whitespace that may already have been
+    // read. whitespace after a value leaves nothing to undo, so the separator can be read after it instead.
+    crate fn insert(&mut self, offset: usize, separator: u8) -> Result<(), JsonError> {
+        if self.read > offset {
+            self.reader.update(separator, |_| {})?;
+            self.read += 1;
+        }
+
+        Ok(())
+    }
+
+    // reads the bytes of `data` that have not been read yet, rewriting in place every value among them that is
+    // converted. `rewritten` receives the kind of each rewrite, its offset, and the lengths of the value before and
+    // after it. fails if the reader rejects a byte, which only output the builder's own verifier rejected can cause.
+    crate fn update(
+        &mut self,
+        data:          &mut Vec<u8>,
+        mut rewritten: impl FnMut(JsonRepairKind, usize, usize, usize)) -> Result<(), JsonError>
+    {
+        let Coercer { rules, big, found: numbers, reader, read, frames, path, key } = self;
+
+        while *read < data.len() {
+            let character = data[*read];
+            let mut found = None;
+
+            // a minus sign at the end of the output may still be undone to read `-Infinity` as a literal, so it is left
+            // until something follows it.
+            if character == b'-' && *read + 1 == data.len() && !reader.verifier().in_string() {
+                break;
+            }
+
+            let result = reader.update(character, |event| {
+                match event {
+                    Event::Begin(_, _) => {
+                        let length = path.len();
+
+                        push_value(frames, path, key);
+                        frames.push((length, 0));
+                    },
+                    Event::End(_) => {
+                        if let Some((length, _)) = frames.pop() {
+                            path.truncate(length);
+                        }
+                    },
+                    Event::Key(_, text) => {
+                        *key = Some(strings::decode_key(text));
+                    },
+                    Event::Scalar(kind, range) => {
+                        let length = path.len();
+
+                        push_value(frames, path, key);
+
+                        match kind {
+                            JsonValueKind::String if rules.iter().any(|x| x.matches(path)) => {
+                                found = Some((kind, range.len(), path.clone()));
+                            },
+                            JsonValueKind::Number if *big != JsonBigNumbers::Ignore => {
+                                found = Some((kind, range.len(), path.clone()));
+                            },
+                            _ => {},
+                        }
+
+                        path.truncate(length);
+                    },
+                }
+            });
+
+            result?;
+            *read += 1;
+
+            match found {
+                Some((JsonValueKind::String, length, path)) => {
+                    let start       = *read - length;
+                    let content     = &data[start + 1..*read - 1];
+                    let replacement = rules.iter().filter(|x| x.matches(&path)).find_map(|x| convert(x.kind, content));
+
+                    if let Some(replacement) = replacement {
+                        rewritten(JsonRepairKind::Coercion, start, length, replacement.len());
+
+                        data.splice(start..*read, replacement.iter().cloned());
+                        *read = start + replacement.len();
+                    }
+                },
+                // a number is only known to be complete once the character after it is read.
+                Some((_, length, path)) => {
+                    let end   = *read - 1;
+                    let start = end - length;
+
+                    if is_big(&data[start..end]) {
+                        numbers.push(path);
+
+                        if *big == JsonBigNumbers::String {
+                            rewritten(JsonRepairKind::BigNumber, start, length, length + 2);
+
+                            data.insert(end, b'"');
+                            data.insert(start, b'"');
+                            *read += 2;
+                        }
+                    }
+                },
+                None => {},
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+
+// returns the value the string `content` is converted to by `kind`, or `None` if it is not converted.
+fn convert(kind: JsonCoercionKind, content: &[u8]) -> Option<Vec<u8>> {
+    match kind {
+        JsonCoercionKind::Numbers => {
+            match is_number(content) {
+                true  => Some(content.to_vec()),
+                false => None,
+            }
+        },
+        JsonCoercionKind::Booleans => {
+            match () {
+                _ if content.eq_ignore_ascii_case(b"true")  => Some(b"true".to_vec()),
+                _ if content.eq_ignore_ascii_case(b"false") => Some(b"false".to_vec()),
+                _                                          => None,
+            }
+        },
+        JsonCoercionKind::Mojibake => {
+            let mut text = mojibake(content)?;
+
+            while let Some(x) = mojibake(&text) {
+                text = x;
+            }
+
+            Some([&b"\""[..], &text[..], &b"\""[..]].concat())
+        },
+    }
+}
+
+// decodes the mojibake `text` once: each of its characters is replaced by the byte windows-1252 encodes it as. returns
+// `None` if `text` is all ascii, if a character has no such byte, or if the bytes are not valid utf8.
+fn mojibake(text: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(text).ok()?;
+
+    if text.is_ascii() {
+        return None;
+    }
+
+    let bytes = text.chars().map(windows_1252).collect::<Option<Vec<u8>>>()?;
+
+    match std::str::from_utf8(&bytes) {
+        Ok(_)  => Some(bytes),
+        Err(_) => None,
+    }
+}
+
+// the characters that windows-1252 encodes as the bytes `0x80` to `0x9f`. the five bytes it leaves undefined are
+// decoded as the latin-1 control character with the same value.
+const WINDOWS_1252: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+// returns the byte that windows-1252 encodes `character` as, falling back to latin-1.
+fn windows_1252(character: char) -> Option<u8> {
+    match character as u32 {
+        0x00..=0xff => Some(character as u32 as u8),
+        _           => WINDOWS_1252.iter().position(|x| *x == character).map(|i| 0x80 + i as u8),
+    }
+}
+
+// true if the json number `text` loses precision when parsed as an `i64` (if it is an integer) or an `f64`.
+crate fn is_big(text: &[u8]) -> bool {
+    let text = match std::str::from_utf8(text) {
+        Ok(x)  => x,
+        Err(_) => return true,
+    };
+
+    if !text.contains(['.', 'e', 'E']) {
+        return text.parse::<i64>().is_err();
+    }
+
+    match text.parse::<f64>() {
+        Ok(x) if x.is_finite() => decimal(text) != decimal(&format!("{:e}", x)),
+        _                      => true,
+    }
+}
+
+// returns the significant digits of the json number `text` without leading or trailing zeros, and the exponent that
+// places the decimal point before the first of them - or `None` if the exponent does not fit in an `i64`.
+fn decimal(text: &str) -> Option<(Vec<u8>, i64)> {
+    let text = text.trim_start_matches('-');
+
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], text[i + 1..].trim_start_matches('+').parse::<i64>().ok()?),
+        None    => (text, 0),
+    };
+
+    let (integer, fraction) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None    => (mantissa, ""),
+    };
+
+    let digits  = integer.bytes().chain(fraction.bytes()).collect::<Vec<_>>();
+    let leading = digits.iter().take_while(|x| **x == b'0').count();
+    let digits  = digits[leading..].to_vec();
+    let length  = digits.len() - digits.iter().rev().take_while(|x| **x == b'0').count();
+
+    match length {
+        0 => Some((vec![], 0)),
+        _ => Some((digits[..length].to_vec(), exponent.checked_add(integer.len() as i64 - leading as i64)?)),
+    }
+}
+
+// true if `text` is exactly one json number.
+fn is_number(text: &[u8]) -> bool {
+    let digits = |i: usize| text[i..].iter().take_while(|x| x.is_ascii_digit()).count();
+
+    let mut i = match text.first() {
+        Some(b'-') => 1,
+        _          => 0,
+    };
+
+    match (text.get(i), digits(i)) {
+        (Some(b'0'), 1)        => i += 1,
+        (Some(b'1'..=b'9'), n) => i += n,
+        _                      => return false,
+    }
+
+    if text.get(i) == Some(&b'.') {
+        match digits(i + 1) {
+            0 => return false,
+            n => i += n + 1,
+        }
+    }
+
+    if let Some(b'e') | Some(b'E') = text.get(i) {
+        i += 1;
+
+        if let Some(b'+') | Some(b'-') = text.get(i) {
+            i += 1;
+        }
+
+        match digits(i) {
+            0 => return false,
+            n => i += n,
+        }
+    }
+
+    i == text.len()
+}
@@ -0,0 +1,245 @@
+use {
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::build,
+    arya::coerce,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::strings,
+};
+
+
+
+/// a member that a [`JsonBuilder`](./struct.JsonBuilder.html) adds to a completed json object if it is missing, so
+/// that strict deserialization of a truncated payload does not fail on absent fields.
+///
+/// # remarks
+///
+/// `pointer` is the json pointer ([rfc 6901](https://tools.ietf.org/html/rfc6901)) of the member: its last segment
+/// is the key, and the rest is the object it belongs to. a `*` segment matches any key or array index, so that
+/// `/items/*/quantity` adds a `quantity` to every object in `items`. object keys are matched once unescaped, so a
+/// `"st\u0061tus"` member is not added again as `"status"`, and the key is escaped when it is added.
+///
+/// missing members are added after the last member of their object when it is completed - by `completed_bytes()`,
+/// `completed_string()` or `completed_snapshot()` - and only to objects that exist. a sink is written nothing until
+/// `completed_bytes()` has added them.
+/// `value` must be a single json value, which [`JsonOptionsBuilder`](./struct.JsonOptionsBuilder.html) checks.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonDefault};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.defaults.push(JsonDefault::at("/status", r#""pending""#));
+/// options.defaults.push(JsonDefault::at("/items/*/quantity", "1"));
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "items": [{ "id": 1, "quantity": 4 }, { "id": 2 }, { "i"#).unwrap();
+///
+/// assert_eq!(
+///     builder.completed_string().unwrap(),
+///     r#"{ "items": [{ "id": 1, "quantity": 4 }, { "id": 2,"quantity":1 }, { "quantity":1}],"status":"pending"}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonDefault {
+    /// the json pointer of the member.
+    pub pointer: String,
+
+    /// the json value the member is given.
+    pub value:   String,
+}
+
+impl JsonDefault {
+    pub fn at(pointer: impl Into<String>, value: impl Into<String>) -> JsonDefault {
+        JsonDefault { pointer: pointer.into(), value: value.into() }
+    }
+
+    // returns the json pointer of the object this member belongs to, and its unescaped key.
+    fn split(&self) -> (&str, String) {
+        let (parent, key) = self.pointer.split_at(self.pointer.rfind('/').unwrap_or(0));
+        let key           = key.trim_start_matches('/').replace("~1", "/").replace("~0", "~");
+
+        (parent, key)
+    }
+
+    // true if this default can be used: its pointer names a member - every `~` in it is escaped as `~0` or `~1`, and
+    // its last segment is a key rather than a `*` - and its value is a single json value.
+    crate fn is_valid(&self) -> bool {
+        let mut verifier = JsonVerifier::new();
+
+        let segment = &self.pointer[self.pointer.rfind('/').map_or(0, |i| i + 1)..];
+        let escapes = self.pointer.split('~').skip(1).all(|x| x.starts_with('0') || x.starts_with('1'));
+
+        let pointer = self.pointer.starts_with('/') && escapes && segment != "*";
+        let value   = verifier.update(b'[').is_ok() && verifier.splice_value(self.value.as_bytes()).is_ok();
+
+        pointer && value
+    }
+}
+
+
+
+// returns a default for every object member in the complete json object `template`, given the kind and range of each
+// of its values in order, so that completion adds whichever of them are missing.
+crate fn from_template(
+    template: &[u8],
+    values:   &[(String, JsonValueKind, Range<usize>)]) -> Result<Vec<JsonDefault>, JsonError>
+{
+    let mut defaults = vec![];
+
+    // the kind and end of each container that the current value is inside, innermost last.
+    let mut parents: Vec<(JsonValueKind, usize)> = vec![];
+
+    for (path, kind, range) in values {
+        while parents.last().map_or(false, |(_, end)| *end <= range.start) {
+            parents.pop();
+        }
+
+        if let Some((JsonValueKind::Object, _)) = parents.last() {
+            let value = String::from_utf8(template[range.clone()].to_vec()).map_err(|_| JsonErrorKind::Utf8)?;
+
+            defaults.push(JsonDefault::at(path.clone(), value));
+        }
+
+        if *kind == JsonValueKind::Object || *kind == JsonValueKind::Array {
+            parents.push((*kind, range.end));
+        }
+    }
+
+    Ok(defaults)
+}
+
+
+
+// adds the members in `defaults` that are missing from the objects in the complete json object `data`. `data` is
+// returned as it is if none are missing.
+crate fn inject(data: Vec<u8>, defaults: &[JsonDefault]) -> Result<Vec<u8>, JsonError> {
+    let insertions = insertions(&data, defaults)?;
+
+    if insertions.is_empty() {
+        return Ok(data);
+    }
+
+    let mut output = Vec::with_capacity(data.len() + insertions.iter().map(|(_, x)| x.len()).sum::<usize>());
+    let mut start  = 0;
+
+    for (offset, member) in insertions {
+        output.extend_from_slice(&data[start..offset]);
+        output.extend_from_slice(&member);
+
+        start = offset;
+    }
+
+    output.extend_from_slice(&data[start..]);
+    Ok(output)
+}
+
+
+
+// returns the members in `defaults` that are missing from the objects in the complete json object `data`, and the
+// offsets in `data` they are inserted at, in order.
+crate fn insertions(data: &[u8], defaults: &[JsonDefault]) -> Result<Vec<(usize, Vec<u8>)>, JsonError> {
+    // each default's parent and unescaped key, its key as a json string, and its value.
+    let defaults = defaults
+        .iter()
+        .map(|default| {
+            let (parent, name) = default.split();
+            let mut quoted     = vec![];
+
+            build::write_string(&name, |x| {
+                quoted.extend_from_slice(x);
+                Ok(())
+            })?;
+
+            Ok(((parent, name), quoted, default.value.as_bytes()))
+        })
+        .collect::<Result<Vec<_>, JsonError>>()?;
+
+    let mut reader     = EventReader::new(JsonVerifier::new());
+    let mut insertions = vec![];
+
+    // one frame per open container: the length of its path, and the number of values seen. then the json pointer to
+    // the innermost container, and the key of the value currently being read. for each open container, whether it
+    // is an object, the keys seen in it, and the end of its last value.
+    let mut frames = vec![];
+    let mut path   = String::new();
+    let mut key    = None;
+    let mut keys   = vec![];
+
+    for &character in data {
+        reader.update(character, |event| {
+            match event {
+                Event::Begin(kind, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    frames.push((length, 0));
+                    keys.push((kind == JsonValueKind::Object, vec![], None));
+                },
+                Event::End(offset) => {
+                    let (object, seen, last) = keys.pop().unwrap_or_default();
+                    let mut members          = frames.last().map(|(_, count)| *count).unwrap_or(0);
+
+                    if object {
+                        for ((parent, name), quoted, value) in &defaults {
+                            if coerce::matches(parent, &path) && !seen.contains(name) {
+                                let mut member = vec![];
+
+                                if members > 0 {
+                                    member.push(b',');
+                                }
+
+                                member.extend_from_slice(quoted);
+                                member.push(b':');
+                                member.extend_from_slice(value);
+
+                                insertions.push((last.unwrap_or(offset), member));
+                                members += 1;
+                            }
+                        }
+                    }
+
+                    if let Some((length, _)) = frames.pop() {
+                        path.truncate(length);
+                    }
+
+                    if let Some((_, _, last)) = keys.last_mut() {
+                        *last = Some(offset + 1);
+                    }
+                },
+                Event::Key(_, text) => {
+                    let text = strings::decode_key(text);
+
+                    if let Some((_, seen, _)) = keys.last_mut() {
+                        seen.push(text.clone());
+                    }
+
+                    key = Some(text);
+                },
+                Event::Scalar(_, range) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    path.truncate(length);
+
+                    if let Some((_, _, last)) = keys.last_mut() {
+                        *last = Some(range.end);
+                    }
+                },
+            }
+        })?;
+    }
+
+    Ok(insertions)
+}
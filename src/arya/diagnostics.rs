@@ -0,0 +1,484 @@
+use {
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::TokenSink,
+    arya::build,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::strings,
+};
+
+#[cfg(feature = "metrics")]
+use {
+    arya::metrics,
+};
+
+#[cfg(feature = "rayon")]
+use {
+    rayon::iter::IntoParallelIterator,
+    rayon::iter::ParallelIterator,
+};
+
+
+
+impl JsonError {
+    /// renders this error as an annotated snippet of `source`, at the offset it occurred at - or at the start of
+    /// `source` if that is not known. see [`annotate()`](./fn.annotate.html).
+    pub fn annotate(&self, source: impl JsonSource) -> String {
+        annotate(source, self.offset().unwrap_or(0), &self.to_string())
+    }
+
+    /// renders this error as a json object on a single line, for logging and alerting pipelines.
+    ///
+    /// # remarks
+    ///
+    /// `offset` is only written if it is known. `found`, `expected` and `path` are only written for errors from
+    /// [`validate_with_context()`](./fn.validate_with_context.html), and `found` is the rejected byte as a number, or
+    /// `null` if the input stream ended too early.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonError, JsonErrorKind, validate_with_context};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let error = JsonError::at(JsonErrorKind::Invalid, 7);
+    ///
+    /// assert_eq!(error.to_json(), r#"{"error":"invalid","message":"the input stream is not valid json","offset":7}"#);
+    ///
+    /// let error = validate_with_context(r#"{ "a": [1, 2 x] }"#).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     error.to_json(),
+    ///     concat!(
+    ///         r#"{"error":"invalid","message":"the input stream is not valid json","offset":13,"found":120,"#,
+    ///         r#""expected":[",","]"],"path":"/a/2"}"#));
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut members = vec![
+            ("error",   json_string(self.name())),
+            ("message", json_string(&self.kind().to_string())),
+        ];
+
+        if let Some(offset) = self.offset() {
+            members.push(("offset", offset.to_string()));
+        }
+
+        if let Some(path) = self.path() {
+            let expected = self.expected().iter().map(|x| json_string(x)).collect::<Vec<_>>();
+
+            members.push(("found",    self.found().map_or("null".to_string(), |x| x.to_string())));
+            members.push(("expected", format!("[{}]", expected.join(","))));
+            members.push(("path",     json_string(path)));
+        }
+
+        json_object(&members)
+    }
+}
+
+
+
+/// validates `source`, stopping at the first error and describing the context it occurred in.
+///
+/// # remarks
+///
+/// this follows the path of every value to report where an error occurred, so it is slower than
+/// [`JsonVerifier`](./struct.JsonVerifier.html) - use it to explain an error once one has been found.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, validate_with_context};
+/// #
+/// # fn main() {
+/// #
+/// let error = validate_with_context(r#"{ "a": [1, 2 x] }"#).unwrap_err();
+///
+/// assert_eq!(error.kind(), JsonErrorKind::Invalid);
+/// assert_eq!((error.offset(), error.found(), error.path()), (Some(13), Some(b'x'), Some("/a/2")));
+/// assert_eq!(error.expected(), [",", "]"]);
+///
+/// assert_eq!(
+///     error.to_string(),
+///     "the input stream is not valid json (at byte 13, in /a/2): expected `,` or `]`, found `x`");
+///
+/// // keys are unescaped before they are written into the path.
+/// let error = validate_with_context(r#"{ "a\u002fb": x }"#).unwrap_err();
+///
+/// assert_eq!(error.path(), Some("/a~1b"));
+/// # }
+/// ```
+pub fn validate_with_context(source: impl JsonSource) -> Result<(), JsonError> {
+    let source     = source.stream();
+    let mut reader = EventReader::new(JsonVerifier::new());
+
+    // one frame per open container: the length of its path, and the number of values seen. then the json pointer to
+    // the innermost container, and the key of the value currently being read.
+    let mut frames = vec![];
+    let mut path   = String::new();
+    let mut key    = None;
+
+    for (offset, &character) in source.iter().enumerate() {
+        let expected = reader.verifier().expected();
+        let result   = reader.update(character, |event| {
+            match event {
+                Event::Begin(_, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    frames.push((length, 0));
+                },
+                Event::End(_) => {
+                    if let Some((length, _)) = frames.pop() {
+                        path.truncate(length);
+                    }
+                },
+                Event::Key(_, text) => {
+                    key = Some(strings::decode_key(text));
+                },
+                Event::Scalar(_, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    path.truncate(length);
+                },
+            }
+        });
+
+        if let Err(error) = result {
+            let expected = match error.kind() {
+                JsonErrorKind::Invalid => expected,
+                _                      => vec![],
+            };
+
+            let path = next_path(reader.verifier(), frames, path, key);
+
+            return Err(error.with_context(offset, Some(character), expected, path));
+        }
+    }
+
+    match reader.verifier().status() {
+        JsonStatus::Valid    => Ok(()),
+        JsonStatus::Continue => {
+            let expected = reader.verifier().expected();
+            let path     = next_path(reader.verifier(), frames, path, key);
+
+            Err(JsonError::new(JsonErrorKind::Invalid).with_context(source.len(), None, expected, path))
+        },
+    }
+}
+
+// returns the json pointer of the value that is about to be read, or that is being read. inside an object, that is
+// only known once its key has been read - until then, it is the json pointer of the object. `key` is the unescaped key
+// of that value, as `strings::decode_key()` returns it.
+crate fn next_path(
+    verifier:   &JsonVerifier<impl TokenSink>,
+    mut frames: Vec<(usize, usize)>,
+    mut path:   String,
+    mut key:    Option<String>) -> String
+{
+    let object = verifier.in_key() || verifier.in_object_value();
+
+    if key.is_some() || !object {
+        push_value(&mut frames, &mut path, &mut key);
+    }
+
+    path
+}
+
+
+
+/// validates `source`, collecting every error instead of stopping at the first one.
+///
+/// # remarks
+///
+/// each rejected byte is skipped, and validation continues as if it had never been there. if `source` ends before
+/// the json object is complete, a final `JsonErrorKind::Invalid` is reported at the end of `source`.
+///
+/// skipping a byte is not always the right recovery, so one mistake can cause several errors to be reported.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonError, JsonErrorKind, validate_all};
+/// #
+/// # fn main() {
+/// #
+/// let errors = validate_all(r#"{ "a": 1,, "b": x2 "#).unwrap_err();
+///
+/// assert_eq!(errors, vec![
+///     JsonError::at(JsonErrorKind::Invalid, 9),
+///     JsonError::at(JsonErrorKind::Invalid, 16),
+///     JsonError::at(JsonErrorKind::Invalid, 19),
+/// ]);
+/// # }
+/// ```
+pub fn validate_all(source: impl JsonSource) -> Result<(), Vec<JsonError>> {
+    validate_all_with_options(JsonVerifierOptions::default(), source)
+}
+
+/// validates `source` like [`validate_all()`](./fn.validate_all.html), with the specified options.
+pub fn validate_all_with_options(
+    options: JsonVerifierOptions,
+    source:  impl JsonSource) -> Result<(), Vec<JsonError>>
+{
+    let source       = source.stream();
+    let mut verifier = JsonVerifier::with_options(options);
+    let mut errors   = vec![];
+
+    for (offset, &character) in source.iter().enumerate() {
+        if let Err(error) = verifier.update(character) {
+            errors.push(error.with_offset(offset));
+        }
+    }
+
+    if verifier.status() == JsonStatus::Continue {
+        errors.push(JsonError::at(JsonErrorKind::Invalid, source.len()));
+    }
+
+    #[cfg(feature = "metrics")]
+    metrics::record(|x| {
+        x.processed(source.len());
+
+        match errors.first() {
+            Some(e) => x.failed(e.kind()),
+            None    => x.validated(),
+        }
+    });
+
+    match errors.is_empty() {
+        true  => Ok(()),
+        false => Err(errors),
+    }
+}
+
+
+
+/// validates a json object followed by padding up to the end of `source`, such as a fixed-size record read from block
+/// storage, and returns the length of the json object and any whitespace after it.
+///
+/// # remarks
+///
+/// the padding begins with the first `pad` byte after the json object is complete - usually `0` or `b' '` - and every
+/// byte after it must also be `pad`. there may be no padding at all. fails at the first byte that is neither part of
+/// the json object nor padding, or at the end of `source` if the json object is incomplete.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonError, JsonErrorKind, validate_padded};
+/// #
+/// # fn main() {
+/// #
+/// assert_eq!(validate_padded(&b"{ \"a\": 1 }\0\0\0\0"[..], 0), Ok(10));
+/// assert_eq!(validate_padded(&b"[1, 2]      "[..], b' '), Ok(6));
+///
+/// assert_eq!(
+///     validate_padded(&b"{ \"a\": 1 }\0\0x\0"[..], 0),
+///     Err(JsonError::at(JsonErrorKind::Invalid, 12)));
+/// # }
+/// ```
+pub fn validate_padded(source: impl JsonSource, pad: u8) -> Result<usize, JsonError> {
+    let source       = source.stream();
+    let mut verifier = JsonVerifier::new();
+    let mut length   = source.len();
+    let mut result   = Ok(());
+
+    for (offset, &character) in source.iter().enumerate() {
+        if character == pad && verifier.status() == JsonStatus::Valid {
+            length = offset;
+            break;
+        }
+
+        if let Err(error) = verifier.update(character) {
+            result = Err(error.with_offset(offset));
+            break;
+        }
+    }
+
+    let result = result.and_then(|()| {
+        match (verifier.status(), source[length..].iter().position(|x| *x != pad)) {
+            (JsonStatus::Continue, _)    => Err(JsonError::at(JsonErrorKind::Invalid, source.len())),
+            (JsonStatus::Valid, Some(x)) => Err(JsonError::at(JsonErrorKind::Invalid, length + x)),
+            (JsonStatus::Valid, None)    => Ok(length),
+        }
+    });
+
+    #[cfg(feature = "metrics")]
+    metrics::record(|x| {
+        x.processed(source.len());
+
+        match &result {
+            Ok(_)      => x.validated(),
+            Err(error) => x.failed(error.kind()),
+        }
+    });
+
+    result
+}
+
+/// validates each document in `documents`, returning the first error in each one.
+///
+/// # remarks
+///
+/// a single verifier is reused for every document, so validating many small documents does not allocate for each one.
+/// a document that ends before its json object is complete is reported like in
+/// [`validate_all()`](./fn.validate_all.html).
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonError, JsonErrorKind, validate_batch};
+/// #
+/// # fn main() {
+/// #
+/// let documents: Vec<&[u8]> = vec![br#"{ "a": 1 }"#, br#"[1, 2"#, br#"{ "a": x }"#];
+///
+/// assert_eq!(validate_batch(documents), vec![
+///     Ok(()),
+///     Err(JsonError::at(JsonErrorKind::Invalid, 5)),
+///     Err(JsonError::at(JsonErrorKind::Invalid, 7)),
+/// ]);
+/// # }
+/// ```
+pub fn validate_batch<'a>(documents: impl IntoIterator<Item = &'a [u8]>) -> Vec<Result<(), JsonError>> {
+    let mut verifier = JsonVerifier::new();
+
+    documents.into_iter().map(|x| validate_with(&mut verifier, x)).collect()
+}
+
+/// validates each document in `documents` like [`validate_batch()`](./fn.validate_batch.html), in parallel.
+///
+/// # remarks
+///
+/// requires the `rayon` feature. each worker thread reuses its own verifier, and the results are returned in the same
+/// order as `documents`.
+#[cfg(feature = "rayon")]
+pub fn validate_batch_parallel<'a>(
+    documents: impl IntoParallelIterator<Item = &'a [u8]>) -> Vec<Result<(), JsonError>>
+{
+    documents
+        .into_par_iter()
+        .map_init(JsonVerifier::new, validate_with)
+        .collect()
+}
+
+// resets `verifier` and validates `document` with it, stopping at the first error.
+crate fn validate_with(verifier: &mut JsonVerifier, document: &[u8]) -> Result<(), JsonError> {
+    verifier.reset();
+
+    let result = document
+        .iter()
+        .enumerate()
+        .try_for_each(|(offset, &character)| verifier.update(character).map_err(|error| error.with_offset(offset)))
+        .and_then(|()| {
+            match verifier.status() {
+                JsonStatus::Valid    => Ok(()),
+                JsonStatus::Continue => Err(JsonError::at(JsonErrorKind::Invalid, document.len())),
+            }
+        });
+
+    #[cfg(feature = "metrics")]
+    metrics::record(|x| {
+        x.processed(document.len());
+
+        match &result {
+            Ok(())     => x.validated(),
+            Err(error) => x.failed(error.kind()),
+        }
+    });
+
+    result
+}
+
+
+/// returns the one-based line and column of byte `offset` in `source`. columns are counted in characters.
+pub fn line_column(source: impl JsonSource, offset: usize) -> (usize, usize) {
+    let source = source.stream();
+    let offset = offset.min(source.len());
+    let start  = source[..offset].iter().rposition(|x| *x == b'\n').map_or(0, |x| x + 1);
+    let line   = source[..start].iter().filter(|x| **x == b'\n').count();
+    let column = String::from_utf8_lossy(&source[start..offset]).chars().count();
+
+    (line + 1, column + 1)
+}
+
+// renders `members` - keys, and the json text of their values - as a compact json object.
+crate fn json_object(members: &[(&str, String)]) -> String {
+    let members = members.iter().map(|(key, value)| format!("{}:{}", json_string(key), value)).collect::<Vec<_>>();
+
+    format!("{{{}}}", members.join(","))
+}
+
+// renders `value` as a quoted json string.
+crate fn json_string(value: &str) -> String {
+    let mut output = vec![];
+
+    let _ = build::write_string(value, |x| {
+        output.extend_from_slice(x);
+        Ok(())
+    });
+
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+/// renders `message` as a rustc-style annotated snippet of `source`, with a caret under byte `offset` and up to two
+/// lines of context on either side.
+///
+/// # examples
+///
+/// ```
+/// # use arya::annotate;
+/// #
+/// # fn main() {
+/// #
+/// let source = "{\n  \"a\": 1,\n  \"b\": x\n}";
+///
+/// assert_eq!(annotate(source, 19, "unexpected character"), [
+///     "error: unexpected character",
+///     " --> line 3, column 8",
+///     "  |",
+///     "1 | {",
+///     "2 |   \"a\": 1,",
+///     "3 |   \"b\": x",
+///     "  |        ^",
+///     "4 | }",
+///     "",
+/// ].join("\n"));
+/// # }
+/// ```
+pub fn annotate(source: impl JsonSource, offset: usize, message: &str) -> String {
+    let source         = source.stream();
+    let (line, column) = line_column(source, offset);
+
+    let lines  = source.split(|x| *x == b'\n').collect::<Vec<_>>();
+    let first  = line.saturating_sub(2).max(1);
+    let last   = (line + 2).min(lines.len());
+    let width  = last.to_string().len();
+    let gutter = " ".repeat(width);
+
+    let mut output = format!("error: {}\n{}--> line {}, column {}\n{} |\n", message, gutter, line, column, gutter);
+
+    for number in first..=last {
+        let text = String::from_utf8_lossy(lines[number - 1]);
+        let text = text.trim_end_matches('\r');
+
+        output.push_str(&format!("{:>width$} | {}\n", number, text, width = width));
+
+        if number == line {
+            // keep tabs, so that the caret lines up however they are displayed.
+            let indent = text.chars().take(column - 1).map(|x| if x == '\t' { '\t' } else { ' ' }).collect::<String>();
+
+            output.push_str(&format!("{} | {}^\n", gutter, indent));
+        }
+    }
+
+    output
+}
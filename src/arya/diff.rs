@@ -0,0 +1,169 @@
+use {
+    std::collections::BTreeMap,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::strings,
+};
+
+
+
+/// how a value changed between two snapshots of a streaming json object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonChangeKind {
+    /// the value did not exist in the previous snapshot.
+    Added,
+
+    /// the value is a string or number that continues the previous value, such as `"hello"` after `"hel"`.
+    Extended,
+
+    /// the value exists in both snapshots, but is different and does not continue the previous value - usually because
+    /// a value of a different kind now has the same path.
+    Replaced,
+
+    /// the value existed in the previous snapshot, but no longer does.
+    Removed,
+}
+
+
+
+/// a value that changed between two snapshots, and its json pointer ([rfc 6901](https://tools.ietf.org/html/rfc6901)).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonChange {
+    pub path: String,
+    pub kind: JsonChangeKind,
+}
+
+
+
+/// compares two complete json objects - usually consecutive
+/// [`completed_snapshot()`](./struct.JsonBuilder.html#method.completed_snapshot)s of a stream - and returns the paths
+/// of the values that changed between them.
+///
+/// # remarks
+///
+/// this lets a ui that renders a json object while it is streamed in update only the components whose values
+/// changed.
+///
+/// objects and arrays are only reported when they are added, removed or replaced. when their contents change, only
+/// the values inside them are reported. changes are returned in the order their values appear in `current`, followed
+/// by the values that were removed.
+///
+/// paths are built from unescaped keys, so `"a\u002fb"` and `"a/b"` are the same key, and both become `/a~1b`.
+///
+/// fails with `JsonErrorKind::Invalid` if either snapshot is not a complete json object.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonChange, JsonChangeKind, diff_snapshots};
+/// #
+/// # fn main() {
+/// #
+/// let mut builder = JsonBuilder::llm();
+///
+/// builder.update(r#"{ "title": "hel"#).unwrap();
+/// let previous = builder.completed_snapshot().unwrap();
+///
+/// builder.update(r#"lo", "tags": ["a"#).unwrap();
+/// let current = builder.completed_snapshot().unwrap();
+///
+/// assert_eq!(diff_snapshots(&previous, &current).unwrap(), vec![
+///     JsonChange { path: "/title".to_string(),  kind: JsonChangeKind::Extended },
+///     JsonChange { path: "/tags".to_string(),   kind: JsonChangeKind::Added    },
+///     JsonChange { path: "/tags/0".to_string(), kind: JsonChangeKind::Added    },
+/// ]);
+/// # }
+/// ```
+pub fn diff_snapshots(previous: impl JsonSource, current: impl JsonSource) -> Result<Vec<JsonChange>, JsonError> {
+    let mut previous = values(previous.stream())?.into_iter().collect::<BTreeMap<_, _>>();
+    let mut changes  = vec![];
+
+    for (path, (kind, text)) in values(current.stream())? {
+        let change = match previous.remove(&path) {
+            None                                   => Some(JsonChangeKind::Added),
+            Some((x, _)) if x != kind              => Some(JsonChangeKind::Replaced),
+            Some(_) if is_container(kind)          => None,
+            Some((_, x)) if x == text              => None,
+            Some((_, x)) if extends(kind, x, text) => Some(JsonChangeKind::Extended),
+            Some(_)                                => Some(JsonChangeKind::Replaced),
+        };
+
+        if let Some(kind) = change {
+            changes.push(JsonChange { path, kind });
+        }
+    }
+
+    changes.extend(previous.into_keys().map(|path| JsonChange { path, kind: JsonChangeKind::Removed }));
+    Ok(changes)
+}
+
+
+
+// returns the json pointer, kind and text of every value in the complete json object `data`, in order. the text of
+// an object or array is left empty.
+fn values(data: &[u8]) -> Result<Vec<(String, (JsonValueKind, &[u8]))>, JsonError> {
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut values = vec![];
+
+    // one frame per open container: the length of its path, and the number of values seen. then the json pointer to
+    // the innermost container, and the key of the value currently being read.
+    let mut frames = vec![];
+    let mut path   = String::new();
+    let mut key    = None;
+
+    for &character in data {
+        reader.update(character, |event| {
+            match event {
+                Event::Begin(kind, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    frames.push((length, 0));
+                    values.push((path.clone(), (kind, &[][..])));
+                },
+                Event::End(_) => {
+                    if let Some((length, _)) = frames.pop() {
+                        path.truncate(length);
+                    }
+                },
+                Event::Key(_, text) => {
+                    key = Some(strings::decode_key(text));
+                },
+                Event::Scalar(kind, range) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    values.push((path.clone(), (kind, &data[range])));
+                    path.truncate(length);
+                },
+            }
+        })?;
+    }
+
+    match reader.verifier().status() {
+        JsonStatus::Valid    => Ok(values),
+        JsonStatus::Continue => Err(JsonErrorKind::Invalid.into()),
+    }
+}
+
+fn is_container(kind: JsonValueKind) -> bool {
+    kind == JsonValueKind::Object || kind == JsonValueKind::Array
+}
+
+// true if the string or number `current` continues `previous`. a string continues another if it begins with the
+// other's text without its closing quote.
+fn extends(kind: JsonValueKind, previous: &[u8], current: &[u8]) -> bool {
+    match kind {
+        JsonValueKind::String => current.starts_with(&previous[..previous.len() - 1]),
+        JsonValueKind::Number => current.starts_with(previous),
+        _                     => false,
+    }
+}
@@ -0,0 +1,199 @@
+use {
+    std::io,
+    std::io::Read,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonStatus,
+    arya::JsonTrailing,
+};
+
+
+
+/// an iterator over the json documents in a stream read from `reader`, such as concatenated or newline-delimited json.
+///
+/// # remarks
+///
+/// documents may be separated by any amount of whitespace, or by none at all. each document is yielded as soon as it
+/// is complete, without the whitespace around it, and completed like
+/// [`JsonBuilder::completed_snapshot()`](./struct.JsonBuilder.html#method.completed_snapshot) - so that members are
+/// stripped, normalized and added to every document the same way, including the last.
+///
+/// a document that is not valid json yields an `io::ErrorKind::InvalidData` error wrapping a
+/// [`JsonError`](./struct.JsonError.html) with the offset in the whole stream, and ends the iteration, as does an
+/// error from `reader`. if the stream ends part way through a document, it is completed if `complete_last()` was
+/// called, and otherwise yields an `io::ErrorKind::UnexpectedEof` error.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonDocuments;
+/// # use std::io::ErrorKind;
+/// #
+/// # fn main() {
+/// #
+/// let stream    = &b"{ \"a\": 1 }\n[2, 3] {\"b\": [4"[..];
+/// let documents = JsonDocuments::new(stream).collect::<Vec<_>>();
+///
+/// assert_eq!(documents[0].as_ref().unwrap(), br#"{ "a": 1 }"#);
+/// assert_eq!(documents[1].as_ref().unwrap(), b"[2, 3]");
+/// assert_eq!(documents[2].as_ref().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+///
+/// let documents = JsonDocuments::new(stream).complete_last().collect::<Vec<_>>();
+///
+/// assert_eq!(documents[2].as_ref().unwrap(), br#"{"b": [4]}"#);
+/// # }
+/// ```
+pub struct JsonDocuments<R> {
+    reader:   R,
+    builder:  JsonBuilder,
+    complete: bool,
+    done:     bool,
+
+    // the last chunk read from `reader`, the next byte of it to apply and its length, and the number of bytes applied.
+    buffer:   Vec<u8>,
+    start:    usize,
+    length:   usize,
+    offset:   usize,
+}
+
+impl<R: Read> JsonDocuments<R> {
+    pub fn new(reader: R) -> JsonDocuments<R> {
+        JsonDocuments::with_options(reader, JsonBuilderOptions::default())
+    }
+
+    /// creates an iterator that reads each document with a builder created from `options`, such as to repair them.
+    /// `options.trailing` is not used, since this iterator decides where each document ends.
+    pub fn with_options(reader: R, mut options: JsonBuilderOptions) -> JsonDocuments<R> {
+        options.trailing = JsonTrailing::Reject;
+
+        JsonDocuments {
+            reader,
+            builder:  JsonBuilder::with_options(options),
+            complete: false,
+            done:     false,
+
+            buffer:   vec![0; 8 * 1024],
+            start:    0,
+            length:   0,
+            offset:   0,
+        }
+    }
+
+    /// completes a document that the stream ends part way through, instead of yielding an error for it.
+    pub fn complete_last(mut self) -> JsonDocuments<R> {
+        self.complete = true;
+        self
+    }
+
+    // reads the next chunk of the stream, returning `false` at its end. interrupted reads are retried.
+    fn fill(&mut self) -> io::Result<bool> {
+        loop {
+            match self.reader.read(&mut self.buffer) {
+                Ok(length) => {
+                    self.start  = 0;
+                    self.length = length;
+
+                    return Ok(length > 0);
+                },
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                    continue;
+                },
+                Err(e) => {
+                    return Err(e);
+                },
+            }
+        }
+    }
+
+    // completes the document that the stream ended part way through, if there is one.
+    fn last(&mut self) -> Option<io::Result<Vec<u8>>> {
+        self.done = true;
+
+        if self.builder.len() == 0 {
+            return None;
+        }
+
+        match self.complete {
+            true  => Some(self.document()),
+            false => Some(Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                JsonError::at(JsonErrorKind::Invalid, self.offset)))),
+        }
+    }
+
+    // completes the document held by the builder, without the whitespace after it, then resets the builder for the
+    // next one.
+    fn document(&mut self) -> io::Result<Vec<u8>> {
+        let document = self.builder.completed_snapshot().map(|mut data| {
+            let end = data.iter().rposition(|x| !b" \t\r\n".contains(x)).map_or(0, |i| i + 1);
+
+            data.truncate(end);
+            data
+        });
+
+        self.builder.reset();
+        document.map_err(|error| invalid(self.offset, error))
+    }
+}
+
+impl<R: Read> Iterator for JsonDocuments<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<io::Result<Vec<u8>>> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            if self.start == self.length {
+                match self.fill() {
+                    Ok(true)  => {},
+                    Ok(false) => return self.last(),
+                    Err(e)    => {
+                        self.done = true;
+                        return Some(Err(e));
+                    },
+                }
+            }
+
+            // whitespace between documents is not part of either.
+            if self.builder.len() == 0 {
+                let skipped = self.buffer[self.start..self.length]
+                    .iter()
+                    .take_while(|x| b" \t\r\n".contains(x))
+                    .count();
+
+                self.start  += skipped;
+                self.offset += skipped;
+
+                if self.start == self.length {
+                    continue;
+                }
+            }
+
+            // the builder rejects the first byte after a complete document, which begins the next one.
+            let (length, result) = self.builder.update_partial(&self.buffer[self.start..self.length]);
+
+            self.start  += length;
+            self.offset += length;
+
+            if self.builder.status() == JsonStatus::Valid {
+                return Some(self.document());
+            }
+
+            if let Err(error) = result {
+                self.done = true;
+                return Some(Err(invalid(self.offset, error)));
+            }
+        }
+    }
+}
+
+
+
+fn invalid(offset: usize, error: JsonError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.with_offset(offset))
+}
@@ -0,0 +1,238 @@
+use {
+    std::mem,
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonTransition,
+    arya::JsonVerifier,
+    arya::TokenSink,
+    arya::table::Token,
+};
+
+
+
+/// the kind of a json value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonValueKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Boolean,
+    Null,
+}
+
+
+
+// a structural event, derived from the state transitions of a `JsonVerifier`. ranges are byte offsets into the input
+// stream, and include the quotes of strings.
+crate enum Event<'a> {
+    // a container was opened by the token at this offset.
+    Begin(JsonValueKind, usize),
+
+    // the innermost container was closed by the token at this offset.
+    End(usize),
+
+    // an object key was completed. `text` is the raw (still escaped) content between the quotes.
+    Key(Range<usize>, &'a [u8]),
+
+    // a string, number, boolean or null value was completed.
+    Scalar(JsonValueKind, Range<usize>),
+}
+
+
+
+// drives a `JsonVerifier` one character at a time, and reports the structural events its transitions imply.
+//
+// the events are derived by a `TokenSink` in the same pass as validation, so callers that only want validation never
+// pay for the bookkeeping.
+#[derive(Clone)]
+crate struct EventReader {
+    verifier: JsonVerifier<EventSink>,
+}
+
+impl EventReader {
+    // `verifier` only provides the options - it must not have read any input yet.
+    crate fn new(verifier: JsonVerifier) -> EventReader {
+        EventReader { verifier: JsonVerifier::with_sink(verifier.options(), EventSink::default()) }
+    }
+
+    crate fn verifier(&self) -> &JsonVerifier<EventSink> {
+        &self.verifier
+    }
+
+    crate fn offset(&self) -> usize {
+        self.verifier.len()
+    }
+
+    // returns the heap memory held by this reader's verifier, and by the key text and events its sink holds.
+    crate fn memory_usage(&self) -> usize {
+        let sink = self.verifier.sink();
+
+        self.verifier.memory_usage().total() + sink.text.capacity() + sink.events.capacity() * mem::size_of::<Pending>()
+    }
+
+    crate fn reset(&mut self) {
+        self.verifier.reset();
+        self.verifier.sink_mut().reset();
+    }
+
+    crate fn update(&mut self, character: u8, mut sink: impl FnMut(Event)) -> Result<(), JsonError> {
+        self.verifier.update(character)?;
+
+        let EventSink { text, events, .. } = self.verifier.sink_mut();
+
+        for event in events.drain(..) {
+            match event {
+                Pending::Begin(kind, offset) => sink(Event::Begin(kind, offset)),
+                Pending::End(offset)         => sink(Event::End(offset)),
+                Pending::Key(range)          => sink(Event::Key(range, text)),
+                Pending::Scalar(kind, range) => sink(Event::Scalar(kind, range)),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+
+// an event that an `EventSink` has found, until its `EventReader` reports it.
+#[derive(Clone)]
+enum Pending {
+    Begin(JsonValueKind, usize),
+    End(usize),
+    Key(Range<usize>),
+    Scalar(JsonValueKind, Range<usize>),
+}
+
+// the bookkeeping of an `EventReader`, which finds the events each transition of its verifier implies.
+#[derive(Clone, Default)]
+crate struct EventSink {
+    depth:  usize,
+
+    // the kind and start offset of the scalar or key currently being read, if any.
+    scalar: Option<(JsonValueKind, usize)>,
+    key:    Option<usize>,
+    text:   Vec<u8>,
+
+    // the events of the last character, in order.
+    events: Vec<Pending>,
+}
+
+impl EventSink {
+    fn reset(&mut self) {
+        self.depth  = 0;
+        self.scalar = None;
+        self.key    = None;
+        self.text.clear();
+        self.events.clear();
+    }
+
+    fn start(&mut self, kind: JsonValueKind, offset: usize) {
+        self.scalar = Some((kind, offset));
+    }
+}
+
+impl TokenSink for EventSink {
+    fn transition(&mut self, transition: &JsonTransition) {
+        let (before, after) = (transition.before.0, transition.after.0);
+        let offset          = transition.offset;
+        let depth           = mem::replace(&mut self.depth, transition.depth);
+
+        // completions.
+        if let Some(start) = self.key {
+            match after {
+                Token::Colon => {
+                    self.events.push(Pending::Key(start..offset + 1));
+                    self.key = None;
+                },
+                _ => {
+                    self.text.push(transition.character);
+                },
+            }
+        }
+
+        if let Some((kind, start)) = self.scalar {
+            let end = match kind {
+                JsonValueKind::Number => match after.is_number() {
+                    true  => None,
+                    false => Some(offset),
+                },
+                _ => match after {
+                    Token::Ok => Some(offset + 1),
+                    _         => None,
+                },
+            };
+
+            if let Some(end) = end {
+                self.events.push(Pending::Scalar(kind, start..end));
+                self.scalar = None;
+            }
+        }
+
+        // beginnings.
+        if transition.depth > depth {
+            match transition.character {
+                b'{' => self.events.push(Pending::Begin(JsonValueKind::Object, offset)),
+                _    => self.events.push(Pending::Begin(JsonValueKind::Array, offset)),
+            }
+        } else if transition.depth < depth {
+            self.events.push(Pending::End(offset));
+        } else if !is_scalar(before) {
+            match (before, after) {
+                (Token::Object, Token::String) | (Token::Key, Token::String) => {
+                    self.key = Some(offset);
+                    self.text.clear();
+                },
+                (_, Token::String)                                          => self.start(JsonValueKind::String, offset),
+                (_, Token::Minus) | (_, Token::Zero) | (_, Token::Integer) => self.start(JsonValueKind::Number, offset),
+                (_, Token::TrueTr) | (_, Token::FalseFa)                   => self.start(JsonValueKind::Boolean, offset),
+                (_, Token::NullNu)                                         => self.start(JsonValueKind::Null, offset),
+                _                                                          => {},
+            }
+        }
+    }
+}
+
+
+
+// appends the segment of the value that is about to be read to the json pointer `path` of the innermost container,
+// which is described by the last of `frames` - the length of its path, and the number of values seen. the segment is
+// the value's key if it is an object member, or otherwise its index.
+crate fn push_value(frames: &mut [(usize, usize)], path: &mut String, key: &mut Option<String>) {
+    if let Some((_, count)) = frames.last_mut() {
+        match key.take() {
+            Some(key) => {
+                push_key(path, &key);
+            },
+            None => {
+                path.push('/');
+                path.push_str(&count.to_string());
+            },
+        }
+
+        *count += 1;
+    }
+}
+
+// appends the segment of the object member `key` to the json pointer `path`, escaping `~` and `/`.
+crate fn push_key(path: &mut String, key: &str) {
+    path.push('/');
+
+    for character in key.chars() {
+        match character {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            x   => path.push(x),
+        }
+    }
+}
+
+// true for every state in the middle of a string, number or literal.
+crate fn is_scalar(token: Token) -> bool {
+    match token {
+        Token::Begin | Token::Ok | Token::Object | Token::Key | Token::Colon | Token::Value | Token::Array => false,
+        _                                                                                                  => true,
+    }
+}
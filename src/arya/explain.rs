@@ -0,0 +1,140 @@
+use {
+    arya::JsonError,
+    arya::JsonSource,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::diagnostics,
+    arya::events::Event,
+    arya::events::EventReader,
+};
+
+
+
+/// a human-readable explanation of why an input stream is, or is not, valid json. see
+/// [`explain()`](./fn.explain.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// the first error in the input stream, or `None` if it is valid json.
+    pub error:      Option<JsonError>,
+
+    /// the line and column of the error, both starting at 1. see [`line_column()`](./fn.line_column.html).
+    pub line:       usize,
+    pub column:     usize,
+
+    /// a description of what was being read when the error occurred, such as `"an object key"` or `"a number"`.
+    pub parsing:    &'static str,
+
+    /// the kind and offset of every container that was open when the error occurred, outermost first.
+    pub containers: Vec<(JsonValueKind, usize)>,
+}
+
+impl Explanation {
+    /// returns true if the input stream is valid json.
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let error = match &self.error {
+            Some(x) => x,
+            None    => return write!(f, "the input stream is valid json."),
+        };
+
+        let offset = error.offset().unwrap_or(0);
+        let path   = error.path().unwrap_or("");
+
+        writeln!(f, "{}, at line {}, column {} (byte {}).", error.kind(), self.line, self.column, offset)?;
+
+        match path.is_empty() {
+            true  => writeln!(f, "it was reading {}.", self.parsing)?,
+            false => writeln!(f, "it was reading {}, at {}.", self.parsing, path)?,
+        }
+
+        for (kind, offset) in self.containers.iter().rev() {
+            let kind = match kind {
+                JsonValueKind::Object => "an object",
+                _                     => "an array",
+            };
+
+            writeln!(f, "it was inside {} opened at byte {}.", kind, offset)?;
+        }
+
+        if !error.expected().is_empty() {
+            let expected = error.expected().iter().map(|x| format!("`{}`", x)).collect::<Vec<_>>();
+
+            write!(f, "it expected {}, ", expected.join(" or "))?;
+
+            match error.found() {
+                Some(x) if x.is_ascii_graphic() => write!(f, "but found `{}`.", x as char)?,
+                Some(x)                         => write!(f, "but found byte 0x{:02x}.", x)?,
+                None                            => write!(f, "but the input stream ended.")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+
+/// validates `source`, and explains why it is not valid json: what was being read, inside which containers, what was
+/// expected and what was found instead.
+///
+/// # remarks
+///
+/// this is meant for surfaces that show errors to people, such as command line tools and language servers. the
+/// explanation displays as a short narrative, and its fields can be used to build other presentations - for example,
+/// highlighting the containers that are still open. like
+/// [`validate_with_context()`](./fn.validate_with_context.html), this is slower than
+/// [`JsonVerifier`](./struct.JsonVerifier.html).
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonValueKind, explain};
+/// #
+/// # fn main() {
+/// #
+/// let explanation = explain(r#"{ "a": [1, 2 x] }"#);
+///
+/// assert!(!explanation.is_valid());
+/// assert_eq!(explanation.containers, vec![(JsonValueKind::Object, 0), (JsonValueKind::Array, 7)]);
+///
+/// assert_eq!(explanation.to_string(), [
+///     "the input stream is not valid json, at line 1, column 14 (byte 13).",
+///     "it was reading the space after a value, at /a/2.",
+///     "it was inside an array opened at byte 7.",
+///     "it was inside an object opened at byte 0.",
+///     "it expected `,` or `]`, but found `x`.",
+/// ].join("\n"));
+/// # }
+/// ```
+pub fn explain(source: impl JsonSource) -> Explanation {
+    let source = source.stream();
+    let error  = match diagnostics::validate_with_context(source) {
+        Ok(()) => return Explanation { error: None, line: 0, column: 0, parsing: "", containers: vec![] },
+        Err(x) => x,
+    };
+
+    // the bytes before the error were accepted, so reading them again only replays the open containers.
+    let offset         = error.offset().unwrap_or(source.len());
+    let mut reader     = EventReader::new(JsonVerifier::new());
+    let mut containers = vec![];
+
+    for &character in &source[..offset] {
+        let _ = reader.update(character, |event| {
+            match event {
+                Event::Begin(kind, offset) => containers.push((kind, offset)),
+                Event::End(_)              => { containers.pop(); },
+                _                          => {},
+            }
+        });
+    }
+
+    let (line, column) = diagnostics::line_column(source, offset);
+    let parsing        = reader.verifier().describe();
+
+    Explanation { error: Some(error), line, column, parsing, containers }
+}
@@ -0,0 +1,398 @@
+use {
+    std::io,
+    std::io::Write,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::coerce,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_key,
+    arya::events::push_value,
+    arya::strings,
+    arya::table::Token,
+};
+
+
+
+/// a streaming filter that keeps or drops object members by their path, so that a large json object can be stripped
+/// down to the fields of interest in the same pass that validates or repairs it.
+///
+/// # remarks
+///
+/// paths are json pointers ([rfc 6901](https://tools.ietf.org/html/rfc6901)) in which a `*` segment matches any key
+/// or array index. object keys are matched once unescaped, so `"p\u0061ssword"` is matched by `/password`.
+///
+/// - [`allow()`](#method.allow) keeps only the members at the given paths, and the objects on the way to them.
+/// - [`deny()`](#method.deny) drops the members at the given paths, and keeps everything else.
+///
+/// only object members are dropped - array elements are always kept, though the members of objects inside them are
+/// filtered. commas are removed along with the members they separated, so the output is valid json.
+///
+/// bytes are passed on as soon as it is known whether they are kept, and only the key of the current member is held
+/// back, so memory use does not grow with the size of the input. the input must be valid json - to filter damaged
+/// json, filter the output of a [`JsonBuilder`](./struct.JsonBuilder.html) with [`sink()`](#method.sink). after an
+/// error, the filter must be [`reset()`](#method.reset) before it is used again.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonFilter};
+/// # use std::sync::{Arc, Mutex};
+/// #
+/// # fn main() {
+/// #
+/// let mut filter = JsonFilter::allow(vec!["/id", "/items/*/name"]);
+/// let mut output = vec![];
+///
+/// filter.update(r#"{ "id": 1, "body": "lorem", "items": [{ "name": "a", "price": 2 }] }"#, |x| {
+///     output.extend_from_slice(x);
+///     Ok(())
+/// }).unwrap();
+///
+/// assert_eq!(output, br#"{ "id": 1, "items": [{ "name": "a" }] }"#.to_vec());
+///
+/// // filter the repaired output of a builder as it is built.
+/// #[derive(Clone)]
+/// struct Shared(Arc<Mutex<Vec<u8>>>);
+///
+/// impl std::io::Write for Shared {
+///     fn write(&mut self, data: &[u8]) -> std::io::Result<usize> { self.0.lock().unwrap().write(data) }
+///     fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+/// }
+///
+/// let shared      = Shared(Arc::new(Mutex::new(vec![])));
+/// let mut builder = JsonBuilder::with_sink(JsonFilter::deny(vec!["/body"]).sink(shared.clone()));
+///
+/// builder.update(r#"{ "body": "lorem", "tags": ["a", "b"#).unwrap();
+/// builder.completed_bytes().unwrap();
+///
+/// assert_eq!(*shared.0.lock().unwrap(), br#"{ "tags": ["a"]}"#.to_vec());
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct JsonFilter {
+    reader:   EventReader,
+    pointers: Vec<String>,
+    allow:    bool,
+
+    // the maximum number of elements kept in each array, and the json value that replaces the rest, if any.
+    elements: usize,
+    marker:   Option<Vec<u8>>,
+
+    // one frame per open container: the length of its path, and the number of values seen. then the state of each
+    // open container, the json pointer to the innermost container, and the key of the value currently being read.
+    frames:   Vec<(usize, usize)>,
+    states:   Vec<Container>,
+    path:     String,
+    key:      Option<String>,
+
+    // the depth of the container whose member or element is being dropped, if any.
+    dropping: Option<usize>,
+}
+
+// the state of an open container: whether it is an object, the bytes held back since its last member or element,
+// whether the member or element being read is kept, and the number of them that were kept.
+#[derive(Clone)]
+struct Container {
+    object:  bool,
+    pending: Vec<u8>,
+    keep:    Option<bool>,
+    kept:    usize,
+}
+
+impl JsonFilter {
+    /// creates a filter that keeps every member. use it to only limit the length of arrays.
+    pub fn new() -> JsonFilter {
+        JsonFilter::create(Vec::<String>::new(), false)
+    }
+
+    /// creates a filter that keeps only the members at `pointers`, the values inside them, and the objects that
+    /// contain them.
+    pub fn allow<T: Into<String>>(pointers: impl IntoIterator<Item = T>) -> JsonFilter {
+        JsonFilter::create(pointers, true)
+    }
+
+    /// creates a filter that drops the members at `pointers`.
+    pub fn deny<T: Into<String>>(pointers: impl IntoIterator<Item = T>) -> JsonFilter {
+        JsonFilter::create(pointers, false)
+    }
+
+    fn create<T: Into<String>>(pointers: impl IntoIterator<Item = T>, allow: bool) -> JsonFilter {
+        JsonFilter {
+            reader:   EventReader::new(JsonVerifier::new()),
+            pointers: pointers.into_iter().map(Into::into).collect(),
+            allow,
+
+            elements: std::usize::MAX,
+            marker:   None,
+
+            frames:   vec![],
+            states:   vec![],
+            path:     String::new(),
+            key:      None,
+
+            dropping: None,
+        }
+    }
+
+    /// keeps at most `maximum` elements of each array, for previews of enormous arrays. if `marker` is given, it is
+    /// added to every array that was cut short, in place of the elements that were dropped.
+    ///
+    /// # remarks
+    ///
+    /// `marker` is written as it is, so it must be a single json value, such as `"..."` or `{ "truncated": true }`.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonFilter;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut filter = JsonFilter::new().maximum_elements(2, Some(r#""...""#));
+    /// let mut output = vec![];
+    ///
+    /// filter.update(r#"{ "a": [1, 2, 3, 4], "b": [[5, 6, 7]], "c": [8] }"#, |x| {
+    ///     output.extend_from_slice(x);
+    ///     Ok(())
+    /// }).unwrap();
+    ///
+    /// assert_eq!(output, br#"{ "a": [1, 2, "..."], "b": [[5, 6, "..."]], "c": [8] }"#.to_vec());
+    /// # }
+    /// ```
+    pub fn maximum_elements(mut self, maximum: usize, marker: Option<&str>) -> JsonFilter {
+        self.elements = maximum;
+        self.marker   = marker.map(|x| x.as_bytes().to_vec());
+        self
+    }
+
+    /// resets this filter, so that it may filter another json object.
+    pub fn reset(&mut self) {
+        self.reader.reset();
+        self.frames.clear();
+        self.states.clear();
+        self.path.clear();
+        self.key      = None;
+        self.dropping = None;
+    }
+
+    /// returns a writer that filters the bytes written to it, and writes the bytes that are kept to `writer`.
+    pub fn sink<W: Write>(self, writer: W) -> JsonFilterSink<W> {
+        JsonFilterSink { filter: self, writer }
+    }
+
+    /// filters `source`, passing the bytes that are kept to `sink`.
+    pub fn update(
+        &mut self,
+        source:   impl JsonSource,
+        mut sink: impl FnMut(&[u8]) -> Result<(), JsonError>) -> Result<(), JsonError>
+    {
+        let mut output = vec![];
+
+        for &character in source.stream() {
+            self.filter(character, &mut output)?;
+        }
+
+        match output.is_empty() {
+            true  => Ok(()),
+            false => sink(&output),
+        }
+    }
+
+    fn filter(&mut self, character: u8, output: &mut Vec<u8>) -> Result<(), JsonError> {
+        let JsonFilter { reader, pointers, allow, elements, marker, frames, states, path, key, dropping } = self;
+
+        let offset    = reader.offset();
+        let limited   = *elements != std::usize::MAX;
+        let mut owned = None;
+
+        // the first character of an element decides whether it is kept, if arrays are limited.
+        let element = match reader.verifier().token() {
+            Token::Array | Token::Value => !b" \t\r\n]".contains(&character),
+            _                           => false,
+        };
+
+        if let (Some(array), true, None) = (states.last_mut(), element && limited, *dropping) {
+            if !array.object {
+                match array.kept < *elements {
+                    true => {
+                        output.extend_from_slice(&array.pending);
+                        array.kept += 1;
+                    },
+                    false => {
+                        // the marker takes the place of the first element that is dropped.
+                        if let (Some(marker), true) = (marker.as_ref(), array.kept == *elements) {
+                            output.extend_from_slice(&array.pending);
+                            output.extend_from_slice(marker);
+                            array.kept += 1;
+                        }
+
+                        *dropping = Some(frames.len());
+                    },
+                }
+
+                array.pending.clear();
+                array.keep = Some(dropping.is_none());
+            }
+        }
+
+        reader.update(character, |event| {
+            // the end of a value, and whether the current character is part of it.
+            let mut ended = None;
+
+            match event {
+                Event::Begin(kind, _) => {
+                    let length = path.len();
+
+                    push_value(frames, path, key);
+                    frames.push((length, 0));
+                    states.push(Container { object: kind == JsonValueKind::Object, pending: vec![], keep: None, kept: 0 });
+
+                    owned = Some(dropping.is_none());
+                },
+                Event::End(offset) => {
+                    if let (Some((length, _)), Some(state)) = (frames.pop(), states.pop()) {
+                        path.truncate(length);
+
+                        if dropping.is_none() {
+                            output.extend_from_slice(&state.pending);
+                        }
+                    }
+
+                    ended = Some(offset + 1);
+                },
+                Event::Key(_, text) => {
+                    let text   = strings::decode_key(text);
+                    let length = path.len();
+
+                    push_key(path, &text);
+                    *key = Some(text);
+
+                    let keep = match allow {
+                        true  => pointers.iter().any(|x| overlaps(x, path)),
+                        false => !pointers.iter().any(|x| coerce::matches(x, path)),
+                    };
+
+                    path.truncate(length);
+
+                    if let Some(object) = states.last_mut() {
+                        if dropping.is_none() {
+                            match keep {
+                                true => {
+                                    let mut pending = std::mem::take(&mut object.pending);
+
+                                    // the separator before this member is not needed if no member was kept before it.
+                                    if object.kept == 0 {
+                                        if let Some(i) = pending.iter().position(|x| *x == b',') {
+                                            pending.remove(i);
+                                        }
+                                    }
+
+                                    output.extend_from_slice(&pending);
+                                    object.kept += 1;
+                                },
+                                false => {
+                                    object.pending.clear();
+                                    *dropping = Some(frames.len());
+                                },
+                            }
+                        }
+
+                        object.keep = Some(keep);
+                    }
+
+                    owned = Some(dropping.is_none());
+                },
+                Event::Scalar(_, range) => {
+                    let length = path.len();
+
+                    push_value(frames, path, key);
+                    path.truncate(length);
+
+                    ended = Some(range.end);
+                },
+            }
+
+            // a value directly inside a container ends the member or element it is.
+            if let Some(end) = ended {
+                let member  = states.last_mut().map_or(false, |x| x.keep.take().is_some());
+                let dropped = member && *dropping == Some(frames.len());
+
+                if end > offset {
+                    owned = Some(!dropped && dropping.is_none());
+                }
+
+                if dropped {
+                    *dropping = None;
+                }
+            }
+        })?;
+
+        // between members - and between elements, if arrays are limited - characters are held back until it is known
+        // whether the next one is kept.
+        let held = states.last().map_or(false, |x| x.keep.is_none() && (x.object || limited));
+
+        match (owned, dropping.is_some(), held) {
+            (Some(true), _, _)                    => output.push(character),
+            (Some(false), _, _) | (None, true, _) => {},
+            (None, false, true)                   => states.last_mut().unwrap().pending.push(character),
+            (None, false, false)                  => output.push(character),
+        }
+
+        Ok(())
+    }
+}
+
+
+
+/// a writer that filters the bytes written to it with a [`JsonFilter`](./struct.JsonFilter.html). see
+/// [`JsonFilter::sink()`](./struct.JsonFilter.html#method.sink).
+pub struct JsonFilterSink<W: Write> {
+    filter: JsonFilter,
+    writer: W,
+}
+
+impl<W: Write> JsonFilterSink<W> {
+    /// returns the writer that kept bytes are written to.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for JsonFilterSink<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let writer = &mut self.writer;
+        let mut failed = None;
+
+        let result = self.filter.update(data, |x| {
+            writer.write_all(x).map_err(|error| {
+                failed = Some(error);
+                JsonErrorKind::Io.into()
+            })
+        });
+
+        match (result, failed) {
+            (Ok(()), _)         => Ok(data.len()),
+            (Err(_), Some(x))   => Err(x),
+            (Err(error), None)  => Err(io::Error::new(io::ErrorKind::InvalidData, error)),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+
+
+// true if the paths `pointer` and `path` are the same, or one is inside the other.
+fn overlaps(pointer: &str, path: &str) -> bool {
+    pointer
+        .split('/')
+        .zip(path.split('/'))
+        .all(|(x, y)| x == "*" || x == y)
+}
@@ -0,0 +1,133 @@
+use {
+    arya,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonStatus,
+    arya::table::Effect,
+    arya::table::Token,
+    arya::verify::ValueType,
+};
+
+
+
+/// a json syntax validator with a compile-time maximum depth, that never allocates.
+///
+/// # remarks
+///
+/// this validates exactly like a [`JsonVerifier`](./struct.JsonVerifier.html) with default options and a maximum
+/// depth of `MAXIMUM_DEPTH`, but its stack is an inline array, so it lives wherever this verifier does and the depth
+/// check is a single comparison against a constant. it is intended for embedded and real-time contexts.
+///
+/// only validation is supported - the per-token limits, non-standard whitespace handling and trace mode of
+/// `JsonVerifier` are not available. `JsonVerifier` itself keeps its growable stack, so that its depth can still be
+/// chosen at runtime; both read the same grammar, and differ only in where their stacks live.
+///
+/// `MAXIMUM_DEPTH` must be at least 1, which is checked when the program is compiled.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, JsonFixedVerifier, JsonStatus};
+/// #
+/// # fn main() {
+/// #
+/// let mut json = JsonFixedVerifier::<2>::new();
+///
+/// for character in br#"{ "a": [1, 2] }"#.iter() {
+///     json.update(*character).unwrap();
+/// }
+///
+/// assert_eq!(json.status(), JsonStatus::Valid);
+///
+/// json.reset();
+///
+/// assert_eq!(json.update(b'['), Ok(()));
+/// assert_eq!(json.update(b'['), Ok(()));
+/// assert_eq!(json.update(b'['), Err(JsonErrorKind::Exceeded.into()));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct JsonFixedVerifier<const MAXIMUM_DEPTH: usize> {
+    state:  Token,
+    stack:  [ValueType; MAXIMUM_DEPTH],
+    depth:  usize,
+    length: usize,
+}
+
+impl<const MAXIMUM_DEPTH: usize> JsonFixedVerifier<MAXIMUM_DEPTH> {
+    // evaluated when `new()` is instantiated, so that a zero depth fails to compile rather than panicking.
+    const NONZERO: () = assert!(MAXIMUM_DEPTH > 0, "a fixed verifier must allow a depth of at least 1.");
+
+    pub fn new() -> JsonFixedVerifier<MAXIMUM_DEPTH> {
+        let () = Self::NONZERO;
+
+        JsonFixedVerifier {
+            state:  Token::Begin,
+            stack:  [ValueType::Key; MAXIMUM_DEPTH],
+            depth:  0,
+            length: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        match self.state == Token::Ok && self.depth == 0 {
+            true  => JsonStatus::Valid,
+            false => JsonStatus::Continue,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state  = Token::Begin;
+        self.depth  = 0;
+        self.length = 0;
+    }
+
+    /// applies `character` to this json object.
+    ///
+    /// # remarks
+    ///
+    /// like [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), a rejected character returns an
+    /// error but leaves this json object unchanged.
+    pub fn update(&mut self, character: u8) -> Result<(), JsonError> {
+        let (state, effect) = arya::table::step(self.state, character, self.last())?;
+
+        match effect {
+            Effect::None                               => {},
+            Effect::Open(ty)                           => self.push(ty)?,
+            Effect::Close                              => self.depth -= 1,
+            Effect::Separate(ty) | Effect::Replace(ty) => self.stack[self.depth - 1] = ty,
+        }
+
+        self.state(state)
+    }
+
+
+
+    fn last(&self) -> Option<ValueType> {
+        match self.depth {
+            0 => None,
+            _ => Some(self.stack[self.depth - 1]),
+        }
+    }
+
+    fn push(&mut self, ty: ValueType) -> Result<(), JsonError> {
+        if self.depth < MAXIMUM_DEPTH {
+            self.stack[self.depth] = ty;
+            self.depth += 1;
+            Ok(())
+        } else {
+            Err(JsonErrorKind::Exceeded.into())
+        }
+    }
+
+    fn state(&mut self, state: Token) -> Result<(), JsonError> {
+        self.state   = state;
+        self.length += 1;
+
+        Ok(())
+    }
+}
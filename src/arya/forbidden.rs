@@ -0,0 +1,221 @@
+use {
+    std::ops::Range,
+
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::strings::decode_key,
+};
+
+
+
+/// the object keys that a [`JsonBuilder`](./struct.JsonBuilder.html) reports or strips, such as the keys that let a
+/// json object pollute the prototypes of the javascript objects it is deserialized into.
+///
+/// # remarks
+///
+/// keys are compared after unescaping, so `"__pro\u0074o__"` is `__proto__`. members are found at any depth,
+/// including inside arrays. the json pointers of the members that were found can be inspected with
+/// [`JsonBuilder::forbidden_keys()`](./struct.JsonBuilder.html#method.forbidden_keys).
+///
+/// with `JsonForbiddenAction::Strip`, each member is removed along with the comma that separated it, so the output is
+/// still valid json. they are removed from the output of `bytes()`, `bytes_cow()` and every `completed_*()` method,
+/// and from what is written to a sink - which is held back until the builder is finished.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonForbiddenAction, JsonForbiddenKeys};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.forbidden_keys = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Strip));
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "a": 1, "__proto__": { "admin": true }, "b": [{ "constructor": {}, "c": 2 }"#).unwrap();
+///
+/// assert_eq!(builder.forbidden_keys(), ["/__proto__", "/b/0/constructor"]);
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "a": 1, "b": [{ "c": 2 }]}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonForbiddenKeys {
+    /// the forbidden keys, unescaped.
+    pub keys:   Vec<String>,
+
+    /// what is done with the members that have them.
+    pub action: JsonForbiddenAction,
+}
+
+impl JsonForbiddenKeys {
+    pub fn new<T: Into<String>>(keys: impl IntoIterator<Item = T>, action: JsonForbiddenAction) -> JsonForbiddenKeys {
+        JsonForbiddenKeys { keys: keys.into_iter().map(Into::into).collect(), action }
+    }
+
+    /// forbids `__proto__`, `constructor` and `prototype`, the keys that javascript prototype pollution relies on.
+    pub fn prototype(action: JsonForbiddenAction) -> JsonForbiddenKeys {
+        JsonForbiddenKeys::new(vec!["__proto__", "constructor", "prototype"], action)
+    }
+}
+
+
+
+/// what a [`JsonBuilder`](./struct.JsonBuilder.html) does with the members that have a
+/// [`JsonForbiddenKeys`](./struct.JsonForbiddenKeys.html) key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonForbiddenAction {
+    /// they are left as they are, but their paths are reported.
+    Report,
+
+    /// they are removed from the output, and their paths are reported.
+    Strip,
+}
+
+
+
+// the members of an object: the offset of each one's key, the offset just after its value, and whether its key is
+// forbidden.
+type Members = Vec<(usize, usize, bool)>;
+
+// returns the json pointers of the members of `data` that have one of `keys`, and the ranges of `data` to remove to
+// strip them. `data` must be a prefix of a valid json object - only the members of objects that are complete are
+// stripped.
+crate fn find(data: &[u8], keys: &[String]) -> (Vec<String>, Vec<Range<usize>>) {
+    let mut reader  = EventReader::new(JsonVerifier::new());
+    let mut paths   = vec![];
+    let mut ranges  = vec![];
+
+    // one frame per open container, as for `push_value()`, along with the members seen so far if it is an object. then
+    // the json pointer to the innermost container, the key of the value currently being read, and whether it is
+    // forbidden.
+    let mut frames  = vec![];
+    let mut members = Vec::<Option<Members>>::new();
+    let mut path    = String::new();
+    let mut key     = None;
+    let mut found   = false;
+
+    for &character in data {
+        let result = reader.update(character, |event| {
+            match event {
+                Event::Begin(kind, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+
+                    if std::mem::take(&mut found) {
+                        paths.push(path.clone());
+                    }
+
+                    frames.push((length, 0));
+                    members.push(match kind {
+                        JsonValueKind::Object => Some(vec![]),
+                        _                     => None,
+                    });
+                },
+                Event::End(offset) => {
+                    if let Some((length, _)) = frames.pop() {
+                        path.truncate(length);
+                    }
+
+                    if let Some(Some(object)) = members.pop() {
+                        strip(&object, &mut ranges);
+                    }
+
+                    if let Some(Some(object)) = members.last_mut() {
+                        if let Some(member) = object.last_mut() {
+                            member.1 = offset + 1;
+                        }
+                    }
+                },
+                Event::Key(range, text) => {
+                    let decoded = decode_key(text);
+
+                    found = keys.iter().any(|x| *x == decoded);
+                    key   = Some(decoded);
+
+                    if let Some(Some(object)) = members.last_mut() {
+                        object.push((range.start, range.end, found));
+                    }
+                },
+                Event::Scalar(_, range) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+
+                    if std::mem::take(&mut found) {
+                        paths.push(path.clone());
+                    }
+
+                    path.truncate(length);
+
+                    if let Some(Some(object)) = members.last_mut() {
+                        if let Some(member) = object.last_mut() {
+                            member.1 = range.end;
+                        }
+                    }
+                },
+            }
+        });
+
+        if result.is_err() {
+            break;
+        }
+    }
+
+    // the ranges of nested objects are found first. a range inside another one is already removed by it.
+    ranges.sort_by_key(|x| x.start);
+
+    let mut end = 0;
+
+    ranges.retain(|x| {
+        let keep = x.start >= end;
+
+        end = end.max(x.end);
+        keep
+    });
+
+    (paths, ranges)
+}
+
+// adds the ranges that remove the forbidden members of the complete object `members` to `ranges`. a member is removed
+// up to the key of the member after it - or, if every member after it is removed too, from the end of the last member
+// that is kept - so that no comma is left behind.
+fn strip(members: &Members, ranges: &mut Vec<Range<usize>>) {
+    let last = match members.iter().rposition(|x| !x.2) {
+        Some(x) => x,
+        None    => {
+            if let (Some(first), Some(last)) = (members.first(), members.last()) {
+                ranges.push(first.0..last.1);
+            }
+
+            return;
+        },
+    };
+
+    for (i, member) in members[..last].iter().enumerate().filter(|(_, x)| x.2) {
+        ranges.push(member.0..members[i + 1].0);
+    }
+
+    if last + 1 < members.len() {
+        ranges.push(members[last].1..members[members.len() - 1].1);
+    }
+}
+
+// returns `data` without `ranges`, which must be in order and must not overlap.
+crate fn remove<T: Clone>(data: &[T], ranges: &[Range<usize>]) -> Vec<T> {
+    let mut output = Vec::with_capacity(data.len());
+    let mut start  = 0;
+
+    for range in ranges {
+        output.extend_from_slice(&data[start..range.start]);
+        start = range.end;
+    }
+
+    output.extend_from_slice(&data[start..]);
+    output
+}
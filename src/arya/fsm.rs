@@ -0,0 +1,263 @@
+//! a pure, stateless interface to the json state machine that [`JsonVerifier`](../struct.JsonVerifier.html) is built
+//! on.
+//!
+//! [`step()`](./fn.step.html) applies a single byte to a [`State`](./struct.State.html) and describes what happens,
+//! without instantiating a verifier or allocating. it reads the same transition table as the verifier, so engines that
+//! cannot hold one - gpu token filters, property testers, or other languages over ffi - can drive the exact same
+//! grammar.
+//!
+//! a `State` only knows the innermost open container: a step that opens or closes one tells the caller to push or
+//! pop it, and the caller keeps the rest of the stack. the limits of
+//! [`JsonVerifierOptions`](../struct.JsonVerifierOptions.html) are not applied, and `step()` behaves as a verifier
+//! created with `JsonVerifier::new()`.
+
+
+
+use {
+    arya::JsonState,
+    arya::table,
+    arya::table::Effect,
+    arya::table::Token,
+    arya::verify::ValueType,
+};
+
+
+
+/// the number of states, and of rows in [`TRANSITIONS`](./static.TRANSITIONS.html).
+pub const STATES: usize = table::STATES;
+
+/// the state before any input, as a row of `TRANSITIONS`.
+pub const BEGIN: u8 = Token::Begin as u8;
+
+/// the state after a complete value, as a row of `TRANSITIONS`. a json object is complete in this state when no
+/// container is open.
+pub const OK: u8 = Token::Ok as u8;
+
+/// the state after `{`, which expects a key or `}`, as a row of `TRANSITIONS`.
+pub const OBJECT: u8 = Token::Object as u8;
+
+/// the state after a comma in an object, which expects a key, as a row of `TRANSITIONS`.
+pub const KEY: u8 = Token::Key as u8;
+
+/// the state after a key, which expects `:`, as a row of `TRANSITIONS`.
+pub const AFTER_KEY: u8 = Token::Colon as u8;
+
+/// the state after `:` or a comma in an array, which expects a value, as a row of `TRANSITIONS`.
+pub const VALUE: u8 = Token::Value as u8;
+
+/// the state after `[`, which expects a value or `]`, as a row of `TRANSITIONS`.
+pub const ARRAY: u8 = Token::Array as u8;
+
+/// the byte is not accepted in this state.
+pub const REJECT: u8 = 0xff;
+
+/// `}` closes an object with no members. the innermost container must be a key - it is popped, and the state is `OK`.
+pub const CLOSE_EMPTY_OBJECT: u8 = 0x80;
+
+/// `}` closes an object. the innermost container must be an object - it is popped, and the state is `OK`.
+pub const CLOSE_OBJECT: u8 = 0x81;
+
+/// `]` closes an array. the innermost container must be an array - it is popped, and the state is `OK`.
+pub const CLOSE_ARRAY: u8 = 0x82;
+
+/// `{` opens an object. a key is pushed, and the state is `OBJECT`.
+pub const OPEN_OBJECT: u8 = 0x83;
+
+/// `[` opens an array. an array is pushed, and the state is `ARRAY`.
+pub const OPEN_ARRAY: u8 = 0x84;
+
+/// `"` closes a string. the state is `AFTER_KEY` if the innermost container is a key, `OK` if it is an object or an
+/// array, and the byte is rejected at the top level.
+pub const QUOTE: u8 = 0x85;
+
+/// `,` ends a value. if the innermost container is an object, it is replaced by a key and the state is `KEY`. if it is an
+/// array, the state is `VALUE`. otherwise the byte is rejected.
+pub const COMMA: u8 = 0x86;
+
+/// `:` ends a key. the innermost container must be a key - it is replaced by an object, and the state is `VALUE`.
+pub const COLON: u8 = 0x87;
+
+
+
+/// the json state machine as a flat table, with one row per state and one column per byte, for embedding in programs
+/// that cannot depend on arya.
+///
+/// # remarks
+///
+/// the table is generated at compile time from the same transition table that the verifier uses, and its rows follow
+/// the order of the states' names: `begin`, `ok`, `object`, `key`, `colon`, `value`, `array`, `string`, `escape`,
+/// `u1` - `u4`, `minus`, `zero`, `integer`, `fraction 1`, `fraction 2`, `exponent 1` - `exponent 3`, then `tr`, `tru`,
+/// `true`, `fa`, `fal`, `fals`, `false`, `nu`, `nul` and `null`.
+///
+/// an entry below `STATES` is the next state, and any other entry is `REJECT` or one of the container operations
+/// above. the caller keeps a stack of open containers - a key, an object or an array - which starts empty. as with
+/// [`step()`](./fn.step.html), the limits of [`JsonVerifierOptions`](../struct.JsonVerifierOptions.html) are not
+/// applied.
+///
+/// # examples
+///
+/// ```
+/// # use arya::fsm::{self, TRANSITIONS};
+/// #
+/// # fn main() {
+/// #
+/// #[derive(PartialEq)]
+/// enum Open { Key, Object, Array }
+///
+/// fn accepts(input: &[u8]) -> bool {
+///     let mut state = fsm::BEGIN;
+///     let mut stack = vec![];
+///
+///     for &byte in input {
+///         state = match (TRANSITIONS[state as usize][byte as usize], stack.last()) {
+///             (fsm::CLOSE_EMPTY_OBJECT, Some(Open::Key))    => { stack.pop(); fsm::OK },
+///             (fsm::CLOSE_OBJECT,       Some(Open::Object)) => { stack.pop(); fsm::OK },
+///             (fsm::CLOSE_ARRAY,        Some(Open::Array))  => { stack.pop(); fsm::OK },
+///             (fsm::OPEN_OBJECT,        _)                  => { stack.push(Open::Key); fsm::OBJECT },
+///             (fsm::OPEN_ARRAY,         _)                  => { stack.push(Open::Array); fsm::ARRAY },
+///             (fsm::QUOTE,              Some(Open::Key))    => fsm::AFTER_KEY,
+///             (fsm::QUOTE,              Some(_))            => fsm::OK,
+///             (fsm::COMMA,              Some(Open::Object)) => { stack.pop(); stack.push(Open::Key); fsm::KEY },
+///             (fsm::COMMA,              Some(Open::Array))  => fsm::VALUE,
+///             (fsm::COLON,              Some(Open::Key))    => { stack.pop(); stack.push(Open::Object); fsm::VALUE },
+///             (x, _) if (x as usize) < fsm::STATES          => x,
+///             _                                             => return false,
+///         };
+///     }
+///
+///     state == fsm::OK && stack.is_empty()
+/// }
+///
+/// assert!(accepts(br#"{ "a": [1, true, "b"], "c": {} }"#));
+/// assert!(!accepts(br#"{ "a": [1, true, "b"} }"#));
+/// assert!(!accepts(br#"{ "a" }"#));
+/// # }
+/// ```
+pub static TRANSITIONS: [[u8; 256]; STATES] = table::flatten();
+
+
+
+/// an open container, as seen by the state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Container {
+    /// an object that expects a key next, or is empty.
+    Key,
+
+    /// an object that expects a value next, or has just read one.
+    Object,
+
+    /// an array.
+    Array,
+}
+
+
+
+/// the state of the json state machine, and the innermost open container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct State {
+    /// the current state.
+    pub state:     JsonState,
+
+    /// the innermost open container, or `None` at the top level.
+    pub container: Option<Container>,
+}
+
+impl State {
+    /// the state before any input.
+    pub const BEGIN: State = State { state: JsonState(Token::Begin), container: None };
+
+    /// whether a json object read from `State::BEGIN` is complete in this state, and may end here.
+    pub fn is_complete(&self) -> bool {
+        self.state.0 == Token::Ok && self.container.is_none()
+    }
+}
+
+impl Default for State {
+    fn default() -> State {
+        State::BEGIN
+    }
+}
+
+
+
+/// the result of applying a byte to a [`State`](./struct.State.html). see [`step()`](./fn.step.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    /// the byte is accepted, and the innermost container is unchanged.
+    Next(State),
+
+    /// the byte opens a container. the caller pushes the old innermost container, if any, onto its stack.
+    Push(State),
+
+    /// the byte closes the innermost container. the caller pops the new innermost container from its stack, and sets
+    /// it as the container of this state.
+    Pop(JsonState),
+
+    /// the byte is not accepted in this state. the state is unchanged.
+    Reject,
+}
+
+
+
+/// applies `byte` to `state`.
+///
+/// # remarks
+///
+/// non-ascii bytes are accepted without changing the state, as they are by the verifier - utf8 sequences are not
+/// checked.
+///
+/// # examples
+///
+/// ```
+/// # use arya::fsm::{self, State, Step};
+/// #
+/// # fn main() {
+/// #
+/// let mut state = State::BEGIN;
+/// let mut stack = vec![];
+///
+/// for &byte in br#"{ "a": [1, true] }"#.iter() {
+///     match fsm::step(state, byte) {
+///         Step::Next(x) => state = x,
+///         Step::Push(x) => { stack.push(state.container); state = x; },
+///         Step::Pop(x)  => state = State { state: x, container: stack.pop().unwrap() },
+///         Step::Reject  => panic!("rejected {}", byte as char),
+///     }
+/// }
+///
+/// assert!(state.is_complete());
+/// assert_eq!(fsm::step(state, b'}'), Step::Reject);
+/// # }
+/// ```
+pub fn step(state: State, byte: u8) -> Step {
+    let next = |token, container| State { state: JsonState(token), container };
+
+    match table::step(state.state.0, byte, state.container.map(value_type)) {
+        Ok((token, Effect::None))         => Step::Next(next(token, state.container)),
+        Ok((token, Effect::Open(ty)))     => Step::Push(next(token, Some(container(ty)))),
+        Ok((token, Effect::Close))        => Step::Pop(JsonState(token)),
+        Ok((token, Effect::Separate(ty))) => Step::Next(next(token, Some(container(ty)))),
+        Ok((token, Effect::Replace(ty)))  => Step::Next(next(token, Some(container(ty)))),
+        Err(_)                            => Step::Reject,
+    }
+}
+
+
+
+// the container that a verifier's stack entry `ty` is.
+fn container(ty: ValueType) -> Container {
+    match ty {
+        ValueType::Key    => Container::Key,
+        ValueType::Object => Container::Object,
+        ValueType::Array  => Container::Array,
+    }
+}
+
+// the stack entry a verifier keeps for `container`.
+fn value_type(container: Container) -> ValueType {
+    match container {
+        Container::Key    => ValueType::Key,
+        Container::Object => ValueType::Object,
+        Container::Array  => ValueType::Array,
+    }
+}
@@ -0,0 +1,111 @@
+use {
+    arya::JsonError,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+};
+
+
+
+/// receives every object key a [`JsonInterningVerifier`](./struct.JsonInterningVerifier.html) reads.
+///
+/// this is implemented for every `FnMut(&[u8])`.
+pub trait JsonInterner {
+    /// called with the raw (still escaped) content of each key, without its quotes, as soon as the key is complete.
+    fn intern(&mut self, key: &[u8]);
+}
+
+impl<T: FnMut(&[u8])> JsonInterner for T {
+    fn intern(&mut self, key: &[u8]) {
+        self(key)
+    }
+}
+
+
+
+/// a json syntax validator that hands every object key to an interner as it is read.
+///
+/// # remarks
+///
+/// this lets a document builder downstream of arya share the storage for repeated keys across many json objects,
+/// without reading each key a second time. the interner is kept across `reset()`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonInterningVerifier;
+/// # use std::collections::HashSet;
+/// #
+/// # fn main() {
+/// #
+/// let mut keys     = HashSet::new();
+/// let mut verifier = JsonInterningVerifier::new(|key: &[u8]| {
+///     keys.insert(key.to_vec());
+/// });
+///
+/// for document in &[r#"{ "id": 1, "name": "a" }"#, r#"{ "id": 2, "name": "b" }"#] {
+///     verifier.reset();
+///     verifier.update(*document).unwrap();
+/// }
+///
+/// drop(verifier);
+/// assert_eq!(keys.len(), 2);
+/// # }
+/// ```
+pub struct JsonInterningVerifier<T: JsonInterner> {
+    reader:   EventReader,
+    interner: T,
+}
+
+impl<T: JsonInterner> JsonInterningVerifier<T> {
+    pub fn new(interner: T) -> JsonInterningVerifier<T> {
+        JsonInterningVerifier::with_maximum_depth(interner, std::usize::MAX)
+    }
+
+    pub fn with_maximum_depth(interner: T, maximum_depth: usize) -> JsonInterningVerifier<T> {
+        JsonInterningVerifier {
+            reader: EventReader::new(JsonVerifier::with_maximum_depth(maximum_depth)),
+            interner,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.reader.offset()
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.reader.verifier().status()
+    }
+
+    pub fn reset(&mut self) {
+        self.reader.reset();
+    }
+
+    pub fn interner(&mut self) -> &mut T {
+        &mut self.interner
+    }
+
+    pub fn into_interner(self) -> T {
+        self.interner
+    }
+
+    /// applies `source` to this json object.
+    ///
+    /// like [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), an invalid character is rejected
+    /// without changing any state.
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        let JsonInterningVerifier { reader, interner } = self;
+
+        for &character in source.stream() {
+            reader.update(character, |event| {
+                if let Event::Key(_, text) = event {
+                    interner.intern(text);
+                }
+            })?;
+        }
+
+        Ok(())
+    }
+}
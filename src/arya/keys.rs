@@ -0,0 +1,169 @@
+use {
+    std::collections::BTreeMap,
+
+    arya::JsonError,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_key,
+    arya::strings,
+};
+
+
+
+/// a json syntax validator that also collects every object key it sees, for monitoring schema drift in the same pass
+/// as validation.
+///
+/// # remarks
+///
+/// keys are counted by their unescaped text, so `"a\/b"` and `"a/b"` are counted as the same key.
+///
+/// when created with [`with_paths()`](#method.with_paths), the path of every key is counted too. paths are json
+/// pointers ([rfc 6901](https://tools.ietf.org/html/rfc6901)), except that every array index is replaced by `*` so
+/// that the elements of an array share their paths.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonKeys;
+/// #
+/// # fn main() {
+/// #
+/// let mut keys = JsonKeys::with_paths();
+///
+/// keys.update(r#"{ "id": 1, "tags": [{ "id": 2 }, { "id": 3, "new": true }] }"#).unwrap();
+///
+/// assert_eq!(keys.keys()["id"], 3);
+/// assert_eq!(keys.paths().unwrap()["/tags/*/id"], 2);
+/// assert_eq!(keys.paths().unwrap()["/tags/*/new"], 1);
+///
+/// let mut keys = JsonKeys::new();
+///
+/// keys.update(r#"{ "a\/b": 1, "a/b": 2 }"#).unwrap();
+///
+/// assert_eq!(keys.keys()["a/b"], 2);
+/// # }
+/// ```
+pub struct JsonKeys {
+    reader: EventReader,
+    keys:   BTreeMap<String, usize>,
+    paths:  Option<BTreeMap<String, usize>>,
+
+    // the length of the path of each open container, and the key of the value currently being read.
+    frames: Vec<usize>,
+    path:   String,
+    key:    Option<String>,
+}
+
+impl JsonKeys {
+    /// creates a collector that counts keys.
+    pub fn new() -> JsonKeys {
+        JsonKeys::create(std::usize::MAX, false)
+    }
+
+    /// creates a collector that counts both keys and their paths.
+    pub fn with_paths() -> JsonKeys {
+        JsonKeys::create(std::usize::MAX, true)
+    }
+
+    pub fn with_maximum_depth(maximum_depth: usize) -> JsonKeys {
+        JsonKeys::create(maximum_depth, false)
+    }
+
+    fn create(maximum_depth: usize, paths: bool) -> JsonKeys {
+        JsonKeys {
+            reader: EventReader::new(JsonVerifier::with_maximum_depth(maximum_depth)),
+            keys:   BTreeMap::new(),
+            paths:  if paths { Some(BTreeMap::new()) } else { None },
+
+            frames: vec![],
+            path:   String::new(),
+            key:    None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.reader.offset()
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.reader.verifier().status()
+    }
+
+    /// resets this validator. the collected keys are kept, so that they can be accumulated across many json objects.
+    pub fn reset(&mut self) {
+        self.reader.reset();
+        self.frames.clear();
+        self.path.clear();
+        self.key = None;
+    }
+
+    /// forgets every collected key and path.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+
+        if let Some(paths) = &mut self.paths {
+            paths.clear();
+        }
+    }
+
+    /// applies `source` to this json object.
+    ///
+    /// like [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), an invalid character is rejected
+    /// without changing any state.
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        let JsonKeys { reader, keys, paths, frames, path, key } = self;
+
+        for &character in source.stream() {
+            reader.update(character, |event| {
+                match event {
+                    Event::Begin(_, _) => {
+                        frames.push(path.len());
+
+                        match key.take() {
+                            Some(key)                => push_key(path, &key),
+                            None if frames.len() > 1 => path.push_str("/*"),
+                            None                     => {},
+                        }
+                    },
+                    Event::End(_) => {
+                        if let Some(length) = frames.pop() {
+                            path.truncate(length);
+                        }
+                    },
+                    Event::Key(_, text) => {
+                        let text = strings::decode_key(text);
+
+                        if let Some(paths) = paths {
+                            let length = path.len();
+
+                            push_key(path, &text);
+                            *paths.entry(path.clone()).or_insert(0) += 1;
+                            path.truncate(length);
+                        }
+
+                        *keys.entry(text.clone()).or_insert(0) += 1;
+                        *key = Some(text);
+                    },
+                    Event::Scalar(_, _) => {
+                        *key = None;
+                    },
+                }
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// returns every key seen so far, with the number of times it was seen.
+    pub fn keys(&self) -> &BTreeMap<String, usize> {
+        &self.keys
+    }
+
+    /// returns the path of every key seen so far, with the number of times it was seen, if paths are being collected.
+    pub fn paths(&self) -> Option<&BTreeMap<String, usize>> {
+        self.paths.as_ref()
+    }
+}
@@ -0,0 +1,126 @@
+use {
+    hina,
+
+    std::collections::VecDeque,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+};
+
+
+
+/// a validator for newline-delimited json, where every line is a separate json document.
+///
+/// # remarks
+///
+/// unlike the [`JsonBuilder`](./struct.JsonBuilder.html), an invalid line does not poison the validator: the rest of
+/// that line is skipped, an error is recorded for it, and validation continues with the next line.
+///
+/// lines are numbered from zero. blank lines are skipped and produce no result, but are still counted.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, JsonLines};
+/// #
+/// # fn main() {
+/// #
+/// let mut lines = JsonLines::new();
+///
+/// lines.update("{ \"a\": 1 }\n{ \"a\": ]\n\n[2]");
+///
+/// assert_eq!(lines.next_line(), Some((0, Ok(b"{ \"a\": 1 }".to_vec()))));
+/// assert_eq!(lines.next_line(), Some((1, Err(JsonErrorKind::Invalid.into()))));
+/// assert_eq!(lines.next_line(), None);
+///
+/// assert_eq!(lines.finish(), vec![(3, Ok(b"[2]".to_vec()))]);
+/// # }
+/// ```
+pub struct JsonLines {
+    verifier:  JsonVerifier,
+    line:      Vec<u8>,
+    number:    usize,
+    error:     Option<JsonError>,
+    completed: VecDeque<(usize, Result<Vec<u8>, JsonError>)>,
+}
+
+impl JsonLines {
+    pub fn new() -> JsonLines {
+        JsonLines::with_maximum_depth(std::usize::MAX)
+    }
+
+    pub fn with_maximum_depth(maximum_depth: usize) -> JsonLines {
+        JsonLines {
+            verifier:  JsonVerifier::with_maximum_depth(maximum_depth),
+            line:      vec![],
+            number:    0,
+            error:     None,
+            completed: VecDeque::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.verifier.reset();
+        self.line.clear();
+        self.number = 0;
+        self.error  = None;
+        self.completed.clear();
+    }
+
+    /// applies `source` to this stream.
+    ///
+    /// invalid lines are reported through `next_line()` rather than here, so this never fails.
+    pub fn update(&mut self, source: impl JsonSource) {
+        for &character in source.stream() {
+            if character == b'\n' {
+                self.end_line();
+            } else if self.error.is_none() {
+                match self.verifier.update(character) {
+                    Ok(()) => self.line.push(character),
+                    Err(e) => self.error = Some(e),
+                }
+            }
+        }
+    }
+
+    /// takes the result of the next completed line, if any.
+    pub fn next_line(&mut self) -> Option<(usize, Result<Vec<u8>, JsonError>)> {
+        self.completed.pop_front()
+    }
+
+    /// finishes the stream, returning the result of every remaining line, including a final line without a trailing
+    /// line break.
+    pub fn finish(mut self) -> Vec<(usize, Result<Vec<u8>, JsonError>)> {
+        self.end_line();
+        self.completed.drain(..).collect()
+    }
+
+
+
+    fn end_line(&mut self) {
+        let number = self.number;
+        let result = match self.error.take() {
+            Some(e) => Some(Err(e)),
+            None    => {
+                let line = hina::trim(&self.line);
+
+                match (line.is_empty(), self.verifier.status()) {
+                    (true, _)                     => None,
+                    (false, JsonStatus::Valid)    => Some(Ok(line.to_vec())),
+                    (false, JsonStatus::Continue) => Some(Err(JsonErrorKind::Invalid.into())),
+                }
+            },
+        };
+
+        if let Some(result) = result {
+            self.completed.push_back((number, result));
+        }
+
+        self.verifier.reset();
+        self.line.clear();
+        self.number += 1;
+    }
+}
@@ -0,0 +1,383 @@
+use {
+    std::collections::HashMap,
+    std::collections::HashSet,
+    std::collections::hash_map::Entry,
+    std::ops::Range,
+
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::diagnostics,
+    arya::strings::decode_key,
+};
+
+#[cfg(feature = "unicode")]
+use {
+    arya::unicode,
+};
+
+
+
+/// the lint rules checked by [`lint()`](./fn.lint.html).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct JsonLintRules {
+    /// report spaces and tabs at the end of a line.
+    pub trailing_whitespace: bool,
+
+    /// report keys that appear more than once in the same object.
+    pub duplicate_keys:      bool,
+
+    /// report keys that only differ by case from another key in the same object, such as `"Id"` and `"id"`. these are
+    /// not duplicates, but are likely to collide in case-insensitive consumers.
+    pub near_duplicate_keys: bool,
+
+    /// report lines indented with a different character than the first indented line, or by an amount that is not a
+    /// multiple of it.
+    pub indentation:         bool,
+
+    /// report keys that are not in ascending (bytewise) order within their object.
+    pub sorted_keys:         bool,
+
+    /// report keys that look like another key in the same object, but are spelled differently - such as `"admin"` and
+    /// `"аdmin"` with a cyrillic `а`, or keys that differ only by invisible characters. requires the `unicode` feature,
+    /// and is never reported without it.
+    pub confusable_keys:     bool,
+}
+
+impl Default for JsonLintRules {
+    fn default() -> JsonLintRules {
+        JsonLintRules {
+            trailing_whitespace: true,
+            duplicate_keys:      true,
+            near_duplicate_keys: false,
+            indentation:         true,
+            sorted_keys:         false,
+            confusable_keys:     true,
+        }
+    }
+}
+
+
+
+/// the kind of problem a [`JsonDiagnostic`](./struct.JsonDiagnostic.html) reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonLint {
+    /// the input is not valid json.
+    Syntax(JsonErrorKind),
+
+    /// a line ends with spaces or tabs.
+    TrailingWhitespace,
+
+    /// a key appears more than once in the same object.
+    DuplicateKey,
+
+    /// a key only differs by case from another key in the same object. this is a warning rather than an error: the
+    /// keys are distinct, but not to case-insensitive consumers.
+    NearDuplicateKey,
+
+    /// a line is indented inconsistently with the rest of the input.
+    InconsistentIndentation,
+
+    /// a key sorts before the key that precedes it.
+    UnsortedKey,
+
+    /// a key looks like another key in the same object.
+    ConfusableKey,
+}
+
+
+
+/// a single problem found by [`lint()`](./fn.lint.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonDiagnostic {
+    /// the kind of problem.
+    pub lint:  JsonLint,
+
+    /// the byte range of the input the problem applies to. keys include their quotes.
+    pub range: Range<usize>,
+}
+
+impl std::fmt::Display for JsonDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.lint {
+            JsonLint::Syntax(e)               => write!(f, "{}", e)?,
+            JsonLint::TrailingWhitespace      => write!(f, "trailing whitespace")?,
+            JsonLint::DuplicateKey            => write!(f, "duplicate key")?,
+            JsonLint::NearDuplicateKey        => write!(f, "key only differs from another key by case")?,
+            JsonLint::InconsistentIndentation => write!(f, "inconsistent indentation")?,
+            JsonLint::UnsortedKey             => write!(f, "key is not in sorted order")?,
+            JsonLint::ConfusableKey           => write!(f, "key looks like another key")?,
+        }
+
+        write!(f, " (at byte {})", self.range.start)
+    }
+}
+
+impl JsonDiagnostic {
+    /// renders this problem as a json object on a single line, for logging and alerting pipelines.
+    ///
+    /// # remarks
+    ///
+    /// `lint` is `"syntax"`, `"trailing_whitespace"`, `"duplicate_key"`, `"near_duplicate_key"`,
+    /// `"inconsistent_indentation"`, `"unsorted_key"` or `"confusable_key"`. syntax errors also have an `error`, the
+    /// [`name()`](./enum.JsonErrorKind.html#method.name) of the error.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonLintRules, lint};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let diagnostics = lint(r#"{ "a": 1, "a": 2 }"#, &JsonLintRules::default());
+    ///
+    /// assert_eq!(
+    ///     diagnostics[0].to_json(),
+    ///     r#"{"lint":"duplicate_key","message":"duplicate key (at byte 10)","start":10,"end":13}"#);
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let name = match self.lint {
+            JsonLint::Syntax(_)               => "syntax",
+            JsonLint::TrailingWhitespace      => "trailing_whitespace",
+            JsonLint::DuplicateKey            => "duplicate_key",
+            JsonLint::NearDuplicateKey        => "near_duplicate_key",
+            JsonLint::InconsistentIndentation => "inconsistent_indentation",
+            JsonLint::UnsortedKey             => "unsorted_key",
+            JsonLint::ConfusableKey           => "confusable_key",
+        };
+
+        let mut members = vec![("lint", diagnostics::json_string(name))];
+
+        if let JsonLint::Syntax(error) = self.lint {
+            members.push(("error", diagnostics::json_string(error.name())));
+        }
+
+        members.push(("message", diagnostics::json_string(&self.to_string())));
+        members.push(("start",   self.range.start.to_string()));
+        members.push(("end",     self.range.end.to_string()));
+
+        diagnostics::json_object(&members)
+    }
+}
+
+
+
+/// checks `source` for syntax errors and for the enabled lint rules, returning every problem found in the order they
+/// appear in `source`.
+///
+/// # remarks
+///
+/// syntax errors are found like [`validate_all()`](./fn.validate_all.html). the key rules need a valid json object, and
+/// are only checked if there are no syntax errors.
+///
+/// keys are compared by their exact (escaped) spelling, except by the near duplicate and confusable key rules - which
+/// compare them once unescaped, by their lowercase spelling and by what they look like. each key is reported at most
+/// once, by the first rule - in the order they are declared in `JsonLintRules` - that it breaks.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonLint, JsonLintRules, lint};
+/// #
+/// # fn main() {
+/// #
+/// let source = "{\n  \"b\": 1, \n  \"a\": 2,\n   \"b\": 3\n}";
+/// let lints  = lint(source, &JsonLintRules::default()).into_iter().map(|x| x.lint).collect::<Vec<_>>();
+///
+/// assert_eq!(lints, vec![JsonLint::TrailingWhitespace, JsonLint::InconsistentIndentation, JsonLint::DuplicateKey]);
+/// # }
+/// ```
+///
+/// ```
+/// # use arya::{JsonLint, JsonLintRules, lint};
+/// #
+/// # fn main() {
+/// #
+/// let mut rules = JsonLintRules::default();
+///
+/// rules.near_duplicate_keys = true;
+///
+/// let source = r#"{ "id": 1, "userId": 2, "ID": 3, "UserID": 4, "id": 5 }"#;
+/// let lints  = lint(source, &rules).into_iter().map(|x| x.lint).collect::<Vec<_>>();
+///
+/// assert_eq!(lints, vec![JsonLint::NearDuplicateKey, JsonLint::NearDuplicateKey, JsonLint::DuplicateKey]);
+/// # }
+/// ```
+pub fn lint(source: impl JsonSource, rules: &JsonLintRules) -> Vec<JsonDiagnostic> {
+    let source          = source.stream();
+    let mut diagnostics = vec![];
+
+    match diagnostics::validate_all(source) {
+        Ok(()) => {
+            if rules.duplicate_keys || rules.near_duplicate_keys || rules.sorted_keys || rules.confusable_keys {
+                lint_keys(source, rules, &mut diagnostics);
+            }
+        },
+        Err(errors) => {
+            for e in errors {
+                let offset = e.offset().unwrap_or(source.len());
+
+                diagnostics.push(JsonDiagnostic {
+                    lint:  JsonLint::Syntax(e.kind()),
+                    range: offset..(offset + 1).min(source.len()),
+                });
+            }
+        },
+    }
+
+    if rules.trailing_whitespace || rules.indentation {
+        lint_lines(source, rules, &mut diagnostics);
+    }
+
+    diagnostics.sort_by_key(|x| x.range.start);
+    diagnostics
+}
+
+
+
+fn lint_keys(source: &[u8], rules: &JsonLintRules, diagnostics: &mut Vec<JsonDiagnostic>) {
+    let mut reader = EventReader::new(JsonVerifier::new());
+
+    // the keys seen so far in each open container. arrays have none.
+    let mut frames: Vec<Keys> = vec![];
+
+    for &character in source {
+        let result = reader.update(character, |event| {
+            match event {
+                Event::Begin(_, _) => {
+                    frames.push(Keys::default());
+                },
+                Event::End(_) => {
+                    frames.pop();
+                },
+                Event::Key(range, text) => {
+                    if let Some(lint) = frames.last_mut().and_then(|x| x.add(rules, text)) {
+                        diagnostics.push(JsonDiagnostic { lint, range: range.clone() });
+                    }
+                },
+                Event::Scalar(_, _) => {},
+            }
+        });
+
+        if result.is_err() {
+            return;
+        }
+    }
+}
+
+// the keys seen so far in an object, kept only for the rules that are enabled.
+#[derive(Default)]
+struct Keys {
+    raw:       HashSet<Vec<u8>>,
+    last:      Option<Vec<u8>>,
+
+    // the unescaped keys by their lowercase form and by their skeleton - see `collides()`.
+    lowercase: HashMap<String, (String, bool)>,
+    skeletons: HashMap<String, (String, bool)>,
+}
+
+impl Keys {
+    // adds the raw key `text`, returning the first rule it breaks, if any.
+    fn add(&mut self, rules: &JsonLintRules, text: &[u8]) -> Option<JsonLint> {
+        let key = match rules.near_duplicate_keys || rules.confusable_keys {
+            true  => decode_key(text),
+            false => String::new(),
+        };
+
+        let duplicate  = rules.duplicate_keys      && !self.raw.insert(text.to_vec());
+        let near       = rules.near_duplicate_keys && collides(&mut self.lowercase, key.to_lowercase(), &key);
+        let unsorted   = rules.sorted_keys         && self.last.as_deref().is_some_and(|x| x > text);
+        let confusable = rules.confusable_keys     && confusable(&mut self.skeletons, &key);
+
+        if rules.sorted_keys {
+            self.last = Some(text.to_vec());
+        }
+
+        match () {
+            _ if duplicate  => Some(JsonLint::DuplicateKey),
+            _ if near       => Some(JsonLint::NearDuplicateKey),
+            _ if unsorted   => Some(JsonLint::UnsortedKey),
+            _ if confusable => Some(JsonLint::ConfusableKey),
+            _               => None,
+        }
+    }
+}
+
+// adds `key` to `forms` under `form`, which maps each form to the first key seen with it and whether a key spelled
+// differently was seen with it since. returns true if a key spelled differently than `key` was already seen with it.
+fn collides(forms: &mut HashMap<String, (String, bool)>, form: String, key: &str) -> bool {
+    match forms.entry(form) {
+        Entry::Occupied(mut entry) => {
+            let (first, other) = entry.get_mut();
+            let differs        = first != key;
+            let collides       = *other || differs;
+
+            *other = collides;
+            collides
+        },
+        Entry::Vacant(entry) => {
+            entry.insert((key.to_string(), false));
+            false
+        },
+    }
+}
+
+// true if the unescaped `key` looks like a key already in `skeletons`, but is spelled differently.
+#[cfg(feature = "unicode")]
+fn confusable(skeletons: &mut HashMap<String, (String, bool)>, key: &str) -> bool {
+    collides(skeletons, unicode::skeleton(key), key)
+}
+
+#[cfg(not(feature = "unicode"))]
+fn confusable(_: &mut HashMap<String, (String, bool)>, _: &str) -> bool {
+    false
+}
+
+fn lint_lines(source: &[u8], rules: &JsonLintRules, diagnostics: &mut Vec<JsonDiagnostic>) {
+    // the character and width of the first indented line.
+    let mut indentation = None;
+    let mut start       = 0;
+
+    for line in source.split(|x| *x == b'\n') {
+        let content = match line.last() {
+            Some(b'\r') => &line[..line.len() - 1],
+            _           => line,
+        };
+
+        let leading  = content.iter().take_while(|x| **x == b' ' || **x == b'\t').count();
+        let trailing = content.iter().rev().take_while(|x| **x == b' ' || **x == b'\t').count();
+
+        if rules.trailing_whitespace && trailing > 0 {
+            diagnostics.push(JsonDiagnostic {
+                lint:  JsonLint::TrailingWhitespace,
+                range: start + content.len() - trailing..start + content.len(),
+            });
+        }
+
+        if rules.indentation && leading > 0 && leading < content.len() {
+            let indent     = &content[..leading];
+            let consistent = match indentation {
+                None => {
+                    indentation = Some((indent[0], leading));
+                    indent.iter().all(|x| *x == indent[0])
+                },
+                Some((character, width)) => {
+                    indent.iter().all(|x| *x == character) && leading % width == 0
+                },
+            };
+
+            if !consistent {
+                diagnostics.push(JsonDiagnostic {
+                    lint:  JsonLint::InconsistentIndentation,
+                    range: start..start + leading,
+                });
+            }
+        }
+
+        start += line.len() + 1;
+    }
+}
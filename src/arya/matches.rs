@@ -0,0 +1,244 @@
+use {
+    std::collections::BTreeMap,
+    std::io,
+    std::io::Read,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonNumber,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::decode_string,
+    arya::events,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::parse_number,
+    arya::strings::decode_key,
+};
+
+
+
+/// checks that the json object read from `stream` is equal to the complete json object `reference`, without buffering
+/// `stream`.
+///
+/// # remarks
+///
+/// whitespace and the order of object members are ignored, but the order of array elements is not. strings and keys
+/// are compared after decoding their escape sequences, and numbers by their value - so `"\u0061"` equals `"a"`, and
+/// `1.0` equals `1`. see [`matches_in_order()`](./fn.matches_in_order.html) to also require the same order of members.
+///
+/// `stream` is read until it differs from `reference`, so only the current string or number of it is ever held in
+/// memory. a `stream` that is not valid json, or that ends before its json object is complete, does not match.
+///
+/// fails with `io::ErrorKind::InvalidInput` if `reference` is not a complete json object, and with any error from
+/// `stream`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::matches;
+/// #
+/// # fn main() {
+/// #
+/// let reference = r#"{ "id": 7, "tags": ["a", "b"], "ok": true }"#;
+///
+/// assert!(matches(reference, &br#"{"ok":true,"tags":["a","b"],"id":7.0}"#[..]).unwrap());
+/// assert!(!matches(reference, &br#"{"ok":true,"tags":["b","a"],"id":7}"#[..]).unwrap());
+/// assert!(!matches(reference, &br#"{"ok":true,"tags":["a","b"],"id":7,"extra":null}"#[..]).unwrap());
+/// assert!(!matches(reference, &br#"{"ok":true,"tags":["a","b"],"id":7"#[..]).unwrap());
+/// # }
+/// ```
+pub fn matches(reference: impl JsonSource, stream: impl Read) -> io::Result<bool> {
+    compare(reference.stream(), stream, false)
+}
+
+/// like [`matches()`](./fn.matches.html), but the members of each object must also be in the same order as in
+/// `reference`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::matches_in_order;
+/// #
+/// # fn main() {
+/// #
+/// let reference = r#"{ "a": 1, "b": [2] }"#;
+///
+/// assert!(matches_in_order(reference, &b"{\"a\":1,\n\"b\":[2]}"[..]).unwrap());
+/// assert!(!matches_in_order(reference, &br#"{"b":[2],"a":1}"#[..]).unwrap());
+/// # }
+/// ```
+pub fn matches_in_order(reference: impl JsonSource, stream: impl Read) -> io::Result<bool> {
+    compare(reference.stream(), stream, true)
+}
+
+
+
+fn compare(reference: &[u8], mut stream: impl Read, ordered: bool) -> io::Result<bool> {
+    let invalid  = |_| io::Error::new(io::ErrorKind::InvalidInput, JsonError::from(JsonErrorKind::Invalid));
+    let expected = values(reference).map_err(invalid)?;
+
+    // in order, the values of `reference` are matched one after another. otherwise they are looked up by their path,
+    // and removed once matched so that a duplicate in `stream` does not match again.
+    let mut next      = 0;
+    let mut remaining = match ordered {
+        true  => BTreeMap::new(),
+        false => expected.iter().map(|(path, kind, text)| (path.clone(), (*kind, text.clone()))).collect(),
+    };
+
+    let mut reader = ValueReader::new();
+    let mut buffer = vec![0; 8 * 1024];
+
+    loop {
+        let length = match stream.read(&mut buffer) {
+            Ok(0)                                                => break,
+            Ok(length)                                           => length,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e)                                               => return Err(e),
+        };
+
+        for &character in &buffer[..length] {
+            let mut equal = true;
+
+            let result = reader.update(character, |path, kind, text| {
+                let found = match ordered {
+                    true  => expected.get(next).filter(|x| x.0 == path).map(|x| (x.1, x.2.clone())),
+                    false => remaining.remove(path),
+                };
+
+                next  += 1;
+                equal &= found.map_or(false, |(x, y)| x == kind && same(kind, &y, text));
+            });
+
+            if result.is_err() || !equal {
+                return Ok(false);
+            }
+        }
+    }
+
+    Ok(reader.status() == JsonStatus::Valid && next == expected.len())
+}
+
+// returns the json pointer, kind and text of every value in the complete json object `data`, in order.
+fn values(data: &[u8]) -> Result<Vec<(String, JsonValueKind, Vec<u8>)>, JsonError> {
+    let mut reader = ValueReader::new();
+    let mut values = vec![];
+
+    for &character in data {
+        reader.update(character, |path, kind, text| values.push((path.to_string(), kind, text.to_vec())))?;
+    }
+
+    match reader.status() {
+        JsonStatus::Valid    => Ok(values),
+        JsonStatus::Continue => Err(JsonErrorKind::Invalid.into()),
+    }
+}
+
+// true if the texts `a` and `b` of two values of the kind `kind` have the same value. objects and arrays are compared
+// by their contents, which are separate values.
+fn same(kind: JsonValueKind, a: &[u8], b: &[u8]) -> bool {
+    match kind {
+        JsonValueKind::String => decode_string(a).ok() == decode_string(b).ok(),
+        JsonValueKind::Number => match (parse_number(a), parse_number(b)) {
+            (Ok(x), Ok(y)) => same_number(x, y),
+            _              => false,
+        },
+        _ => a == b,
+    }
+}
+
+fn same_number(a: JsonNumber, b: JsonNumber) -> bool {
+    match (a, b) {
+        (JsonNumber::I64(x), JsonNumber::I64(y)) => x == y,
+        (JsonNumber::U64(x), JsonNumber::U64(y)) => x == y,
+        (JsonNumber::F64(x), JsonNumber::F64(y)) => x == y,
+        (JsonNumber::I64(x), JsonNumber::F64(y)) => x as f64 == y && y as i64 == x,
+        (JsonNumber::F64(x), JsonNumber::I64(y)) => y as f64 == x && x as i64 == y,
+        (JsonNumber::U64(x), JsonNumber::F64(y)) => x as f64 == y && y as u64 == x,
+        (JsonNumber::F64(x), JsonNumber::U64(y)) => y as f64 == x && x as u64 == y,
+        (JsonNumber::Big(x), JsonNumber::Big(y)) => x == y,
+        _                                        => false,
+    }
+}
+
+
+
+// reads a json object one byte at a time, and reports the json pointer, kind and text of each value as it is read.
+// the text of an object or array is empty. only the bytes of the current scalar are kept.
+struct ValueReader {
+    reader: EventReader,
+
+    // one frame per open container: the length of its path, and the number of values seen. then the json pointer to
+    // the innermost container, and the decoded key of the value currently being read.
+    frames: Vec<(usize, usize)>,
+    path:   String,
+    key:    Option<String>,
+
+    // the bytes read since the verifier was last outside of a string, number or literal.
+    buffer: Vec<u8>,
+}
+
+impl ValueReader {
+    fn new() -> ValueReader {
+        ValueReader {
+            reader: EventReader::new(JsonVerifier::new()),
+
+            frames: vec![],
+            path:   String::new(),
+            key:    None,
+
+            buffer: vec![],
+        }
+    }
+
+    fn status(&self) -> JsonStatus {
+        self.reader.verifier().status()
+    }
+
+    fn update(&mut self, character: u8, mut sink: impl FnMut(&str, JsonValueKind, &[u8])) -> Result<(), JsonError> {
+        let ValueReader { reader, frames, path, key, buffer } = self;
+
+        // the offset of the first byte in `buffer`.
+        let start = reader.offset() - buffer.len();
+
+        buffer.push(character);
+
+        let result = reader.update(character, |event| {
+            match event {
+                Event::Begin(kind, _) => {
+                    let length = path.len();
+
+                    push_value(frames, path, key);
+                    frames.push((length, 0));
+                    sink(path, kind, &[]);
+                },
+                Event::End(_) => {
+                    if let Some((length, _)) = frames.pop() {
+                        path.truncate(length);
+                    }
+                },
+                Event::Key(_, text) => {
+                    *key = Some(decode_key(text));
+                },
+                Event::Scalar(kind, range) => {
+                    let length = path.len();
+
+                    push_value(frames, path, key);
+                    sink(path, kind, &buffer[range.start - start..range.end - start]);
+                    path.truncate(length);
+                },
+            }
+        });
+
+        if result.is_err() {
+            buffer.pop();
+        } else if !events::is_scalar(reader.verifier().token()) {
+            buffer.clear();
+        }
+
+        result
+    }
+}
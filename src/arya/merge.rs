@@ -0,0 +1,337 @@
+use {
+    hina,
+
+    arya::JsonBuilder,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::strings,
+};
+
+
+
+impl JsonBuilder {
+    /// combines `documents` into a single json array, with each document becoming one element.
+    ///
+    /// # remarks
+    ///
+    /// every document must be exactly one complete json value. the first document that is not causes the merge to
+    /// fail.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let builder = JsonBuilder::merge_array(vec![r#"{ "a": 1 }"#, "[2]", "3"]).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"[{ "a": 1 },[2],3]"#);
+    /// # }
+    /// ```
+    pub fn merge_array<T: JsonSource>(documents: impl IntoIterator<Item = T>) -> Result<JsonBuilder, JsonError> {
+        let mut builder = JsonBuilder::new();
+
+        builder.begin_array()?;
+
+        for document in documents {
+            builder.value(document)?;
+        }
+
+        builder.end_array()?;
+        Ok(builder)
+    }
+
+    /// shallowly merges the json objects in `documents` into a single json object.
+    ///
+    /// # remarks
+    ///
+    /// members keep the position and the spelling of their first occurrence, and take the value of their last
+    /// occurrence. keys are compared by their unescaped text, so `"a"` and `"\u0061"` are the same key.
+    ///
+    /// every document must be a complete json object. the first document that is not causes the merge to fail.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let builder = JsonBuilder::merge_object(vec![
+    ///     r#"{ "a": 1, "b": [1, 2] }"#,
+    ///     r#"{ "c": null, "a": { "x": true } }"#,
+    /// ]).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{"a":{ "x": true },"b":[1, 2],"c":null}"#);
+    ///
+    /// let builder = JsonBuilder::merge_object(vec![r#"{ "a/b": 1 }"#, r#"{ "a\/b": 2 }"#]).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{"a/b":2}"#);
+    /// # }
+    /// ```
+    pub fn merge_object<T: JsonSource>(documents: impl IntoIterator<Item = T>) -> Result<JsonBuilder, JsonError> {
+        let documents = documents.into_iter().collect::<Vec<_>>();
+        let mut members: Vec<Named> = vec![];
+
+        for document in &documents {
+            for (name, (key, value)) in named(object_members(document.stream())?) {
+                match members.iter().position(|x| x.0 == name) {
+                    Some(i) => (members[i].1).1 = value,
+                    None    => members.push((name, (key, value))),
+                }
+            }
+        }
+
+        let mut builder = JsonBuilder::new();
+
+        builder.update(b'{')?;
+
+        for (i, (_, (key, value))) in members.into_iter().enumerate() {
+            if i > 0 {
+                builder.update(b',')?;
+            }
+
+            builder.update(key)?;
+            builder.update(b':')?;
+            builder.update(value)?;
+        }
+
+        builder.update(b'}')?;
+        Ok(builder)
+    }
+
+    /// completes this json object, then applies the [rfc 7396](https://tools.ietf.org/html/rfc7396) json merge patch
+    /// `patch` to it.
+    ///
+    /// # remarks
+    ///
+    /// members of `patch` replace the members of this object with the same key, a `null` member removes one, and an
+    /// object member is merged into the object it replaces. a `patch` that is not an object replaces this object
+    /// entirely.
+    ///
+    /// the merge only ever copies spans of the two documents, so no tree of values is built. like `merge_object()`,
+    /// keys are compared by their unescaped text, and members keep the position and the spelling of their first
+    /// occurrence - in this object, if they are in it.
+    ///
+    /// the merged object is built by this builder again, so it keeps its options, sink and callbacks: `patch` may be
+    /// no deeper than this builder's maximum depth, and the merged object is written to the sink after whatever this
+    /// builder already wrote to it.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": 1, "b": { "x": 1, "y": 2 }, "c": [1, 2"#).unwrap();
+    ///
+    /// let builder = builder.apply_merge_patch(r#"{ "a": null, "b": { "y": null, "z": 3 }, "d": "new" }"#).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{"b":{"x":1,"z":3},"c":[1, 2],"d":"new"}"#);
+    ///
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a/b": { "x": 1, "y": 2 } }"#).unwrap();
+    ///
+    /// let builder = builder.apply_merge_patch(r#"{ "a\/b": { "\u0078": null } }"#).unwrap();
+    ///
+    /// assert_eq!(builder.string().unwrap(), r#"{"a/b":{"y":2}}"#);
+    /// # }
+    /// ```
+    pub fn apply_merge_patch(mut self, patch: impl JsonSource) -> Result<JsonBuilder, JsonError> {
+        let target       = self.completed_snapshot()?;
+        let patch        = hina::trim(patch.stream());
+        let mut verifier = JsonVerifier::with_maximum_depth(self.maximum_depth());
+
+        for &character in patch {
+            verifier.update(character)?;
+        }
+
+        if verifier.status() != JsonStatus::Valid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        let mut output = vec![];
+
+        merge_patch(&target, patch, &mut output);
+
+        self.reset();
+        self.update(output)?;
+
+        match self.status() {
+            JsonStatus::Valid    => Ok(self),
+            JsonStatus::Continue => Err(JsonErrorKind::Invalid.into()),
+        }
+    }
+}
+
+
+
+// a `(key, value)` object member.
+type Member<'a> = (&'a [u8], &'a [u8]);
+
+// an object member and the unescaped text of its key, which is what keys are compared by.
+type Named<'a> = (String, Member<'a>);
+
+// pairs each of `members` with the unescaped text of its key.
+fn named(members: Vec<Member<'_>>) -> Vec<Named<'_>> {
+    members
+        .into_iter()
+        .map(|x| (strings::decode_key(&x.0[1..x.0.len() - 1]), x))
+        .collect()
+}
+
+// splits a complete json object into its top-level members, with surrounding whitespace trimmed.
+fn object_members(document: &[u8]) -> Result<Vec<Member<'_>>, JsonError> {
+    if hina::trim(document).first() != Some(&b'{') {
+        return Err(JsonErrorKind::Invalid.into());
+    }
+
+    let mut verifier = JsonVerifier::new();
+
+    for &character in document {
+        verifier.update(character)?;
+    }
+
+    match verifier.status() == JsonStatus::Valid {
+        true  => Ok(members(document)),
+        false => Err(JsonErrorKind::Invalid.into()),
+    }
+}
+
+// splits the valid json object `document` into its top-level members, with surrounding whitespace trimmed, without
+// verifying it again.
+fn members(document: &[u8]) -> Vec<Member<'_>> {
+    let mut members = vec![];
+    let mut depth   = 0;
+    let mut string  = false;
+    let mut escape  = false;
+    let mut start   = 0;
+    let mut colon   = 0;
+
+    for (i, &character) in document.iter().enumerate() {
+        if string {
+            match character {
+                _ if escape => escape = false,
+                b'\\'       => escape = true,
+                b'"'        => string = false,
+                _           => {},
+            }
+
+            continue;
+        }
+
+        match character {
+            b'"'        => string = true,
+            b':'        => if depth == 1 { colon = i },
+            b'{' | b'[' => {
+                depth += 1;
+
+                if depth == 1 {
+                    start = i + 1;
+                }
+            },
+            b',' | b'}' | b']' => {
+                if depth == 1 && colon > start {
+                    members.push((hina::trim(&document[start..colon]), hina::trim(&document[colon + 1..i])));
+                }
+
+                if depth == 1 {
+                    start = i + 1;
+                }
+
+                if character != b',' {
+                    depth -= 1;
+                }
+            },
+            _ => {},
+        }
+    }
+
+    members
+}
+
+// a member to write while merging: its key, its value in the target - empty if it does not exist yet - and its value
+// in the patch, if any.
+type Write<'a> = (&'a [u8], &'a [u8], Option<&'a [u8]>);
+
+// writes the result of applying the valid json merge patch `patch` to the valid json value `target` into `output`. an
+// empty `target` is a value that does not exist yet.
+//
+// nested objects are merged with an explicit stack of the members left to write at each depth, rather than by
+// recursion, so that deeply nested patches cannot overflow the stack.
+fn merge_patch<'a>(target: &'a [u8], patch: &'a [u8], output: &mut Vec<u8>) {
+    let mut stack: Vec<(Vec<Write<'a>>, usize)> = vec![];
+
+    open(target, patch, output, &mut stack);
+
+    while let Some((writes, i)) = stack.last_mut() {
+        let (key, target, patch) = match writes.get(*i) {
+            Some(&write) => write,
+            None         => {
+                output.push(b'}');
+                stack.pop();
+                continue;
+            },
+        };
+
+        if *i > 0 {
+            output.push(b',');
+        }
+
+        *i += 1;
+
+        output.extend_from_slice(key);
+        output.push(b':');
+
+        match patch {
+            Some(patch) => open(target, patch, output, &mut stack),
+            None        => output.extend_from_slice(target),
+        }
+    }
+}
+
+// begins applying `patch` to `target`: a patch that is not an object is written as it is, and one that is has its
+// opening brace written and the members of the merged object pushed onto `stack`.
+fn open<'a>(target: &'a [u8], patch: &'a [u8], output: &mut Vec<u8>, stack: &mut Vec<(Vec<Write<'a>>, usize)>) {
+    if patch.first() != Some(&b'{') {
+        output.extend_from_slice(patch);
+        return;
+    }
+
+    let patches = named(members(patch));
+    let targets = match hina::trim(target).first() == Some(&b'{') {
+        true  => named(members(target)),
+        false => vec![],
+    };
+
+    // the value of the last occurrence of the key named `name` in the patch, if any.
+    let patched = |name: &str| patches.iter().rev().find(|x| x.0 == name).map(|x| (x.1).1);
+    let mut writes = vec![];
+
+    for (name, (key, value)) in &targets {
+        writes.push((*key, *value, patched(name)));
+    }
+
+    for (i, (name, (key, _))) in patches.iter().enumerate() {
+        let added    = !targets.iter().any(|x| x.0 == *name);
+        let earliest = !patches[..i].iter().any(|x| x.0 == *name);
+
+        if added && earliest {
+            writes.push((*key, &b""[..], patched(name)));
+        }
+    }
+
+    // a `null` member removes one.
+    writes.retain(|x| x.2 != Some(&b"null"[..]));
+
+    output.push(b'{');
+    stack.push((writes, 0));
+}
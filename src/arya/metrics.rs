@@ -0,0 +1,95 @@
+use {
+    std::sync::OnceLock,
+
+    arya::JsonErrorKind,
+    arya::JsonRepairKind,
+};
+
+
+
+/// receives counters from arya, for charting validation and repair rates in production.
+///
+/// # remarks
+///
+/// install a recorder with [`set_metrics()`](./fn.set_metrics.html). every method does nothing by default, so a
+/// recorder only needs to implement the counters it is interested in.
+///
+/// counters are reported by [`JsonBuilder`](./struct.JsonBuilder.html), [`validate_all()`](./fn.validate_all.html),
+/// [`validate_batch()`](./fn.validate_batch.html) and
+/// [`JsonValidatorService`](./struct.JsonValidatorService.html). `JsonVerifier` validates a single byte at a time and
+/// has no notion of a finished document, so it reports nothing.
+///
+/// a builder counts its bytes and repairs as it goes, and reports them once at the end of each update. without the
+/// `metrics` feature, none of this is compiled.
+pub trait JsonMetrics: Send + Sync {
+    /// called when a document is validated successfully, or when a builder produces its output.
+    fn validated(&self) {}
+
+    /// called with the number of input bytes each time input is validated or applied to a builder.
+    fn processed(&self, _bytes: usize) {}
+
+    /// called each time a builder makes a lenient repair.
+    fn repaired(&self, _kind: JsonRepairKind) {}
+
+    /// called when a document fails validation, or a builder becomes invalid. only the first error in a document is
+    /// reported.
+    fn failed(&self, _error: JsonErrorKind) {}
+}
+
+
+
+static METRICS: OnceLock<&'static dyn JsonMetrics> = OnceLock::new();
+
+/// installs `metrics` as the process-wide metrics recorder.
+///
+/// # remarks
+///
+/// a recorder can only be installed once. returns `false`, and leaves the installed recorder in place, if one was
+/// already installed.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonMetrics, set_metrics};
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// #
+/// struct Counters {
+///     validated: AtomicUsize,
+///     processed: AtomicUsize,
+/// }
+///
+/// impl JsonMetrics for Counters {
+///     fn validated(&self) {
+///         self.validated.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn processed(&self, bytes: usize) {
+///         self.processed.fetch_add(bytes, Ordering::Relaxed);
+///     }
+/// }
+///
+/// static COUNTERS: Counters = Counters { validated: AtomicUsize::new(0), processed: AtomicUsize::new(0) };
+///
+/// # fn main() {
+/// #
+/// assert!(set_metrics(&COUNTERS));
+///
+/// let mut builder = JsonBuilder::new();
+///
+/// builder.update(r#"{ "a": 1 "#).unwrap();
+/// builder.completed_bytes().unwrap();
+///
+/// assert_eq!(COUNTERS.validated.load(Ordering::Relaxed), 1);
+/// assert_eq!(COUNTERS.processed.load(Ordering::Relaxed), 9);
+/// # }
+/// ```
+pub fn set_metrics(metrics: &'static dyn JsonMetrics) -> bool {
+    METRICS.set(metrics).is_ok()
+}
+
+// reports to the installed recorder, if there is one.
+crate fn record(report: impl FnOnce(&dyn JsonMetrics)) {
+    if let Some(metrics) = METRICS.get() {
+        report(*metrics);
+    }
+}
@@ -0,0 +1,371 @@
+use {
+    std::convert::Infallible,
+    std::future::Future,
+    std::pin::Pin,
+    std::task::Context,
+    std::task::Poll,
+
+    bytes::Buf,
+    bytes::Bytes,
+    http::HeaderValue,
+    http::Request,
+    http::Response,
+    http::StatusCode,
+    http::header,
+    http::request::Parts,
+    http_body::Body,
+    http_body::Frame,
+    http_body::SizeHint,
+    tower_layer::Layer,
+    tower_service::Service,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonStatus,
+};
+
+
+
+/// a tower layer that validates json request bodies before they reach the inner service.
+///
+/// # remarks
+///
+/// the body is streamed through a [`JsonBuilder`](./struct.JsonBuilder.html) frame by frame. the first invalid byte
+/// rejects the request with `400 Bad Request` and a plain text message that includes its offset, without reading the
+/// rest of the body. a body that ends before its json object is complete is rejected at its length.
+///
+/// a body longer than `maximum_length()` - 2 MiB by default - is rejected with `413 Payload Too Large` as soon as a
+/// frame takes it past that, so that no more of it is buffered.
+///
+/// the inner service receives the validated body as a [`JsonBody`](./struct.JsonBody.html). if the builder's options
+/// enable lenient repairs, it receives the repaired json instead, and any `content-length` header is updated to match.
+///
+/// requires the `tower` feature. this works with any service built on `tower` and `http` 1.x, such as an `axum`
+/// router, and with `hyper` 1.x through `hyper-util`'s tower adapter. the inner service's response body must be
+/// constructible from a `String`.
+///
+/// # examples
+///
+/// ```
+/// # extern crate http;
+/// # extern crate http_body;
+/// # extern crate tower_layer;
+/// # extern crate tower_service;
+/// #
+/// # use arya::{JsonBody, JsonBodyLayer, JsonBuilderOptions};
+/// # use http::{Request, Response, StatusCode};
+/// # use http_body::Body;
+/// # use std::convert::Infallible;
+/// # use std::future::{Future, Ready, ready};
+/// # use std::pin::pin;
+/// # use std::task::{Context, Poll, Waker};
+/// # use tower_layer::Layer;
+/// # use tower_service::Service;
+/// #
+/// // a service that responds with the body it receives.
+/// #[derive(Clone)]
+/// struct Echo;
+///
+/// impl Service<Request<JsonBody>> for Echo {
+///     type Response = Response<String>;
+///     type Error    = Infallible;
+///     type Future   = Ready<Result<Response<String>, Infallible>>;
+///
+///     fn poll_ready(&mut self, _: &mut Context) -> Poll<Result<(), Infallible>> {
+///         Poll::Ready(Ok(()))
+///     }
+///
+///     fn call(&mut self, request: Request<JsonBody>) -> Self::Future {
+///         let body = request.into_body().bytes();
+///
+///         ready(Ok(Response::new(String::from_utf8(body.to_vec()).unwrap())))
+///     }
+/// }
+///
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.lenient.commas = true;
+///
+/// let mut service = JsonBodyLayer::with_options(options).layer(Echo);
+/// let mut context = Context::from_waker(Waker::noop());
+///
+/// let response = service.call(Request::new(String::from(r#"{ "a": [1 2] }"#)));
+/// let response = match pin!(response).poll(&mut context) { Poll::Ready(x) => x.unwrap(), _ => unreachable!() };
+///
+/// assert_eq!(response.status(), StatusCode::OK);
+/// assert_eq!(response.body(), r#"{ "a": [1, 2] }"#);
+///
+/// let response = service.call(Request::new(String::from(r#"{ "a": x }"#)));
+/// let response = match pin!(response).poll(&mut context) { Poll::Ready(x) => x.unwrap(), _ => unreachable!() };
+///
+/// assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+/// assert_eq!(response.body(), "the input stream is not valid json (at byte 7)");
+///
+/// let mut service = JsonBodyLayer::new().maximum_length(8).layer(Echo);
+///
+/// let response = service.call(Request::new(String::from(r#"{ "a": [1, 2] }"#)));
+/// let response = match pin!(response).poll(&mut context) { Poll::Ready(x) => x.unwrap(), _ => unreachable!() };
+///
+/// assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonBodyLayer {
+    options: JsonBuilderOptions,
+    maximum: usize,
+}
+
+impl JsonBodyLayer {
+    pub fn new() -> JsonBodyLayer {
+        JsonBodyLayer::with_options(JsonBuilderOptions::default())
+    }
+
+    /// creates a layer that validates, and optionally repairs, request bodies with a builder created with `options`.
+    pub fn with_options(options: JsonBuilderOptions) -> JsonBodyLayer {
+        JsonBodyLayer { options, maximum: 2 * 1024 * 1024 }
+    }
+
+    /// rejects request bodies longer than `maximum` bytes with `413 Payload Too Large`.
+    pub fn maximum_length(mut self, maximum: usize) -> JsonBodyLayer {
+        self.maximum = maximum;
+        self
+    }
+}
+
+impl Default for JsonBodyLayer {
+    fn default() -> JsonBodyLayer {
+        JsonBodyLayer::new()
+    }
+}
+
+impl<S> Layer<S> for JsonBodyLayer {
+    type Service = JsonBodyService<S>;
+
+    fn layer(&self, inner: S) -> JsonBodyService<S> {
+        JsonBodyService { inner, options: self.options.clone(), maximum: self.maximum }
+    }
+}
+
+
+
+/// the service created by [`JsonBodyLayer`](./struct.JsonBodyLayer.html).
+#[derive(Debug, Clone)]
+pub struct JsonBodyService<S> {
+    inner:   S,
+    options: JsonBuilderOptions,
+    maximum: usize,
+}
+
+impl<S, B, R> Service<Request<B>> for JsonBodyService<S>
+where
+    S: Service<Request<JsonBody>, Response = Response<R>> + Clone,
+    B: Body,
+    R: From<String>,
+{
+    type Response = Response<R>;
+    type Error    = S::Error;
+    type Future   = JsonBodyFuture<S, B>;
+
+    fn poll_ready(&mut self, context: &mut Context) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(context)
+    }
+
+    fn call(&mut self, request: Request<B>) -> JsonBodyFuture<S, B> {
+        // the service that was driven to readiness is the one that must be called, so keep a fresh clone instead.
+        let clone         = self.inner.clone();
+        let inner         = std::mem::replace(&mut self.inner, clone);
+        let (parts, body) = request.into_parts();
+
+        JsonBodyFuture {
+            state: State::Reading {
+                inner,
+                parts:   Some(Box::new(parts)),
+                body:    Box::pin(body),
+                builder: Box::new(JsonBuilder::with_options(self.options.clone())),
+                offset:  0,
+                maximum: self.maximum,
+            },
+        }
+    }
+}
+
+
+
+/// the future returned by [`JsonBodyService`](./struct.JsonBodyService.html).
+pub struct JsonBodyFuture<S, B>
+where
+    S: Service<Request<JsonBody>>,
+{
+    state: State<S, B>,
+}
+
+// nothing is ever pinned in place - the body and the inner future are boxed - so this is safe to move.
+impl<S, B> Unpin for JsonBodyFuture<S, B>
+where
+    S: Service<Request<JsonBody>>,
+{
+}
+
+enum State<S, B>
+where
+    S: Service<Request<JsonBody>>,
+{
+    Reading {
+        inner:   S,
+        parts:   Option<Box<Parts>>,
+        body:    Pin<Box<B>>,
+        builder: Box<JsonBuilder>,
+        offset:  usize,
+        maximum: usize,
+    },
+    Calling(Pin<Box<S::Future>>),
+    Done,
+}
+
+impl<S, B, R> Future for JsonBodyFuture<S, B>
+where
+    S: Service<Request<JsonBody>, Response = Response<R>>,
+    B: Body,
+    R: From<String>,
+{
+    type Output = Result<Response<R>, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            let request = match &mut this.state {
+                State::Reading { inner, parts, body, builder, offset, maximum } => {
+                    match body.as_mut().poll_frame(context) {
+                        Poll::Pending => {
+                            return Poll::Pending;
+                        },
+                        Poll::Ready(Some(Ok(frame))) => {
+                            // trailers carry no json, and are dropped. every byte before `offset` was accepted, so it
+                            // is also the number of bytes read so far.
+                            if let Ok(data) = frame.into_data() {
+                                if *offset + data.remaining() > *maximum {
+                                    let e = JsonError::at(JsonErrorKind::TooLarge, *maximum);
+
+                                    this.state = State::Done;
+                                    return Poll::Ready(Ok(reject(StatusCode::PAYLOAD_TOO_LARGE, &e.to_string())));
+                                }
+
+                                if let Err(e) = apply(builder, offset, data) {
+                                    this.state = State::Done;
+                                    return Poll::Ready(Ok(reject(StatusCode::BAD_REQUEST, &e.to_string())));
+                                }
+                            }
+
+                            continue;
+                        },
+                        Poll::Ready(Some(Err(_))) => {
+                            let message = "the request body could not be read";
+
+                            this.state = State::Done;
+                            return Poll::Ready(Ok(reject(StatusCode::BAD_REQUEST, message)));
+                        },
+                        Poll::Ready(None) => {},
+                    }
+
+                    let data = match builder.status() {
+                        JsonStatus::Valid    => std::mem::replace(&mut **builder, JsonBuilder::new()).bytes(),
+                        JsonStatus::Continue => Err(JsonErrorKind::Invalid.into()),
+                    };
+
+                    let data = match data {
+                        Ok(x) => Bytes::from(x),
+                        Err(error) => {
+                            let e = error.with_offset(*offset);
+
+                            this.state = State::Done;
+                            return Poll::Ready(Ok(reject(StatusCode::BAD_REQUEST, &e.to_string())));
+                        },
+                    };
+
+                    let mut parts = parts.take().expect("invariant broken: request parts taken twice.");
+
+                    if parts.headers.contains_key(header::CONTENT_LENGTH) {
+                        parts.headers.insert(header::CONTENT_LENGTH, HeaderValue::from(data.len()));
+                    }
+
+                    inner.call(Request::from_parts(*parts, JsonBody { data: Some(data) }))
+                },
+                State::Calling(future) => {
+                    return match future.as_mut().poll(context) {
+                        Poll::Ready(x) => {
+                            this.state = State::Done;
+                            Poll::Ready(x)
+                        },
+                        Poll::Pending => {
+                            Poll::Pending
+                        },
+                    };
+                },
+                State::Done => {
+                    panic!("`JsonBodyFuture` polled after completion.");
+                },
+            };
+
+            this.state = State::Calling(Box::pin(request));
+        }
+    }
+}
+
+
+
+/// a request body that has been validated by [`JsonBodyLayer`](./struct.JsonBodyLayer.html).
+#[derive(Debug, Clone)]
+pub struct JsonBody {
+    data: Option<Bytes>,
+}
+
+impl JsonBody {
+    /// consumes this body and returns its contents.
+    pub fn bytes(self) -> Bytes {
+        self.data.unwrap_or_default()
+    }
+}
+
+impl Body for JsonBody {
+    type Data  = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        Poll::Ready(self.get_mut().data.take().map(|x| Ok(Frame::data(x))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.data.as_ref().map_or(0, |x| x.len() as u64))
+    }
+}
+
+
+
+// applies a frame of the request body to `builder`, advancing `offset` past the bytes that were accepted.
+fn apply(builder: &mut JsonBuilder, offset: &mut usize, mut data: impl Buf) -> Result<(), JsonError> {
+    while data.has_remaining() {
+        let length = data.chunk().len();
+
+        builder.update_at(data.chunk(), offset)?;
+        data.advance(length);
+    }
+
+    Ok(())
+}
+
+fn reject<R: From<String>>(status: StatusCode, message: &str) -> Response<R> {
+    let mut response = Response::new(R::from(message.to_string()));
+
+    *response.status_mut() = status;
+    response.headers_mut().insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain; charset=utf-8"));
+    response
+}
@@ -1,20 +1,365 @@
+mod base64;
+mod borrow;
 mod build;
+mod cancel;
+mod checkpoint;
+mod coerce;
+mod defaults;
+mod diagnostics;
+mod diff;
+mod documents;
+mod explain;
+mod filter;
+mod events;
+mod fixed;
+mod forbidden;
+mod intern;
+mod keys;
+mod lines;
+mod lint;
+mod matches;
+mod merge;
+mod numbers;
+mod observe;
+mod offsets;
+mod options;
+mod patch;
+mod registry;
+mod reorder;
+mod repair;
+mod sniff;
+mod service;
+mod shape;
+mod spill;
+mod split;
+mod sse;
+mod steps;
+mod strings;
 mod table;
+mod temporal;
+mod tokens;
+mod trace;
+mod triage;
+mod truncate;
+mod unwrap;
 mod verify;
+mod websocket;
+
+#[cfg(feature = "actix")]
+mod actix;
+
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+mod binary;
+
+#[cfg(feature = "metrics")]
+mod metrics;
+
+#[cfg(feature = "serde")]
+mod ser;
+
+#[cfg(feature = "tower")]
+mod middleware;
+
+#[cfg(feature = "unicode")]
+mod unicode;
+
+#[cfg(feature = "visualize")]
+pub mod visualize;
+
+pub mod fsm;
+pub mod testing;
 
 
 
 pub use {
+    arya::base64::JsonBase64Decoder,
+    arya::borrow::JsonBorrowedBuilder,
     arya::build::JsonBuilder,
+    arya::build::JsonBuilderOptions,
+    arya::build::JsonReadChunks,
     arya::build::JsonSource,
+    arya::cancel::JsonCancel,
+    arya::checkpoint::JsonCheckpoints,
+    arya::coerce::JsonBigNumbers,
+    arya::coerce::JsonCoercion,
+    arya::coerce::JsonCoercionKind,
+    arya::defaults::JsonDefault,
+    arya::diagnostics::annotate,
+    arya::diagnostics::line_column,
+    arya::diagnostics::validate_all,
+    arya::diagnostics::validate_all_with_options,
+    arya::diagnostics::validate_batch,
+    arya::diagnostics::validate_padded,
+    arya::diagnostics::validate_with_context,
+    arya::diff::JsonChange,
+    arya::diff::JsonChangeKind,
+    arya::diff::diff_snapshots,
+    arya::documents::JsonDocuments,
+    arya::explain::Explanation,
+    arya::explain::explain,
+    arya::filter::JsonFilter,
+    arya::filter::JsonFilterSink,
+    arya::events::JsonValueKind,
+    arya::fixed::JsonFixedVerifier,
+    arya::forbidden::JsonForbiddenAction,
+    arya::forbidden::JsonForbiddenKeys,
+    arya::intern::JsonInterner,
+    arya::intern::JsonInterningVerifier,
+    arya::keys::JsonKeys,
+    arya::lines::JsonLines,
+    arya::lint::JsonDiagnostic,
+    arya::lint::JsonLint,
+    arya::lint::JsonLintRules,
+    arya::lint::lint,
+    arya::matches::matches,
+    arya::matches::matches_in_order,
+    arya::numbers::JsonNumber,
+    arya::numbers::parse_number,
+    arya::observe::JsonObserver,
+    arya::observe::JsonObservingVerifier,
+    arya::offsets::JsonOffset,
+    arya::offsets::JsonOffsets,
+    arya::options::JsonOptionsBuilder,
+    arya::registry::JsonBuilderMap,
+    arya::repair::JsonCandidate,
+    arya::repair::JsonLenient,
+    arya::repair::JsonNonFinite,
+    arya::repair::JsonRepair,
+    arya::repair::JsonRepairKind,
+    arya::repair::JsonRepairReport,
+    arya::repair::JsonStrategy,
+    arya::service::JsonValidatorService,
+    arya::shape::JsonShape,
+    arya::sniff::Confidence,
+    arya::sniff::JsonFormat,
+    arya::sniff::sniff,
+    arya::spill::JsonSpillBuilder,
+    arya::split::JsonArraySplitter,
+    arya::sse::JsonServerEvents,
+    arya::steps::JsonRepairTask,
+    arya::strings::decode_string,
+    arya::temporal::JsonTimestamp,
+    arya::temporal::JsonTimestampKind,
+    arya::temporal::JsonTimestamps,
+    arya::testing::replay,
+    arya::tokens::JsonToken,
+    arya::tokens::JsonTokenKind,
+    arya::tokens::JsonTokenizer,
+    arya::trace::JsonState,
+    arya::trace::JsonTransition,
+    arya::trace::TokenSink,
+    arya::triage::JsonOutcome,
+    arya::triage::JsonPartition,
+    arya::triage::JsonTriage,
+    arya::verify::JsonMemoryUsage,
+    arya::verify::JsonProgress,
     arya::verify::JsonVerifier,
+    arya::verify::JsonVerifierOptions,
+    arya::websocket::JsonWebSocket,
+};
+
+#[cfg(feature = "actix")]
+pub use {
+    arya::actix::RepairedJson,
+    arya::actix::RepairedJsonConfig,
+    arya::actix::RepairedJsonFuture,
+};
+
+#[cfg(feature = "metrics")]
+pub use {
+    arya::metrics::JsonMetrics,
+    arya::metrics::set_metrics,
+};
+
+#[cfg(feature = "rayon")]
+pub use {
+    arya::diagnostics::validate_batch_parallel,
+};
+
+#[cfg(feature = "serde")]
+pub use {
+    arya::ser::JsonSerializer,
+};
+
+#[cfg(feature = "tower")]
+pub use {
+    arya::middleware::JsonBody,
+    arya::middleware::JsonBodyFuture,
+    arya::middleware::JsonBodyLayer,
+    arya::middleware::JsonBodyService,
 };
 
 
 
 /// the error type for arya json errors.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum JsonError {
+///
+/// # remarks
+///
+/// `kind()` describes what went wrong, as a `Copy` [`JsonErrorKind`](./enum.JsonErrorKind.html) that can be matched
+/// and compared cheaply. an error may also know where it occurred - the byte `offset()` in the input stream - and, from
+/// [`validate_with_context()`](./fn.validate_with_context.html), what was `found()` there, what was `expected()`
+/// instead, and the `path()` to it.
+///
+/// that context is boxed, so an error without it is no larger than two words and does not allocate.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, validate_with_context};
+/// #
+/// # fn main() {
+/// #
+/// let error = validate_with_context(r#"{ "a": [1, 2 x] }"#).unwrap_err();
+///
+/// assert_eq!(error.kind(), JsonErrorKind::Invalid);
+/// assert_eq!((error.offset(), error.found(), error.path()), (Some(13), Some(b'x'), Some("/a/2")));
+/// assert_eq!(error.expected(), [",", "]"]);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    kind:    JsonErrorKind,
+    context: Option<Box<JsonErrorContext>>,
+}
+
+// where an error occurred, and for `validate_with_context()`, what was found there, what was expected instead, and the
+// json pointer to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct JsonErrorContext {
+    offset:   usize,
+    found:    Option<u8>,
+    expected: Vec<&'static str>,
+    path:     Option<String>,
+}
+
+impl JsonError {
+    /// creates an error of `kind`, without any context.
+    pub fn new(kind: JsonErrorKind) -> JsonError {
+        JsonError { kind, context: None }
+    }
+
+    /// creates an error of `kind` that occurred at byte `offset` in the input stream.
+    pub fn at(kind: JsonErrorKind, offset: usize) -> JsonError {
+        JsonError::new(kind).with_offset(offset)
+    }
+
+    /// returns what went wrong, for matching.
+    pub fn kind(&self) -> JsonErrorKind {
+        self.kind
+    }
+
+    /// the offset of the rejected byte, or the length of the input stream if it ended too early - or `None` if where
+    /// this error occurred is not known.
+    pub fn offset(&self) -> Option<usize> {
+        self.context.as_ref().map(|x| x.offset)
+    }
+
+    /// the rejected byte, or `None` if the input stream ended too early or the byte is not known.
+    pub fn found(&self) -> Option<u8> {
+        self.context.as_ref().and_then(|x| x.found)
+    }
+
+    /// a description of every token that would have been accepted instead, other than whitespace, such as `","`,
+    /// `"value"` or `"end of input"`. empty if they are not known.
+    pub fn expected(&self) -> &[&'static str] {
+        self.context.as_ref().map_or(&[], |x| &x.expected)
+    }
+
+    /// the json pointer ([rfc 6901](https://tools.ietf.org/html/rfc6901)) of the value that was being read, or of the
+    /// innermost container if no value was - or `None` if it is not known.
+    pub fn path(&self) -> Option<&str> {
+        self.context.as_ref().and_then(|x| x.path.as_deref())
+    }
+
+    /// a short, machine-readable name for this error. see
+    /// [`JsonErrorKind::name()`](./enum.JsonErrorKind.html#method.name).
+    pub fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    // returns this error as having occurred at byte `offset`, keeping any other context it has.
+    crate fn with_offset(mut self, offset: usize) -> JsonError {
+        match &mut self.context {
+            Some(x) => x.offset = offset,
+            None    => {
+                self.context = Some(Box::new(JsonErrorContext { offset, found: None, expected: vec![], path: None }));
+            },
+        }
+
+        self
+    }
+
+    // returns this error with what was found at where it occurred, what was expected instead, and the path to it.
+    crate fn with_context(
+        self,
+        offset:   usize,
+        found:    Option<u8>,
+        expected: Vec<&'static str>,
+        path:     String) -> JsonError
+    {
+        let context = JsonErrorContext { offset, found, expected, path: Some(path) };
+
+        JsonError { kind: self.kind, context: Some(Box::new(context)) }
+    }
+}
+
+impl From<JsonErrorKind> for JsonError {
+    fn from(kind: JsonErrorKind) -> JsonError {
+        JsonError::new(kind)
+    }
+}
+
+impl PartialEq<JsonErrorKind> for JsonError {
+    fn eq(&self, other: &JsonErrorKind) -> bool {
+        self.kind == *other
+    }
+}
+
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+
+        let context = match &self.context {
+            Some(x) => x,
+            None    => return Ok(()),
+        };
+
+        write!(f, " (at byte {}", context.offset)?;
+
+        if let Some(path) = context.path.as_ref().filter(|x| !x.is_empty()) {
+            write!(f, ", in {}", path)?;
+        }
+
+        write!(f, ")")?;
+
+        if !context.expected.is_empty() {
+            let expected = context.expected.iter().map(|x| format!("`{}`", x)).collect::<Vec<_>>();
+
+            write!(f, ": expected {}", expected.join(" or "))?;
+
+            match context.found {
+                Some(x) if x.is_ascii_graphic() => write!(f, ", found `{}`", x as char)?,
+                Some(x)                         => write!(f, ", found byte 0x{:02x}", x)?,
+                None                            => write!(f, ", found end of input")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+
+
+/// what went wrong in a [`JsonError`](./struct.JsonError.html).
+///
+/// # remarks
+///
+/// new kinds of errors may be added without a breaking change, so matches on this type need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum JsonErrorKind {
     /// the input stream is not valid json.
     Invalid,
 
@@ -23,6 +368,380 @@ pub enum JsonError {
 
     /// parse failed because the input stream contained an object exceeding the maximum specified depth.
     Exceeded,
+
+    /// a value was larger than the maximum specified size.
+    TooLarge,
+
+    /// a `serde::Serialize` implementation failed, or produced a value that cannot be represented in json.
+    Serialize,
+
+    /// the input stream was not valid base64.
+    Base64,
+
+    /// writing to an output sink failed.
+    Io,
+
+    /// the input stream contained a string longer than the maximum specified length.
+    StringTooLong,
+
+    /// the input stream contained an object key longer than the maximum specified length.
+    KeyTooLong,
+
+    /// the input stream contained a number longer than the maximum specified length.
+    NumberTooLong,
+
+    /// the input stream was not valid cbor or messagepack.
+    Binary,
+
+    /// completing an incomplete json object would have appended or dropped more bytes than the maximum specified.
+    CompletionTooLarge,
+
+    /// the options given to a builder cannot be used together.
+    Options,
+
+    /// an update was cancelled, or its deadline passed. see [`JsonCancel`](./struct.JsonCancel.html).
+    Cancelled,
+
+    /// a json object that must be kept byte for byte was modified. see
+    /// [`JsonBuilderOptions::round_trip`](./struct.JsonBuilderOptions.html#structfield.round_trip).
+    Modified,
+
+    /// the input stream was a json string, but it did not contain an escaped json object. see
+    /// [`JsonBuilder::unwrap_escaped()`](./struct.JsonBuilder.html#method.unwrap_escaped).
+    NotEscaped,
+}
+
+impl std::fmt::Display for JsonErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        use arya::JsonErrorKind::*;
+
+        match self {
+            Invalid            => write!(f, "the input stream is not valid json"),
+            Utf8               => write!(f, "the input stream is not a valid utf8 sequence"),
+            Exceeded           => write!(f, "the input stream exceeded the maximum specified depth"),
+            TooLarge           => write!(f, "a value exceeded the maximum specified size"),
+            Serialize          => write!(f, "the value could not be serialized into json"),
+            Base64             => write!(f, "the input stream is not valid base64"),
+            Io                 => write!(f, "writing to the output sink failed"),
+            StringTooLong      => write!(f, "a string exceeded the maximum specified length"),
+            KeyTooLong         => write!(f, "an object key exceeded the maximum specified length"),
+            NumberTooLong      => write!(f, "a number exceeded the maximum specified length"),
+            Binary             => write!(f, "the input stream is not valid cbor or messagepack"),
+            CompletionTooLarge => write!(f, "completing the input stream needed more changes than allowed"),
+            Options            => write!(f, "the options cannot be used together"),
+            Cancelled          => write!(f, "the update was cancelled, or its deadline passed"),
+            Modified           => write!(f, "the json object was modified, but must be kept byte for byte"),
+            NotEscaped         => write!(f, "the input stream is a string that does not contain json"),
+        }
+    }
+}
+
+impl JsonErrorKind {
+    /// a short, machine-readable name for this kind of error, such as `"invalid"` or `"string_too_long"`, that does not
+    /// change between versions.
+    pub fn name(&self) -> &'static str {
+        match self {
+            JsonErrorKind::Invalid            => "invalid",
+            JsonErrorKind::Utf8               => "utf8",
+            JsonErrorKind::Exceeded           => "exceeded",
+            JsonErrorKind::TooLarge           => "too_large",
+            JsonErrorKind::Serialize          => "serialize",
+            JsonErrorKind::Base64             => "base64",
+            JsonErrorKind::Io                 => "io",
+            JsonErrorKind::StringTooLong      => "string_too_long",
+            JsonErrorKind::KeyTooLong         => "key_too_long",
+            JsonErrorKind::NumberTooLong      => "number_too_long",
+            JsonErrorKind::Binary             => "binary",
+            JsonErrorKind::CompletionTooLarge => "completion_too_large",
+            JsonErrorKind::Options            => "options",
+            JsonErrorKind::Cancelled          => "cancelled",
+            JsonErrorKind::Modified           => "modified",
+            JsonErrorKind::NotEscaped         => "not_escaped",
+        }
+    }
+}
+
+
+
+/// how whitespace other than the four whitespace characters allowed by rfc 8259 (space, tab, line feed, and carriage
+/// return) is handled between tokens.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonWhitespace};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.whitespace = JsonWhitespace::Normalize;
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update("[1,\u{a0}2,\u{2028}\"\u{a0}\"]").unwrap();
+///
+/// assert_eq!(builder.string().unwrap(), "[1, 2, \"\u{a0}\"]");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonWhitespace {
+    /// non-ascii characters are accepted anywhere. other ascii whitespace (vertical tab, form feed) is rejected.
+    Permissive,
+
+    /// only rfc 8259 whitespace is accepted between tokens. non-ascii characters are only accepted inside strings.
+    Strict,
+
+    /// like `Strict`, but unicode whitespace (vertical tab, form feed, no-break space, u+2028, the byte order mark, and
+    /// so on) is also accepted between tokens. a [`JsonBuilder`](./struct.JsonBuilder.html) replaces each one with a
+    /// single space.
+    Normalize,
+}
+
+
+
+/// how bytes after a complete json object are handled.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonErrorKind, JsonStatus, JsonTrailing};
+/// # use arya::{JsonVerifier, JsonVerifierOptions};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.trailing = JsonTrailing::Ignore;
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "a": 1 } <|end|>"#).unwrap();
+///
+/// assert_eq!(builder.string().unwrap(), r#"{ "a": 1 }"#);
+///
+/// let mut options = JsonVerifierOptions::default();
+///
+/// options.trailing = JsonTrailing::BeginNextDocument;
+///
+/// let mut verifier = JsonVerifier::with_options(options);
+///
+/// for character in br#"{ "a": 1 } [2"#.iter() {
+///     verifier.update(*character).unwrap();
+/// }
+///
+/// assert_eq!(verifier.status(), JsonStatus::Continue);
+/// assert_eq!(verifier.update(b'x'), Err(JsonErrorKind::Invalid.into()));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonTrailing {
+    /// only whitespace is accepted after a complete json object. anything else is rejected.
+    Reject,
+
+    /// every byte after a complete json object is accepted and ignored - a
+    /// [`JsonBuilder`](./struct.JsonBuilder.html) does not add it to the json object.
+    Ignore,
+
+    /// a `{` or `[` after a complete json object begins the next json object, which replaces it. anything else other
+    /// than whitespace is rejected.
+    ///
+    /// a [`JsonBuilder`](./struct.JsonBuilder.html) finishes writing the complete json object to its sink and is
+    /// `reset()`, so it keeps its sink and [`on_complete()`](./struct.JsonBuilder.html#method.on_complete) callback.
+    BeginNextDocument,
+}
+
+
+
+/// when the contents of strings - their escape sequences and utf8 - are checked by a
+/// [`JsonVerifier`](./struct.JsonVerifier.html).
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonStatus, JsonStrings, JsonVerifier, JsonVerifierOptions};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonVerifierOptions::default();
+///
+/// options.strings = JsonStrings::Deferred;
+///
+/// let source       = br#"{ "a": "\x", "b": "ok" }"#;
+/// let mut verifier = JsonVerifier::with_options(options);
+///
+/// for character in source.iter() {
+///     verifier.update(*character).unwrap();
+/// }
+///
+/// assert_eq!(verifier.status(), JsonStatus::Valid);
+/// assert_eq!(verifier.finalize_strings(&source[..]).unwrap_err().offset(), Some(9));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonStrings {
+    /// escape sequences are checked as they are read. utf8 is not checked.
+    Eager,
+
+    /// only the structure of strings is checked as they are read - any byte is accepted after a backslash. the
+    /// strings that contain escape sequences or non-ascii bytes are recorded, and their escape sequences and utf8 are
+    /// checked later by [`JsonVerifier::finalize_strings()`](./struct.JsonVerifier.html#method.finalize_strings).
+    Deferred,
+}
+
+
+
+/// how much a [`JsonVerifier`](./struct.JsonVerifier.html) records about its input, beyond whether it is valid.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBookkeeping, JsonStatus, JsonVerifier, JsonVerifierOptions};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonVerifierOptions::default();
+///
+/// options.bookkeeping = JsonBookkeeping::Minimal;
+///
+/// let mut verifier = JsonVerifier::with_options(options);
+///
+/// for character in br#"{ "a": [1, 2, { "b": null }] }"#.iter() {
+///     verifier.update(*character).unwrap();
+/// }
+///
+/// assert_eq!(verifier.status(), JsonStatus::Valid);
+/// assert_eq!(verifier.len(), 0);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonBookkeeping {
+    /// the number of bytes read, and the points the input could be truncated to for completion, are recorded.
+    Full,
+
+    /// only the state and the stack of open containers are kept, for pure accept or reject validation where every
+    /// cycle counts. nothing grows with the length of the input - only with its depth, which `maximum_depth` bounds.
+    ///
+    /// `len()` and the byte counts of `progress()` stay at zero, as do the offsets seen by trace mode and
+    /// [`TokenSink`](./trait.TokenSink.html)s, and `JsonStrings::Deferred` is treated as `JsonStrings::Eager`.
+    Minimal,
+}
+
+
+
+/// how an incomplete string is handled when a [`JsonBuilder`](./struct.JsonBuilder.html) completes a json object.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonIncomplete};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.incomplete_keys = JsonIncomplete::Keep;
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "a": 1, "ke"#).unwrap();
+///
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "a": 1, "ke":null}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonIncomplete {
+    /// the incomplete string is dropped, along with the rest of the member or element it belongs to.
+    Drop,
+
+    /// the incomplete string is kept and closed. an incomplete escape sequence or utf8 sequence at its end is dropped.
+    ///
+    /// an incomplete key is also given a `null` value.
+    Keep,
+}
+
+
+
+/// what a [`JsonBuilder`](./struct.JsonBuilder.html) puts in place of an incomplete value that completion drops, so
+/// that downstream systems can tell which values were synthesized.
+///
+/// # remarks
+///
+/// this applies to a value that has been started but cannot be completed - such as `tr`, `-` or an incomplete string
+/// value whose `incomplete_strings` is `Drop` - and to a value that is expected but has not been started, after a
+/// `:` or after a `,` in an array.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonElided};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.elided_members  = JsonElided::Sentinel("__TRUNCATED__".into());
+/// options.elided_elements = JsonElided::Null;
+///
+/// let mut builder = JsonBuilder::with_options(options.clone());
+///
+/// builder.update(r#"{ "a": [1, 2, tr"#).unwrap();
+///
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "a": [1, 2, null]}"#);
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "a": [1, 2], "b": "lor"#).unwrap();
+///
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "a": [1, 2], "b": "__TRUNCATED__"}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonElided {
+    /// the incomplete value is dropped, along with the rest of the member or element it belongs to.
+    Drop,
+
+    /// the incomplete value is replaced with `null`.
+    Null,
+
+    /// the incomplete value is replaced with `""`.
+    EmptyString,
+
+    /// the incomplete value is replaced with a string holding this text.
+    Sentinel(String),
+}
+
+
+
+/// where a [`JsonBuilder`](./struct.JsonBuilder.html) places the closing tokens it appends to complete a json object.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonClosers};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.closers = JsonClosers::Indented;
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update("{\n  \"a\": [\n    1,\n    2").unwrap();
+///
+/// assert_eq!(builder.completed_string().unwrap(), "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonClosers {
+    /// the closing tokens are appended directly after the last value, such as `2]}`.
+    Inline,
+
+    /// if the json object is pretty-printed, each closing token is placed on its own line, indented to the depth of
+    /// the container it closes. the indentation is detected from the first indented line, and the line ending from
+    /// the first line break. json that is not pretty-printed is completed like `Inline`.
+    Indented,
 }
 
 
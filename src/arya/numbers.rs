@@ -0,0 +1,99 @@
+use {
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonVerifier,
+    arya::coerce,
+};
+
+
+
+/// a json number, parsed by [`parse_number()`](./fn.parse_number.html).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonNumber<'a> {
+    /// an integer that fits in an `i64`.
+    I64(i64),
+
+    /// a positive integer that does not fit in an `i64`, but fits in a `u64`.
+    U64(u64),
+
+    /// a number with a fraction or an exponent, whose decimal value is exactly represented by the nearest `f64`.
+    F64(f64),
+
+    /// a number that would lose precision as any of the above, such as `12345678901234567890123` or `1e999`, as it
+    /// appears in the input.
+    Big(&'a str),
+}
+
+
+
+/// parses the json number `span`.
+///
+/// # remarks
+///
+/// `span` must be exactly one json number, as accepted by a [`JsonVerifier`](./struct.JsonVerifier.html) - the
+/// grammar is checked by a verifier, so the two can never disagree. integers are parsed as an `i64` or `u64` if they
+/// fit. other numbers are parsed as an `f64` if that does not change their decimal value. everything else is returned
+/// as text, so that it can be handed to an arbitrary-precision library instead of being silently rounded.
+///
+/// fails with `JsonErrorKind::Invalid` if `span` is not a json number.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, JsonNumber, parse_number};
+/// #
+/// # fn main() {
+/// #
+/// assert_eq!(parse_number("-42"),                   Ok(JsonNumber::I64(-42)));
+/// assert_eq!(parse_number("18446744073709551615"),  Ok(JsonNumber::U64(18446744073709551615)));
+/// assert_eq!(parse_number("1.5e3"),                 Ok(JsonNumber::F64(1500.0)));
+/// assert_eq!(parse_number("1e999"),                 Ok(JsonNumber::Big("1e999")));
+/// assert_eq!(parse_number("0.1000000000000000001"), Ok(JsonNumber::Big("0.1000000000000000001")));
+///
+/// assert_eq!(parse_number("01"), Err(JsonErrorKind::Invalid.into()));
+/// assert_eq!(parse_number("1."), Err(JsonErrorKind::Invalid.into()));
+/// assert_eq!(parse_number("+1"), Err(JsonErrorKind::Invalid.into()));
+/// # }
+/// ```
+pub fn parse_number<'a>(span: &'a (impl JsonSource + ?Sized)) -> Result<JsonNumber<'a>, JsonError> {
+    let span = span.stream();
+
+    if !is_number(span) {
+        return Err(JsonErrorKind::Invalid.into());
+    }
+
+    // every byte of a number is ascii.
+    let text = std::str::from_utf8(span).map_err(|_| JsonError::from(JsonErrorKind::Invalid))?;
+
+    if !text.contains(['.', 'e', 'E']) {
+        if let Ok(x) = text.parse::<i64>() {
+            return Ok(JsonNumber::I64(x));
+        }
+
+        if let Ok(x) = text.parse::<u64>() {
+            return Ok(JsonNumber::U64(x));
+        }
+
+        return Ok(JsonNumber::Big(text));
+    }
+
+    match coerce::is_big(span) {
+        true  => Ok(JsonNumber::Big(text)),
+        false => text.parse::<f64>().map(JsonNumber::F64).map_err(|_| JsonError::from(JsonErrorKind::Invalid)),
+    }
+}
+
+// true if `span` is exactly one number, according to a verifier. the number is read as the only element of an array,
+// and every byte of it must leave the verifier inside a number.
+fn is_number(span: &[u8]) -> bool {
+    let mut verifier = JsonVerifier::new();
+
+    if span.is_empty() || verifier.update(b'[').is_err() {
+        return false;
+    }
+
+    let number = span.iter().all(|x| verifier.update(*x).is_ok() && verifier.token().is_number());
+
+    number && verifier.in_number()
+}
@@ -0,0 +1,156 @@
+use {
+    std::mem,
+
+    arya::JsonError,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonTransition,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::TokenSink,
+};
+
+
+
+/// receives the structural changes a [`JsonObservingVerifier`](./struct.JsonObservingVerifier.html) reads.
+///
+/// every method does nothing by default, so an observer only implements the ones it needs.
+pub trait JsonObserver {
+    /// called when an object or array is opened by the token at `offset`. `depth` is the number of containers that are
+    /// open, including this one.
+    fn opened(&mut self, kind: JsonValueKind, offset: usize, depth: usize) {
+        let _ = (kind, offset, depth);
+    }
+
+    /// called when the innermost object or array is closed by the token at `offset`. `depth` is the number of
+    /// containers that are still open.
+    fn closed(&mut self, offset: usize, depth: usize) {
+        let _ = (offset, depth);
+    }
+
+    /// called when the json object becomes complete. `length` is the number of bytes read so far.
+    fn completed(&mut self, length: usize) {
+        let _ = length;
+    }
+}
+
+
+
+/// a json syntax validator that reports containers being opened and closed, and the json object completing, to an
+/// observer as they are read.
+///
+/// # remarks
+///
+/// this is lighter than the other collectors: the observer is driven as the verifier's
+/// [`TokenSink`](./trait.TokenSink.html), from the depth of each transition, so it can drive progress reporting or
+/// custom streaming logic on large inputs. the observer is kept across `reset()`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonObserver, JsonObservingVerifier, JsonValueKind};
+/// #
+/// #[derive(Default)]
+/// struct Deepest(usize);
+///
+/// impl JsonObserver for Deepest {
+///     fn opened(&mut self, _: JsonValueKind, _: usize, depth: usize) {
+///         self.0 = self.0.max(depth);
+///     }
+/// }
+///
+/// # fn main() {
+/// #
+/// let mut verifier = JsonObservingVerifier::new(Deepest::default());
+///
+/// verifier.update(r#"{ "a": [1, { "b": [] }], "c": {} }"#).unwrap();
+///
+/// assert_eq!(verifier.observer().0, 4);
+/// # }
+/// ```
+pub struct JsonObservingVerifier<T: JsonObserver> {
+    verifier: JsonVerifier<Observed<T>>,
+}
+
+impl<T: JsonObserver> JsonObservingVerifier<T> {
+    pub fn new(observer: T) -> JsonObservingVerifier<T> {
+        JsonObservingVerifier::with_options(observer, JsonVerifierOptions::default())
+    }
+
+    pub fn with_options(observer: T, options: JsonVerifierOptions) -> JsonObservingVerifier<T> {
+        JsonObservingVerifier { verifier: JsonVerifier::with_sink(options, Observed { observer, depth: 0 }) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.verifier.len()
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.verifier.status()
+    }
+
+    pub fn reset(&mut self) {
+        self.verifier.reset();
+        self.verifier.sink_mut().depth = 0;
+    }
+
+    pub fn verifier(&self) -> &JsonVerifier<impl TokenSink> {
+        &self.verifier
+    }
+
+    pub fn observer(&mut self) -> &mut T {
+        &mut self.verifier.sink_mut().observer
+    }
+
+    pub fn into_observer(self) -> T {
+        self.verifier.into_sink().observer
+    }
+
+    /// applies `source` to this json object.
+    ///
+    /// like [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), an invalid character is rejected
+    /// without changing any state - and without reaching the observer.
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        for &character in source.stream() {
+            self.verifier.update(character)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+
+// drives an observer from the transitions of a verifier, and the depth before the last one.
+struct Observed<T> {
+    observer: T,
+    depth:    usize,
+}
+
+impl<T: JsonObserver> TokenSink for Observed<T> {
+    fn transition(&mut self, transition: &JsonTransition) {
+        let depth = mem::replace(&mut self.depth, transition.depth);
+
+        match transition.depth {
+            x if x > depth => self.observer.opened(kind(transition.character), transition.offset, x),
+            x if x < depth => self.observer.closed(transition.offset, x),
+            _              => {},
+        }
+
+        // closing the outermost container leaves the verifier complete.
+        if transition.depth == 0 && depth > 0 {
+            self.observer.completed(transition.offset + 1);
+        }
+    }
+}
+
+
+
+// returns the kind of container the token `character` opens.
+fn kind(character: u8) -> JsonValueKind {
+    match character {
+        b'{' => JsonValueKind::Object,
+        _    => JsonValueKind::Array,
+    }
+}
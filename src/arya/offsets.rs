@@ -0,0 +1,195 @@
+use {
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::events,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::strings,
+};
+
+
+
+/// the location of a single value in a json object.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonOffset {
+    /// the json pointer ([rfc 6901](https://tools.ietf.org/html/rfc6901)) to this value.
+    ///
+    /// object keys are unescaped, so the member `"a\/b"` has the segment `a~1b`, like the member `"a/b"` does.
+    pub path:  String,
+
+    /// the kind of this value.
+    pub kind:  JsonValueKind,
+
+    /// the byte range of this value's key (including quotes), if this value is an object member.
+    pub key:   Option<Range<usize>>,
+
+    /// the byte range of this value. strings include their quotes.
+    pub range: Range<usize>,
+}
+
+
+
+/// a json syntax validator that also records the byte range of every key and value it sees.
+///
+/// # remarks
+///
+/// recording offsets is more expensive than validating alone. use a [`JsonVerifier`](./struct.JsonVerifier.html) if
+/// only validation is needed.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonOffsets, JsonValueKind};
+/// #
+/// # fn main() {
+/// #
+/// let source = r#"{ "name": "annie", "tags": [1, true] }"#;
+/// let mut offsets = JsonOffsets::new();
+///
+/// offsets.update(source).unwrap();
+///
+/// let entries = offsets.entries().unwrap();
+///
+/// assert_eq!(entries[1].path, "/name");
+/// assert_eq!(&source[entries[1].range.clone()], r#""annie""#);
+///
+/// assert_eq!(entries[4].path, "/tags/1");
+/// assert_eq!(entries[4].kind, JsonValueKind::Boolean);
+///
+/// let mut offsets = JsonOffsets::new();
+///
+/// offsets.update(r#"{ "a\/b~": 1 }"#).unwrap();
+///
+/// assert_eq!(offsets.entries().unwrap()[1].path, "/a~1b~0");
+/// # }
+/// ```
+pub struct JsonOffsets {
+    reader:  EventReader,
+    entries: Vec<JsonOffset>,
+
+    // one frame per open container: the index of its entry, the length of its path, and the number of values seen.
+    frames:  Vec<(usize, usize, usize)>,
+    path:    String,
+    key:     Option<(Range<usize>, String)>,
+}
+
+impl JsonOffsets {
+    pub fn new() -> JsonOffsets {
+        JsonOffsets::with_maximum_depth(std::usize::MAX)
+    }
+
+    pub fn with_maximum_depth(maximum_depth: usize) -> JsonOffsets {
+        JsonOffsets {
+            reader:  EventReader::new(JsonVerifier::with_maximum_depth(maximum_depth)),
+            entries: vec![],
+
+            frames:  vec![],
+            path:    String::new(),
+            key:     None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.reader.offset()
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.reader.verifier().status()
+    }
+
+    pub fn reset(&mut self) {
+        self.reader.reset();
+        self.entries.clear();
+        self.frames.clear();
+        self.path.clear();
+        self.key = None;
+    }
+
+    /// applies `source` to this json object.
+    ///
+    /// like [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), an invalid character is rejected
+    /// without changing any state.
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        let JsonOffsets { reader, entries, frames, path, key } = self;
+
+        for &character in source.stream() {
+            reader.update(character, |event| {
+                match event {
+                    Event::Begin(kind, offset) => {
+                        let index = entries.len();
+
+                        entries.push(JsonOffset::new(path, frames, key, kind, offset..offset));
+                        frames.push((index, path.len(), 0));
+                        path.clone_from(&entries[index].path);
+                    },
+                    Event::End(offset) => {
+                        if let Some((index, length, _)) = frames.pop() {
+                            entries[index].range.end = offset + 1;
+                            path.truncate(length);
+                        }
+                    },
+                    Event::Key(range, text) => {
+                        *key = Some((range, strings::decode_key(text)));
+                    },
+                    Event::Scalar(kind, range) => {
+                        entries.push(JsonOffset::new(path, frames, key, kind, range));
+                    },
+                }
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// returns the offsets of every value, in the order they appear in the input, if this json object is complete.
+    pub fn entries(&self) -> Option<&[JsonOffset]> {
+        match self.status() {
+            JsonStatus::Valid    => Some(&self.entries),
+            JsonStatus::Continue => None,
+        }
+    }
+
+    /// consumes this validator, returning the offsets of every value if this json object is complete.
+    pub fn into_entries(self) -> Result<Vec<JsonOffset>, JsonError> {
+        match self.status() {
+            JsonStatus::Valid    => Ok(self.entries),
+            JsonStatus::Continue => Err(JsonErrorKind::Invalid.into()),
+        }
+    }
+}
+
+impl JsonOffset {
+    fn new(
+        path:   &str,
+        frames: &mut [(usize, usize, usize)],
+        key:    &mut Option<(Range<usize>, String)>,
+        kind:   JsonValueKind,
+        range:  Range<usize>) -> JsonOffset
+    {
+        let mut path = path.to_string();
+        let mut span = None;
+
+        if let Some((_, _, count)) = frames.last_mut() {
+            match key.take() {
+                Some((range, text)) => {
+                    span = Some(range);
+                    events::push_key(&mut path, &text);
+                },
+                None => {
+                    path.push('/');
+                    path.push_str(&count.to_string());
+                },
+            }
+
+            *count += 1;
+        }
+
+        JsonOffset { path, kind, key: span, range }
+    }
+}
@@ -0,0 +1,288 @@
+use {
+    arya::JsonBigNumbers,
+    arya::JsonBuilderOptions,
+    arya::JsonClosers,
+    arya::JsonCoercion,
+    arya::JsonDefault,
+    arya::JsonElided,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonForbiddenKeys,
+    arya::JsonIncomplete,
+    arya::JsonLenient,
+    arya::JsonNonFinite,
+    arya::JsonShape,
+    arya::JsonTrailing,
+    arya::JsonWhitespace,
+};
+
+
+
+/// a fluent builder for [`JsonBuilderOptions`](./struct.JsonBuilderOptions.html) that checks the options for
+/// combinations that cannot work before they are used.
+///
+/// # remarks
+///
+/// the builder starts from `JsonBuilderOptions::default()`, or from any other options - such as a preset like
+/// [`JsonBuilderOptions::llm()`](./struct.JsonBuilderOptions.html#method.llm) - with `From`. `build()` fails with
+/// `JsonErrorKind::Options` if:
+///
+/// - `maximum_depth` is `0`, which rejects every json object.
+/// - `lenient.non_finite` repairs `NaN` and `Infinity`, but `lenient.literals` is disabled, so they are never read.
+/// - `maximum_number_length` is `0`, which rejects every number.
+/// - a coercion has a json pointer that is neither empty nor begins with `/`, so it can never match.
+/// - a default has a json pointer that does not begin with `/`, or a value that is not a single json value.
+///
+/// options whose fields are set directly are not checked until they are passed to
+/// [`JsonBuilderOptions::validate()`](./struct.JsonBuilderOptions.html#method.validate), which makes the same checks.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonErrorKind, JsonIncomplete, JsonNonFinite};
+/// #
+/// # fn main() {
+/// #
+/// let options = JsonBuilderOptions::builder()
+///     .maximum_depth(16)
+///     .incomplete_strings(JsonIncomplete::Keep)
+///     .build()
+///     .unwrap();
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "a": "lor"#).unwrap();
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "a": "lor"}"#);
+///
+/// let options = JsonBuilderOptions::builder()
+///     .non_finite(JsonNonFinite::Null)
+///     .build();
+///
+/// assert_eq!(options, Err(JsonErrorKind::Options.into()));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonOptionsBuilder {
+    options: JsonBuilderOptions,
+}
+
+impl JsonOptionsBuilder {
+    pub fn new() -> JsonOptionsBuilder {
+        JsonOptionsBuilder::default()
+    }
+
+    /// sets [`JsonBuilderOptions::maximum_depth`](./struct.JsonBuilderOptions.html#structfield.maximum_depth).
+    pub fn maximum_depth(mut self, maximum_depth: usize) -> JsonOptionsBuilder {
+        self.options.maximum_depth = maximum_depth;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::initial_capacity`](./struct.JsonBuilderOptions.html#structfield.initial_capacity).
+    pub fn initial_capacity(mut self, initial_capacity: usize) -> JsonOptionsBuilder {
+        self.options.initial_capacity = initial_capacity;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::whitespace`](./struct.JsonBuilderOptions.html#structfield.whitespace).
+    pub fn whitespace(mut self, whitespace: JsonWhitespace) -> JsonOptionsBuilder {
+        self.options.whitespace = whitespace;
+        self
+    }
+
+    /// sets
+    /// [`JsonBuilderOptions::maximum_string_length`](./struct.JsonBuilderOptions.html#structfield.maximum_string_length).
+    pub fn maximum_string_length(mut self, maximum_string_length: usize) -> JsonOptionsBuilder {
+        self.options.maximum_string_length = maximum_string_length;
+        self
+    }
+
+    /// sets
+    /// [`JsonBuilderOptions::maximum_key_length`](./struct.JsonBuilderOptions.html#structfield.maximum_key_length).
+    pub fn maximum_key_length(mut self, maximum_key_length: usize) -> JsonOptionsBuilder {
+        self.options.maximum_key_length = maximum_key_length;
+        self
+    }
+
+    /// sets
+    /// [`JsonBuilderOptions::maximum_number_length`](./struct.JsonBuilderOptions.html#structfield.maximum_number_length).
+    pub fn maximum_number_length(mut self, maximum_number_length: usize) -> JsonOptionsBuilder {
+        self.options.maximum_number_length = maximum_number_length;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::incomplete_keys`](./struct.JsonBuilderOptions.html#structfield.incomplete_keys).
+    pub fn incomplete_keys(mut self, incomplete_keys: JsonIncomplete) -> JsonOptionsBuilder {
+        self.options.incomplete_keys = incomplete_keys;
+        self
+    }
+
+    /// sets
+    /// [`JsonBuilderOptions::incomplete_strings`](./struct.JsonBuilderOptions.html#structfield.incomplete_strings).
+    pub fn incomplete_strings(mut self, incomplete_strings: JsonIncomplete) -> JsonOptionsBuilder {
+        self.options.incomplete_strings = incomplete_strings;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::elided_members`](./struct.JsonBuilderOptions.html#structfield.elided_members).
+    pub fn elided_members(mut self, elided_members: JsonElided) -> JsonOptionsBuilder {
+        self.options.elided_members = elided_members;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::elided_elements`](./struct.JsonBuilderOptions.html#structfield.elided_elements).
+    pub fn elided_elements(mut self, elided_elements: JsonElided) -> JsonOptionsBuilder {
+        self.options.elided_elements = elided_elements;
+        self
+    }
+
+    /// replaces every lenient repair with `lenient`.
+    pub fn lenient(mut self, lenient: JsonLenient) -> JsonOptionsBuilder {
+        self.options.lenient = lenient;
+        self
+    }
+
+    /// enables or disables [`JsonLenient::brackets`](./struct.JsonLenient.html#structfield.brackets).
+    pub fn brackets(mut self, enabled: bool) -> JsonOptionsBuilder {
+        self.options.lenient.brackets = enabled;
+        self
+    }
+
+    /// enables or disables [`JsonLenient::commas`](./struct.JsonLenient.html#structfield.commas).
+    pub fn commas(mut self, enabled: bool) -> JsonOptionsBuilder {
+        self.options.lenient.commas = enabled;
+        self
+    }
+
+    /// enables or disables [`JsonLenient::colons`](./struct.JsonLenient.html#structfield.colons).
+    pub fn colons(mut self, enabled: bool) -> JsonOptionsBuilder {
+        self.options.lenient.colons = enabled;
+        self
+    }
+
+    /// enables or disables [`JsonLenient::literals`](./struct.JsonLenient.html#structfield.literals).
+    pub fn literals(mut self, enabled: bool) -> JsonOptionsBuilder {
+        self.options.lenient.literals = enabled;
+        self
+    }
+
+    /// sets [`JsonLenient::non_finite`](./struct.JsonLenient.html#structfield.non_finite).
+    pub fn non_finite(mut self, non_finite: JsonNonFinite) -> JsonOptionsBuilder {
+        self.options.lenient.non_finite = non_finite;
+        self
+    }
+
+    /// enables or disables [`JsonLenient::punctuation`](./struct.JsonLenient.html#structfield.punctuation).
+    pub fn punctuation(mut self, enabled: bool) -> JsonOptionsBuilder {
+        self.options.lenient.punctuation = enabled;
+        self
+    }
+
+    /// enables or disables [`JsonLenient::control`](./struct.JsonLenient.html#structfield.control).
+    pub fn control(mut self, enabled: bool) -> JsonOptionsBuilder {
+        self.options.lenient.control = enabled;
+        self
+    }
+
+    /// adds `coercion` to [`JsonBuilderOptions::coercions`](./struct.JsonBuilderOptions.html#structfield.coercions).
+    pub fn coercion(mut self, coercion: JsonCoercion) -> JsonOptionsBuilder {
+        self.options.coercions.push(coercion);
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::big_numbers`](./struct.JsonBuilderOptions.html#structfield.big_numbers).
+    pub fn big_numbers(mut self, big_numbers: JsonBigNumbers) -> JsonOptionsBuilder {
+        self.options.big_numbers = big_numbers;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::maximum_inserted`](./struct.JsonBuilderOptions.html#structfield.maximum_inserted).
+    pub fn maximum_inserted(mut self, maximum_inserted: usize) -> JsonOptionsBuilder {
+        self.options.maximum_inserted = maximum_inserted;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::maximum_truncated`](./struct.JsonBuilderOptions.html#structfield.maximum_truncated).
+    pub fn maximum_truncated(mut self, maximum_truncated: usize) -> JsonOptionsBuilder {
+        self.options.maximum_truncated = maximum_truncated;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::closers`](./struct.JsonBuilderOptions.html#structfield.closers).
+    pub fn closers(mut self, closers: JsonClosers) -> JsonOptionsBuilder {
+        self.options.closers = closers;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::shape`](./struct.JsonBuilderOptions.html#structfield.shape).
+    pub fn shape(mut self, shape: JsonShape) -> JsonOptionsBuilder {
+        self.options.shape = Some(shape);
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::trailing`](./struct.JsonBuilderOptions.html#structfield.trailing).
+    pub fn trailing(mut self, trailing: JsonTrailing) -> JsonOptionsBuilder {
+        self.options.trailing = trailing;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::forbidden_keys`](./struct.JsonBuilderOptions.html#structfield.forbidden_keys).
+    pub fn forbidden_keys(mut self, forbidden_keys: JsonForbiddenKeys) -> JsonOptionsBuilder {
+        self.options.forbidden_keys = Some(forbidden_keys);
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::round_trip`](./struct.JsonBuilderOptions.html#structfield.round_trip).
+    pub fn round_trip(mut self, round_trip: bool) -> JsonOptionsBuilder {
+        self.options.round_trip = round_trip;
+        self
+    }
+
+    /// sets [`JsonBuilderOptions::normalize_keys`](./struct.JsonBuilderOptions.html#structfield.normalize_keys).
+    #[cfg(feature = "unicode")]
+    pub fn normalize_keys(mut self, normalize_keys: bool) -> JsonOptionsBuilder {
+        self.options.normalize_keys = normalize_keys;
+        self
+    }
+
+    /// adds `default` to [`JsonBuilderOptions::defaults`](./struct.JsonBuilderOptions.html#structfield.defaults).
+    pub fn default_member(mut self, default: JsonDefault) -> JsonOptionsBuilder {
+        self.options.defaults.push(default);
+        self
+    }
+
+    /// checks the options, and returns them if they can be used together.
+    pub fn build(self) -> Result<JsonBuilderOptions, JsonError> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
+
+impl JsonBuilderOptions {
+    /// checks these options like [`JsonOptionsBuilder::build()`](./struct.JsonOptionsBuilder.html#method.build),
+    /// failing with `JsonErrorKind::Options` if they cannot be used together - for options whose fields were set
+    /// directly.
+    pub fn validate(&self) -> Result<(), JsonError> {
+        let lenient = &self.lenient;
+
+        let depth      = self.maximum_depth == 0;
+        let non_finite = lenient.non_finite != JsonNonFinite::Reject && !lenient.literals;
+        let numbers    = self.maximum_number_length == 0;
+        let pointers   = self.coercions
+            .iter()
+            .filter_map(|x| x.pointer.as_ref())
+            .any(|x| !x.is_empty() && !x.starts_with('/'));
+
+        let defaults   = self.defaults.iter().any(|x| !x.is_valid());
+
+        match depth || non_finite || numbers || pointers || defaults {
+            true  => Err(JsonErrorKind::Options.into()),
+            false => Ok(()),
+        }
+    }
+}
+
+impl From<JsonBuilderOptions> for JsonOptionsBuilder {
+    fn from(options: JsonBuilderOptions) -> JsonOptionsBuilder {
+        JsonOptionsBuilder { options }
+    }
+}
@@ -0,0 +1,228 @@
+use {
+    std::collections::HashSet,
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonRepair,
+    arya::JsonRepairKind,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::build,
+    arya::diagnostics,
+    arya::events,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+    arya::strings,
+};
+
+
+
+// describes how `input` - the json object read so far, after lenient repairs - was changed to produce the complete
+// json object `amended`, as an rfc 6902 json patch. `output` is `input[..until]` followed by the tokens completion
+// appended, and `amended` is `output` after its keys were normalized, and its members stripped and added to.
+//
+// a value that a literal, coercion or big number repair was made in is replaced, as is a string that completion
+// closed. a value that completion inserted is added, and a value that completion dropped part way through is removed.
+// repairs to punctuation between values do not change any value, so they are not described. then every value that
+// amending removed is removed, and every value it added is added.
+crate fn repair_patch(
+    input:   &[u8],
+    until:   usize,
+    output:  &[u8],
+    amended: &[u8],
+    repairs: &[JsonRepair]) -> Result<Vec<u8>, JsonError>
+{
+    let mut patch = b"[".to_vec();
+
+    // a value is only described once: the values inside an added or replaced container are not.
+    let mut skip  = 0;
+
+    for (path, kind, range) in values(output)? {
+        if range.start < skip {
+            continue;
+        }
+
+        let repaired = repairs.iter().any(|repair| {
+            let value = match repair.kind {
+                JsonRepairKind::Literal | JsonRepairKind::Coercion | JsonRepairKind::BigNumber => true,
+                _                                                                              => false,
+            };
+
+            value && range.start <= repair.offset && repair.offset < range.end
+        });
+
+        let container = kind == JsonValueKind::Object || kind == JsonValueKind::Array;
+
+        let op = match (range.start >= until, !container && (range.end > until || repaired)) {
+            (true, _)      => "add",
+            (false, true)  => "replace",
+            (false, false) => continue,
+        };
+
+        operation(&mut patch, op, &path, Some(&output[range.clone()]))?;
+        skip = range.end;
+    }
+
+    if let Some(path) = dropped(input, until)? {
+        operation(&mut patch, "remove", &path, None)?;
+    }
+
+    if amended != output {
+        amend_patch(&mut patch, output, amended)?;
+    }
+
+    patch.push(b']');
+    Ok(patch)
+}
+
+// appends the operations that describe how amending the complete json object `before` produced `after` to `patch`:
+// every value whose path is only in `before` is removed, then every value whose path is only in `after` is added. the
+// values inside a removed or added container are not described.
+fn amend_patch(patch: &mut Vec<u8>, before: &[u8], after: &[u8]) -> Result<(), JsonError> {
+    let data   = after;
+    let before = values(before)?;
+    let after  = values(after)?;
+
+    let old = before.iter().map(|x| x.0.as_str()).collect::<HashSet<_>>();
+    let new = after.iter().map(|x| x.0.as_str()).collect::<HashSet<_>>();
+
+    let mut skip = 0;
+
+    for (path, _, range) in &before {
+        if range.start >= skip && !new.contains(path.as_str()) {
+            operation(patch, "remove", path, None)?;
+            skip = range.end;
+        }
+    }
+
+    let mut skip = 0;
+
+    for (path, _, range) in &after {
+        if range.start >= skip && !old.contains(path.as_str()) {
+            operation(patch, "add", path, Some(&data[range.clone()]))?;
+            skip = range.end;
+        }
+    }
+
+    Ok(())
+}
+
+
+
+// returns the json pointer, kind and range of every value in the complete json object `data`, in order.
+crate fn values(data: &[u8]) -> Result<Vec<(String, JsonValueKind, Range<usize>)>, JsonError> {
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut values = vec![];
+
+    // one frame per open container: the length of its path, the number of values seen, and the index of its entry in
+    // `values`. then the json pointer to the innermost container, and the key of the value currently being read.
+    let mut frames = vec![];
+    let mut opened = vec![];
+    let mut path   = String::new();
+    let mut key    = None;
+
+    for &character in data {
+        reader.update(character, |event| {
+            match event {
+                Event::Begin(kind, offset) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    frames.push((length, 0));
+                    opened.push(values.len());
+                    values.push((path.clone(), kind, offset..offset));
+                },
+                Event::End(offset) => {
+                    if let (Some((length, _)), Some(i)) = (frames.pop(), opened.pop()) {
+                        path.truncate(length);
+                        values[i].2.end = offset + 1;
+                    }
+                },
+                Event::Key(_, text) => {
+                    key = Some(strings::decode_key(text));
+                },
+                Event::Scalar(kind, range) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    values.push((path.clone(), kind, range));
+                    path.truncate(length);
+                },
+            }
+        })?;
+    }
+
+    Ok(values)
+}
+
+// returns the json pointer of the value that `input` ends part way through, if completion drops it by truncating
+// `input` to `until`. an incomplete key is not a value, so dropping it is not described.
+fn dropped(input: &[u8], until: usize) -> Result<Option<String>, JsonError> {
+    if until >= input.len() {
+        return Ok(None);
+    }
+
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut frames = vec![];
+    let mut path   = String::new();
+    let mut key    = None;
+
+    for &character in input {
+        reader.update(character, |event| {
+            match event {
+                Event::Begin(_, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    frames.push((length, 0));
+                },
+                Event::End(_) => {
+                    if let Some((length, _)) = frames.pop() {
+                        path.truncate(length);
+                    }
+                },
+                Event::Key(_, text) => {
+                    key = Some(strings::decode_key(text));
+                },
+                Event::Scalar(_, _) => {
+                    let length = path.len();
+
+                    push_value(&mut frames, &mut path, &mut key);
+                    path.truncate(length);
+                },
+            }
+        })?;
+    }
+
+    let verifier = reader.verifier();
+
+    match events::is_scalar(verifier.token()) && !verifier.in_key() {
+        true  => Ok(Some(diagnostics::next_path(verifier, frames, path, key))),
+        false => Ok(None),
+    }
+}
+
+// appends a json patch operation to `patch`.
+fn operation(patch: &mut Vec<u8>, op: &str, path: &str, value: Option<&[u8]>) -> Result<(), JsonError> {
+    if patch.len() > 1 {
+        patch.extend_from_slice(b", ");
+    }
+
+    patch.extend_from_slice(br#"{ "op": ""#);
+    patch.extend_from_slice(op.as_bytes());
+    patch.extend_from_slice(br#"", "path": "#);
+
+    build::write_string(path, |x| {
+        patch.extend_from_slice(x);
+        Ok(())
+    })?;
+
+    if let Some(value) = value {
+        patch.extend_from_slice(br#", "value": "#);
+        patch.extend_from_slice(value);
+    }
+
+    patch.extend_from_slice(b" }");
+    Ok(())
+}
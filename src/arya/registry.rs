@@ -0,0 +1,368 @@
+use {
+    std::collections::BTreeMap,
+    std::mem,
+    std::time::Duration,
+    std::time::Instant,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+};
+
+
+
+/// the json builders of many connections or streams, keyed by their ids - for servers that build a json object per
+/// connection, and need to drop the ones that go quiet or hold too much memory.
+///
+/// # remarks
+///
+/// a builder is created with this map's options the first time its key is used, and is touched whenever it is used
+/// again. builders are removed with `remove()` when their connection closes, and are otherwise evicted:
+///
+/// - `evict_idle()` removes and returns every builder that has not been touched for the idle timeout. call it
+///   periodically, such as from a timer.
+/// - past `maximum_builders()`, using a new key evicts the builder that was touched least recently.
+/// - past `maximum_memory()`, an update evicts the builders that were touched least recently until the builders hold
+///   less memory. if the builder being updated holds more than that on its own, it is evicted instead, and the update
+///   fails with `JsonErrorKind::TooLarge`.
+///
+/// builders evicted by a limit are dropped, to release their memory. their keys can be taken with `take_evicted()`, so
+/// that their connections can be closed.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonBuilderMap;
+/// #
+/// # fn main() {
+/// #
+/// let mut builders = JsonBuilderMap::new().maximum_builders(2);
+///
+/// builders.update(1, r#"{ "a": "#).unwrap();
+/// builders.update(2, "[1, ").unwrap();
+/// builders.update(1, "2 }").unwrap();
+///
+/// // connection 2 was touched least recently, so it is evicted to make room for connection 3.
+/// builders.update(3, "[").unwrap();
+///
+/// assert_eq!(builders.take_evicted(), [2]);
+/// assert_eq!(builders.remove(&1).unwrap().string().unwrap(), r#"{ "a": 2 }"#);
+/// assert_eq!(builders.len(), 1);
+/// # }
+/// ```
+pub struct JsonBuilderMap<K> {
+    options:  JsonBuilderOptions,
+    builders: BTreeMap<K, Entry>,
+
+    // how long a builder may go untouched, and the maximum number of builders and heap memory they may hold.
+    idle:             Option<Duration>,
+    maximum_builders: usize,
+    maximum_memory:   usize,
+
+    // the number of touches so far, which orders builders from least to most recently touched, the key of every
+    // builder by the tick it was last touched at, and the memory held by the builders when they were last measured.
+    clock:    u64,
+    order:    BTreeMap<u64, K>,
+    memory:   usize,
+
+    // the key of the builder last lent out by `builder()`, which must be measured again since it may have changed, and
+    // the keys of the builders evicted by a limit.
+    lent:     Option<K>,
+    evicted:  Vec<K>,
+}
+
+// a builder, when it was last touched - as an instant if builders can go idle, and as a tick of the map's clock - and
+// the heap memory it held when it was last measured.
+struct Entry {
+    builder: JsonBuilder,
+    touched: Option<Instant>,
+    tick:    u64,
+    memory:  usize,
+}
+
+impl<K: Ord + Clone> JsonBuilderMap<K> {
+    pub fn new() -> JsonBuilderMap<K> {
+        JsonBuilderMap::with_options(JsonBuilderOptions::default())
+    }
+
+    /// creates a map that creates every builder with `options`.
+    pub fn with_options(options: JsonBuilderOptions) -> JsonBuilderMap<K> {
+        JsonBuilderMap {
+            options,
+            builders: BTreeMap::new(),
+
+            idle:             None,
+            maximum_builders: std::usize::MAX,
+            maximum_memory:   std::usize::MAX,
+
+            clock:    0,
+            order:    BTreeMap::new(),
+            memory:   0,
+
+            lent:     None,
+            evicted:  vec![],
+        }
+    }
+
+    /// evicts builders with `evict_idle()` once they have not been touched for `idle`.
+    ///
+    /// `wasm32-unknown-unknown` has no clock, so there no builder is ever idle.
+    pub fn idle_timeout(mut self, idle: Duration) -> JsonBuilderMap<K> {
+        self.idle = Some(idle);
+        self
+    }
+
+    /// holds at most `maximum` builders, evicting the one that was touched least recently to make room for another.
+    ///
+    /// the builder being updated is never evicted to make room for itself, so `maximum` must be at least 1. panics if
+    /// it is 0.
+    pub fn maximum_builders(mut self, maximum: usize) -> JsonBuilderMap<K> {
+        assert![maximum > 0];
+
+        self.maximum_builders = maximum;
+        self
+    }
+
+    /// holds at most `maximum` bytes of heap memory across every builder, as measured by
+    /// [`JsonBuilder::memory_usage()`](./struct.JsonBuilder.html#method.memory_usage).
+    pub fn maximum_memory(mut self, maximum: usize) -> JsonBuilderMap<K> {
+        self.maximum_memory = maximum;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.builders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.builders.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.builders.contains_key(key)
+    }
+
+    /// returns the builder for `key`, if there is one, without touching it.
+    pub fn get(&self, key: &K) -> Option<&JsonBuilder> {
+        self.builders.get(key).map(|x| &x.builder)
+    }
+
+    /// returns the heap memory held by every builder.
+    pub fn memory_usage(&self) -> usize {
+        let lent = self.lent.as_ref().and_then(|x| self.builders.get(x));
+
+        match lent {
+            Some(entry) => self.memory - entry.memory + entry.builder.memory_usage().total(),
+            None        => self.memory,
+        }
+    }
+
+    /// touches the builder for `key`, creating it if there is none, and returns it.
+    ///
+    /// # remarks
+    ///
+    /// the builder is lent out mutably, so this map cannot see it grow. its memory is measured again - and the memory
+    /// limit checked - the next time this map is used, other than by `memory_usage()`, which measures it straight
+    /// away. `update()` checks the limit straight away.
+    pub fn builder(&mut self, key: K) -> &mut JsonBuilder {
+        self.settle();
+        self.enforce(None);
+
+        if !self.builders.contains_key(&key) {
+            while self.builders.len() >= self.maximum_builders && self.evict_oldest(None) {}
+        }
+
+        self.clock += 1;
+
+        let tick    = self.clock;
+        let now     = self.now();
+        let options = &self.options;
+        let memory  = &mut self.memory;
+        let order   = &mut self.order;
+
+        self.lent = Some(key.clone());
+        order.insert(tick, key.clone());
+
+        let entry = self.builders.entry(key).or_insert_with(|| {
+            let builder = JsonBuilder::with_options(options.clone());
+            let usage   = builder.memory_usage().total();
+
+            *memory += usage;
+
+            Entry { builder, touched: now, tick, memory: usage }
+        });
+
+        if entry.tick != tick {
+            order.remove(&entry.tick);
+        }
+
+        entry.touched = now;
+        entry.tick    = tick;
+
+        &mut entry.builder
+    }
+
+    /// applies `source` to the builder for `key`, creating it if there is none, then checks the memory limit.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilderMap, JsonErrorKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builders = JsonBuilderMap::new().maximum_memory(4096);
+    ///
+    /// builders.update("a", "[1, 2").unwrap();
+    ///
+    /// assert_eq!(builders.update("b", format!(r#"["{}"#, "x".repeat(10_000))), Err(JsonErrorKind::TooLarge.into()));
+    /// assert_eq!(builders.take_evicted(), ["b"]);
+    ///
+    /// assert!(builders.contains_key(&"a"));
+    /// assert!(builders.memory_usage() <= 4096);
+    /// # }
+    /// ```
+    pub fn update(&mut self, key: K, source: impl JsonSource) -> Result<(), JsonError> {
+        let result = self.builder(key.clone()).update(source);
+
+        self.settle();
+
+        if self.builders.get(&key).map_or(0, |x| x.memory) > self.maximum_memory {
+            self.take(&key);
+            self.evicted.push(key);
+
+            return Err(JsonErrorKind::TooLarge.into());
+        }
+
+        self.enforce(Some(&key));
+        result
+    }
+
+    /// touches the builder for `key`, returning `false` if there is none.
+    pub fn touch(&mut self, key: &K) -> bool {
+        let now = self.now();
+
+        match self.builders.get_mut(key) {
+            Some(entry) => {
+                self.clock += 1;
+                self.order.remove(&entry.tick);
+                self.order.insert(self.clock, key.clone());
+
+                entry.touched = now;
+                entry.tick    = self.clock;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// removes the builder for `key` and returns it, such as when its connection closes.
+    pub fn remove(&mut self, key: &K) -> Option<JsonBuilder> {
+        self.settle();
+        self.take(key)
+    }
+
+    /// removes every builder that has not been touched for the idle timeout, and returns them with their keys - so that
+    /// what they hold can still be completed. returns nothing if there is no idle timeout, or no clock.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilderMap;
+    /// # use std::time::Duration;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builders = JsonBuilderMap::new().idle_timeout(Duration::from_millis(50));
+    ///
+    /// builders.update("a", "[1, 2").unwrap();
+    ///
+    /// std::thread::sleep(Duration::from_millis(100));
+    ///
+    /// builders.update("b", "[").unwrap();
+    ///
+    /// let idle = builders.evict_idle();
+    ///
+    /// assert_eq!(idle.len(), 1);
+    /// assert_eq!(idle[0].0, "a");
+    /// assert_eq!(idle.into_iter().next().unwrap().1.completed_string().unwrap(), "[1, 2]");
+    /// # }
+    /// ```
+    pub fn evict_idle(&mut self) -> Vec<(K, JsonBuilder)> {
+        let idle = match self.idle {
+            Some(x) => x,
+            None    => return vec![],
+        };
+
+        let now = match self.now() {
+            Some(x) => x,
+            None    => return vec![],
+        };
+
+        self.settle();
+
+        // builders are touched in the order of their ticks, so the idle ones come first.
+        let keys = self.order
+            .values()
+            .take_while(|key| self.builders[*key].touched.map_or(false, |x| now.duration_since(x) >= idle))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        keys.into_iter().filter_map(|key| self.take(&key).map(|x| (key, x))).collect()
+    }
+
+    /// returns the keys of the builders evicted by a limit since this was last called, in the order they were evicted.
+    pub fn take_evicted(&mut self) -> Vec<K> {
+        mem::take(&mut self.evicted)
+    }
+
+    // reads the clock, only if builders can go idle. `wasm32-unknown-unknown` has no clock, and panics if it is read.
+    fn now(&self) -> Option<Instant> {
+        match self.idle.is_some() && cfg!(not(all(target_arch = "wasm32", target_os = "unknown"))) {
+            true  => Some(Instant::now()),
+            false => None,
+        }
+    }
+
+    // measures the builder last lent out by `builder()` again.
+    fn settle(&mut self) {
+        if let Some(key) = self.lent.take() {
+            if let Some(entry) = self.builders.get_mut(&key) {
+                let usage = entry.builder.memory_usage().total();
+
+                self.memory  = self.memory - entry.memory + usage;
+                entry.memory = usage;
+            }
+        }
+    }
+
+    // evicts the builders touched least recently, other than `except`, until the memory limit is met.
+    fn enforce(&mut self, except: Option<&K>) {
+        while self.memory > self.maximum_memory && self.evict_oldest(except) {}
+    }
+
+    // evicts the builder touched least recently, other than `except`, returning `false` if there is none.
+    fn evict_oldest(&mut self, except: Option<&K>) -> bool {
+        let oldest = self.order.values().find(|key| Some(*key) != except).cloned();
+
+        match oldest {
+            Some(key) => {
+                self.take(&key);
+                self.evicted.push(key);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // removes the builder for `key`, and the memory it held.
+    fn take(&mut self, key: &K) -> Option<JsonBuilder> {
+        let entry = self.builders.remove(key)?;
+
+        self.order.remove(&entry.tick);
+        self.memory -= entry.memory;
+        Some(entry.builder)
+    }
+}
@@ -0,0 +1,155 @@
+use {
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::strings,
+};
+
+
+
+// sorts the members of every object in the complete json object `data` by their unescaped keys. members with the same
+// key keep their order, and everything between members - commas and whitespace - stays where it was.
+//
+// nothing here recurses - values are kept in a flat list, and written with an explicit stack - so that deeply nested
+// input cannot overflow the stack.
+crate fn sort_keys(data: &[u8]) -> Result<Vec<u8>, JsonError> {
+    let (nodes, root) = parse(data)?;
+    let mut output    = Vec::with_capacity(data.len());
+
+    render(data, &nodes, root, &mut output);
+    Ok(output)
+}
+
+
+
+// a value in a complete json object: its range, and for a container, whether it is an object and its members.
+struct Node {
+    range:   Range<usize>,
+    object:  bool,
+    members: Vec<Member>,
+}
+
+// a member of a container: its unescaped key if it is an object member, the offset it starts at (its key, for an
+// object member), and the index of its value.
+struct Member {
+    key:   Vec<u8>,
+    start: usize,
+    value: usize,
+}
+
+// a container being written: its index, its members in the order they are written, the number of them written so far,
+// and the offset that the input is copied from next.
+type Frame = (usize, Vec<usize>, usize, usize);
+
+// reads the complete json object `data` into a list of its values, returning them and the index of the root.
+fn parse(data: &[u8]) -> Result<(Vec<Node>, usize), JsonError> {
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut nodes  = vec![];
+    let mut key    = None;
+    let mut root   = None;
+
+    // the containers that are open, and the key that each one is the value of.
+    let mut containers: Vec<(usize, Option<(usize, Vec<u8>)>)> = vec![];
+
+    for &character in data {
+        reader.update(character, |event| {
+            let (index, key) = match event {
+                Event::Begin(kind, offset) => {
+                    nodes.push(Node { range: offset..offset, object: kind == JsonValueKind::Object, members: vec![] });
+                    containers.push((nodes.len() - 1, key.take()));
+                    return;
+                },
+                Event::Key(range, text) => {
+                    let text = strings::decode_prefix(&data[range.clone()]).map(|(x, _)| x).unwrap_or(text.to_vec());
+
+                    key = Some((range.start, text));
+                    return;
+                },
+                Event::Scalar(_, range) => {
+                    nodes.push(Node { range, object: false, members: vec![] });
+                    (nodes.len() - 1, key.take())
+                },
+                Event::End(offset) => {
+                    let (index, key) = match containers.pop() {
+                        Some(x) => x,
+                        None    => return,
+                    };
+
+                    nodes[index].range.end = offset + 1;
+                    (index, key)
+                },
+            };
+
+            // attach the completed value to the container it is in, or keep it as the root.
+            match containers.last() {
+                Some(&(parent, _)) => {
+                    let (start, key) = key.unwrap_or((nodes[index].range.start, vec![]));
+
+                    nodes[parent].members.push(Member { key, start, value: index });
+                },
+                None => {
+                    root = Some(index);
+                },
+            }
+        })?;
+    }
+
+    match root {
+        Some(root) => Ok((nodes, root)),
+        None       => Err(JsonErrorKind::Invalid.into()),
+    }
+}
+
+// writes the value `root` into `output`, with the members of every object sorted by key. each member takes the place
+// of another, and everything between members is kept where it was.
+fn render(data: &[u8], nodes: &[Node], root: usize, output: &mut Vec<u8>) {
+    // the containers being written, innermost last.
+    let mut stack = vec![];
+
+    open(data, nodes, root, output, &mut stack);
+
+    while let Some((index, order, written, offset)) = stack.last_mut() {
+        let node = &nodes[*index];
+
+        if *written == node.members.len() {
+            output.extend_from_slice(&data[*offset..node.range.end]);
+            stack.pop();
+            continue;
+        }
+
+        let slot   = &node.members[*written];
+        let member = &node.members[order[*written]];
+
+        output.extend_from_slice(&data[*offset..slot.start]);
+        output.extend_from_slice(&data[member.start..nodes[member.value].range.start]);
+
+        *offset   = nodes[slot.value].range.end;
+        *written += 1;
+
+        open(data, nodes, member.value, output, &mut stack);
+    }
+}
+
+// writes a value without members straight into `output`, or begins writing a container with members by pushing it
+// onto `stack`.
+fn open(data: &[u8], nodes: &[Node], index: usize, output: &mut Vec<u8>, stack: &mut Vec<Frame>) {
+    let node = &nodes[index];
+
+    if node.members.is_empty() {
+        output.extend_from_slice(&data[node.range.clone()]);
+        return;
+    }
+
+    let mut order = (0..node.members.len()).collect::<Vec<_>>();
+
+    if node.object {
+        order.sort_by(|x, y| node.members[*x].key.cmp(&node.members[*y].key));
+    }
+
+    stack.push((index, order, 0, node.range.start));
+}
@@ -0,0 +1,360 @@
+use {
+    arya::diagnostics,
+};
+
+
+
+/// the lenient repairs a [`JsonBuilder`](./struct.JsonBuilder.html) may make to input that would otherwise be
+/// rejected.
+///
+/// # remarks
+///
+/// every repair is disabled by default. each repair that is made is recorded, and can be inspected with
+/// [`JsonBuilder::repairs()`](./struct.JsonBuilder.html#method.repairs).
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonRepair, JsonRepairKind};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.lenient.control = true;
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(&b"{ \"a\": 1\0}\0\0"[..]).unwrap();
+///
+/// assert_eq!(builder.repairs()[1], JsonRepair { offset: 10, kind: JsonRepairKind::Control { found: 0 } });
+/// assert_eq!(builder.report().unwrap().repairs, 3);
+/// assert_eq!(builder.string().unwrap(), r#"{ "a": 1 }"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct JsonLenient {
+    /// replace a closing bracket that does not match the innermost open container with the one that does, so that
+    /// `[1, 2}` becomes `[1, 2]`.
+    pub brackets: bool,
+
+    /// insert a missing comma between two values, so that `[1 2]` becomes `[1, 2]`.
+    pub commas:   bool,
+
+    /// insert a missing colon between a key and its value, so that `{"a" 1}` becomes `{"a": 1}`.
+    pub colons:   bool,
+
+    /// replace the python and javascript literals `True`, `False`, `None` and `undefined` with `true`, `false` and
+    /// `null`.
+    pub literals: bool,
+
+    /// how the literals `NaN`, `Infinity` and `-Infinity` are handled when `literals` is enabled.
+    pub non_finite: JsonNonFinite,
+
+    /// replace curly quotes, full-width colons and commas, and no-break spaces between tokens with their ascii
+    /// equivalents, so that `{“a”：1，“b”：2}` becomes `{"a":1,"b":2}`.
+    ///
+    /// inside a string opened by a curly quote, only the matching closing quote is replaced, so that apostrophes and
+    /// other curly quotes in the text are kept.
+    pub punctuation: bool,
+
+    /// skip nul and other c0 control bytes between tokens, such as the zero padding of a fixed-size or memory-mapped
+    /// buffer, so that `{"a":1}\0\0` becomes `{"a":1}`.
+    ///
+    /// a control byte directly after a number ends it, and is replaced with a space. control bytes inside strings are
+    /// never skipped.
+    pub control:     bool,
+}
+
+
+
+/// how the literals `NaN`, `Infinity` and `-Infinity` are repaired.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonNonFinite {
+    /// they are not repaired, and are rejected like any other invalid input.
+    #[default]
+    Reject,
+
+    /// they are replaced with `null`.
+    Null,
+
+    /// they are replaced with a string containing the literal, such as `"NaN"`.
+    String,
+}
+
+
+
+/// a single repair made by a [`JsonBuilder`](./struct.JsonBuilder.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JsonRepair {
+    /// the offset in the built json object that the repair was made at.
+    pub offset: usize,
+
+    /// the repair that was made.
+    pub kind:   JsonRepairKind,
+}
+
+impl JsonRepair {
+    /// renders this repair as a json object on a single line, for logging and alerting pipelines.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonRepair, JsonRepairKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let repair = JsonRepair { offset: 12, kind: JsonRepairKind::Bracket { found: b'}', replacement: b']' } };
+    ///
+    /// assert_eq!(repair.to_json(), r#"{"offset":12,"kind":"bracket","found":"}","replacement":"]"}"#);
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut members = vec![("offset", self.offset.to_string()), ("kind", diagnostics::json_string(self.kind.name()))];
+
+        match self.kind {
+            JsonRepairKind::Bracket { found, replacement } => {
+                members.push(("found",       diagnostics::json_string(&(found as char).to_string())));
+                members.push(("replacement", diagnostics::json_string(&(replacement as char).to_string())));
+            },
+            JsonRepairKind::Control { found } => {
+                members.push(("found", found.to_string()));
+            },
+            _ => {},
+        }
+
+        diagnostics::json_object(&members)
+    }
+}
+
+
+
+/// the kind of a [`JsonRepair`](./struct.JsonRepair.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonRepairKind {
+    /// the closing bracket `found` was replaced with `replacement`.
+    Bracket { found: u8, replacement: u8 },
+
+    /// a missing comma was inserted.
+    Comma,
+
+    /// a missing colon was inserted.
+    Colon,
+
+    /// a python or javascript literal was replaced.
+    Literal,
+
+    /// a unicode punctuation character was replaced with its ascii equivalent.
+    Punctuation,
+
+    /// the control byte `found` between tokens was skipped.
+    Control { found: u8 },
+
+    /// a string value was converted into another type of value by a
+    /// [`JsonCoercion`](./struct.JsonCoercion.html).
+    Coercion,
+
+    /// a number that loses precision when parsed was replaced with a string. see
+    /// [`JsonBigNumbers`](./enum.JsonBigNumbers.html).
+    BigNumber,
+}
+
+impl JsonRepairKind {
+    /// a short, machine-readable name for this kind of repair, such as `"bracket"` or `"big_number"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            JsonRepairKind::Bracket { .. } => "bracket",
+            JsonRepairKind::Comma          => "comma",
+            JsonRepairKind::Colon          => "colon",
+            JsonRepairKind::Literal        => "literal",
+            JsonRepairKind::Punctuation    => "punctuation",
+            JsonRepairKind::Control { .. } => "control",
+            JsonRepairKind::Coercion       => "coercion",
+            JsonRepairKind::BigNumber      => "big_number",
+        }
+    }
+}
+
+
+
+/// how much a [`JsonBuilder`](./struct.JsonBuilder.html) changed its input to produce a complete json object. see
+/// [`JsonBuilder::report()`](./struct.JsonBuilder.html#method.report).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JsonRepairReport {
+    /// the length of the input, after lenient repairs.
+    pub length:     usize,
+
+    /// the number of bytes completion would append, such as closing tokens.
+    pub inserted:   usize,
+
+    /// the number of bytes completion would drop from the end of the input, such as an incomplete value.
+    pub truncated:  usize,
+
+    /// the number of lenient repairs that were made.
+    pub repairs:    usize,
+
+    /// how much of the completed json object came unchanged from the input, from `0.0` to `1.0`.
+    ///
+    /// every inserted and truncated byte and every repair counts as one change, and the confidence is the share of
+    /// the input and inserted bytes that were not changed. a complete json object with no repairs has a confidence of
+    /// `1.0`.
+    pub confidence: f64,
+}
+
+impl JsonRepairReport {
+    crate fn new(length: usize, inserted: usize, truncated: usize, repairs: usize) -> JsonRepairReport {
+        let changes = (inserted + truncated + repairs) as f64;
+        let total   = (length + inserted).max(1) as f64;
+
+        JsonRepairReport { length, inserted, truncated, repairs, confidence: (1.0 - changes / total).max(0.0) }
+    }
+
+    /// renders this report as a json object on a single line, for logging and alerting pipelines.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonBuilder;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut builder = JsonBuilder::new();
+    ///
+    /// builder.update(r#"{ "a": [1, 2"#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     builder.report().unwrap().to_json(),
+    ///     r#"{"length":12,"inserted":2,"truncated":0,"repairs":0,"confidence":0.8571428571428572}"#);
+    /// # }
+    /// ```
+    pub fn to_json(&self) -> String {
+        diagnostics::json_object(&[
+            ("length",     self.length.to_string()),
+            ("inserted",   self.inserted.to_string()),
+            ("truncated",  self.truncated.to_string()),
+            ("repairs",    self.repairs.to_string()),
+            ("confidence", self.confidence.to_string()),
+        ])
+    }
+}
+
+
+
+/// a way of completing an incomplete json object, tried by
+/// [`JsonBuilder::complete_candidates()`](./struct.JsonBuilder.html#method.complete_candidates).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonStrategy {
+    /// an incomplete key or value is dropped, along with the rest of its member or element.
+    Drop,
+
+    /// an incomplete key or string value is kept and closed, and an incomplete key is given a `null` value. other
+    /// incomplete values are dropped.
+    Keep,
+
+    /// an incomplete value is replaced with `null`, keeping its key. an incomplete key is dropped.
+    Null,
+}
+
+
+
+/// a completed json object produced by a [`JsonStrategy`](./enum.JsonStrategy.html), and how well it preserves the
+/// input. see [`JsonBuilder::complete_candidates()`](./struct.JsonBuilder.html#method.complete_candidates).
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonCandidate {
+    /// the strategy that produced this candidate.
+    pub strategy: JsonStrategy,
+
+    /// the completed json object.
+    pub data:     Vec<u8>,
+
+    /// how much completing it changed the input.
+    pub report:   JsonRepairReport,
+
+    /// the number of values that were closed part way through, such as a string whose text may have been cut short.
+    pub partial:  usize,
+
+    /// the score candidates are ranked by, from `0.0` to `1.0`: the report's confidence, less `0.25` for each partial
+    /// value.
+    pub score:    f64,
+}
+
+
+
+// the literals that may be repaired, and their replacements: the first if they are finite, and the second if they are
+// replaced with a string.
+const LITERALS: [(&[u8], &[u8], &[u8]); 7] = [
+    (b"True",      b"true",  b"true"),
+    (b"False",     b"false", b"false"),
+    (b"None",      b"null",  b"null"),
+    (b"undefined", b"null",  b"null"),
+    (b"NaN",       b"null",  b"\"NaN\""),
+    (b"Infinity",  b"null",  b"\"Infinity\""),
+    (b"-Infinity", b"null",  b"\"-Infinity\""),
+];
+
+// returns `Some(Some(replacement))` if `word` is a literal that may be repaired, `Some(None)` if it is the start of
+// one, and `None` otherwise.
+crate fn literal(word: &[u8], non_finite: JsonNonFinite) -> Option<Option<&'static [u8]>> {
+    let mut prefix = false;
+
+    for (i, &(literal, replacement, string)) in LITERALS.iter().enumerate() {
+        // the first four literals are finite.
+        let replacement = match (i < 4, non_finite) {
+            (true, _)                     => replacement,
+            (false, JsonNonFinite::Reject) => continue,
+            (false, JsonNonFinite::Null)   => replacement,
+            (false, JsonNonFinite::String) => string,
+        };
+
+        if literal == word {
+            return Some(Some(replacement));
+        }
+
+        prefix |= literal.starts_with(word);
+    }
+
+    match prefix {
+        true  => Some(None),
+        false => None,
+    }
+}
+
+
+
+// the unicode punctuation that may be repaired, and its replacement.
+const PUNCTUATION: [(&[u8], u8); 7] = [
+    (b"\xe2\x80\x9c", b'"'), // left double quotation mark
+    (b"\xe2\x80\x9d", b'"'), // right double quotation mark
+    (b"\xe2\x80\x98", b'"'), // left single quotation mark
+    (b"\xe2\x80\x99", b'"'), // right single quotation mark
+    (b"\xef\xbc\x9a", b':'), // fullwidth colon
+    (b"\xef\xbc\x8c", b','), // fullwidth comma
+    (b"\xc2\xa0",     b' '), // no-break space
+];
+
+// returns `Some(Some(replacement))` if `sequence` is punctuation that may be repaired, `Some(None)` if it is the
+// start of some, and `None` otherwise.
+crate fn punctuation(sequence: &[u8]) -> Option<Option<u8>> {
+    let mut result = None;
+
+    for &(punctuation, replacement) in PUNCTUATION.iter() {
+        if punctuation == sequence {
+            return Some(Some(replacement));
+        }
+
+        if punctuation.starts_with(sequence) {
+            result = Some(None);
+        }
+    }
+
+    result
+}
+
+// returns the quote that closes a string opened by the curly quote `sequence`.
+crate fn closing_quote(sequence: &[u8]) -> &'static [u8] {
+    match sequence {
+        b"\xe2\x80\x9c" | b"\xe2\x80\x9d" => b"\xe2\x80\x9d",
+        _                                => b"\xe2\x80\x99",
+    }
+}
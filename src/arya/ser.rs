@@ -0,0 +1,584 @@
+use {
+    serde::ser,
+    serde::ser::Serialize,
+
+    std::io::Write,
+
+    arya,
+    arya::JsonBuilder,
+    arya::JsonError,
+    arya::JsonErrorKind,
+};
+
+
+
+impl ser::Error for JsonError {
+    fn custom<T: std::fmt::Display>(_: T) -> JsonError {
+        JsonErrorKind::Serialize.into()
+    }
+}
+
+
+
+/// a `serde::Serializer` that writes json through a [`JsonBuilder`](./struct.JsonBuilder.html).
+///
+/// # remarks
+///
+/// every byte produced by the serializer is passed through the builder's verifier, so serialized values can be freely
+/// mixed with raw fragments appended by `JsonBuilder::update()` - if the combined output would not be valid json, the
+/// serializer fails with the same error that `update()` would have.
+///
+/// the serializer only writes the value itself. separators between values (`,` and `:`) are the caller's
+/// responsibility.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonBuilder;
+/// #
+/// # fn main() {
+/// #
+/// let mut builder = JsonBuilder::new();
+///
+/// builder.update(r#"{ "names": "#).unwrap();
+/// builder.serialize(&["annie", "hina"]).unwrap();
+/// builder.update(", \"age\": ").unwrap();
+/// builder.serialize(&14).unwrap();
+/// builder.update("}").unwrap();
+///
+/// assert_eq!(builder.string().unwrap(), r#"{ "names": ["annie","hina"], "age": 14}"#);
+/// # }
+/// ```
+pub struct JsonSerializer<'a> {
+    builder: &'a mut JsonBuilder,
+}
+
+impl<'a> JsonSerializer<'a> {
+    pub fn new(builder: &'a mut JsonBuilder) -> JsonSerializer<'a> {
+        JsonSerializer { builder }
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), JsonError> {
+        self.builder.update(data)
+    }
+
+    // writes `value` as it is displayed. most numbers fit in a buffer on the stack, but floats such as `1e300` are
+    // displayed with hundreds of digits, so longer ones are written from the heap.
+    fn write_display(&mut self, value: impl std::fmt::Display) -> Result<(), JsonError> {
+        let mut buffer = [0u8; 64];
+        let mut cursor = std::io::Cursor::new(&mut buffer[..]);
+
+        match write!(cursor, "{}", value) {
+            Ok(()) => {
+                let length = cursor.position() as usize;
+
+                self.write(&buffer[..length])
+            },
+            Err(_) => {
+                self.write(value.to_string().as_bytes())
+            },
+        }
+    }
+
+    fn write_float(&mut self, value: f64) -> Result<(), JsonError> {
+        match value.is_finite() {
+            true  => self.write_display(value),
+            false => self.write(b"null"),
+        }
+    }
+
+    fn write_string(&mut self, value: &str) -> Result<(), JsonError> {
+        let builder = &mut *self.builder;
+
+        arya::build::write_string(value, |x| builder.update(x))
+    }
+}
+
+
+
+impl JsonBuilder {
+    /// serializes `value` into this builder at the current position.
+    ///
+    /// see [`JsonSerializer`](./struct.JsonSerializer.html) for details.
+    pub fn serialize<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        value.serialize(&mut JsonSerializer::new(self))
+    }
+}
+
+
+
+impl<'a, 'b> ser::Serializer for &'b mut JsonSerializer<'a> {
+    type Ok                     = ();
+    type Error                  = JsonError;
+
+    type SerializeSeq           = Compound<'a, 'b>;
+    type SerializeTuple         = Compound<'a, 'b>;
+    type SerializeTupleStruct   = Compound<'a, 'b>;
+    type SerializeTupleVariant  = Compound<'a, 'b>;
+    type SerializeMap           = Compound<'a, 'b>;
+    type SerializeStruct        = Compound<'a, 'b>;
+    type SerializeStructVariant = Compound<'a, 'b>;
+
+    fn serialize_bool(self, value: bool) -> Result<(), JsonError> {
+        match value {
+            true  => self.write(b"true"),
+            false => self.write(b"false"),
+        }
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<(), JsonError> {
+        self.write_display(value)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<(), JsonError> {
+        self.write_float(value as f64)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), JsonError> {
+        self.write_float(value)
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), JsonError> {
+        self.write_string(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), JsonError> {
+        self.write_string(value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), JsonError> {
+        use serde::ser::SerializeSeq;
+
+        let mut sequence = self.serialize_seq(Some(value.len()))?;
+
+        for byte in value {
+            sequence.serialize_element(byte)?;
+        }
+
+        sequence.end()
+    }
+
+    fn serialize_none(self) -> Result<(), JsonError> {
+        self.write(b"null")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), JsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), JsonError> {
+        self.write(b"null")
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), JsonError> {
+        self.write(b"null")
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<(), JsonError> {
+        self.write_string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<(), JsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T) -> Result<(), JsonError>
+    {
+        self.write(b"{")?;
+        self.write_string(variant)?;
+        self.write(b":")?;
+        value.serialize(&mut *self)?;
+        self.write(b"}")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'a, 'b>, JsonError> {
+        self.write(b"[")?;
+
+        Ok(Compound { serializer: self, first: true, close: b"]" })
+    }
+
+    fn serialize_tuple(self, length: usize) -> Result<Compound<'a, 'b>, JsonError> {
+        self.serialize_seq(Some(length))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, length: usize) -> Result<Compound<'a, 'b>, JsonError> {
+        self.serialize_seq(Some(length))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize) -> Result<Compound<'a, 'b>, JsonError>
+    {
+        self.write(b"{")?;
+        self.write_string(variant)?;
+        self.write(b":[")?;
+
+        Ok(Compound { serializer: self, first: true, close: b"]}" })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Compound<'a, 'b>, JsonError> {
+        self.write(b"{")?;
+
+        Ok(Compound { serializer: self, first: true, close: b"}" })
+    }
+
+    fn serialize_struct(self, _: &'static str, length: usize) -> Result<Compound<'a, 'b>, JsonError> {
+        self.serialize_map(Some(length))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize) -> Result<Compound<'a, 'b>, JsonError>
+    {
+        self.write(b"{")?;
+        self.write_string(variant)?;
+        self.write(b":{")?;
+
+        Ok(Compound { serializer: self, first: true, close: b"}}" })
+    }
+}
+
+
+
+#[doc(hidden)]
+pub struct Compound<'a: 'b, 'b> {
+    serializer: &'b mut JsonSerializer<'a>,
+    first:      bool,
+    close:      &'static [u8],
+}
+
+impl<'a, 'b> Compound<'a, 'b> {
+    fn separator(&mut self) -> Result<(), JsonError> {
+        match std::mem::replace(&mut self.first, false) {
+            true  => Ok(()),
+            false => self.serializer.write(b","),
+        }
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.separator()?;
+        value.serialize(&mut *self.serializer)
+    }
+
+    fn field<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), JsonError> {
+        self.separator()?;
+        self.serializer.write_string(key)?;
+        self.serializer.write(b":")?;
+        value.serialize(&mut *self.serializer)
+    }
+
+    fn close(self) -> Result<(), JsonError> {
+        self.serializer.write(self.close)
+    }
+}
+
+impl<'a, 'b> ser::SerializeSeq for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+impl<'a, 'b> ser::SerializeTuple for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+impl<'a, 'b> ser::SerializeTupleStruct for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+impl<'a, 'b> ser::SerializeTupleVariant for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+impl<'a, 'b> ser::SerializeMap for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), JsonError> {
+        self.separator()?;
+        key.serialize(KeySerializer { serializer: &mut *self.serializer })?;
+        self.serializer.write(b":")
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), JsonError> {
+        value.serialize(&mut *self.serializer)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+impl<'a, 'b> ser::SerializeStruct for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), JsonError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+impl<'a, 'b> ser::SerializeStructVariant for Compound<'a, 'b> {
+    type Ok    = ();
+    type Error = JsonError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), JsonError> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), JsonError> {
+        self.close()
+    }
+}
+
+
+
+// json object keys must be strings - strings and chars are written as-is, integers are quoted, and everything else is
+// rejected.
+struct KeySerializer<'a: 'b, 'b> {
+    serializer: &'b mut JsonSerializer<'a>,
+}
+
+impl<'a, 'b> KeySerializer<'a, 'b> {
+    fn quoted(self, value: impl std::fmt::Display) -> Result<(), JsonError> {
+        self.serializer.write(b"\"")?;
+        self.serializer.write_display(value)?;
+        self.serializer.write(b"\"")
+    }
+}
+
+impl<'a, 'b> ser::Serializer for KeySerializer<'a, 'b> {
+    type Ok                     = ();
+    type Error                  = JsonError;
+
+    type SerializeSeq           = ser::Impossible<(), JsonError>;
+    type SerializeTuple         = ser::Impossible<(), JsonError>;
+    type SerializeTupleStruct   = ser::Impossible<(), JsonError>;
+    type SerializeTupleVariant  = ser::Impossible<(), JsonError>;
+    type SerializeMap           = ser::Impossible<(), JsonError>;
+    type SerializeStruct        = ser::Impossible<(), JsonError>;
+    type SerializeStructVariant = ser::Impossible<(), JsonError>;
+
+    fn serialize_str(self, value: &str) -> Result<(), JsonError> {
+        self.serializer.write_string(value)
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), JsonError> {
+        self.serializer.write_string(value.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<(), JsonError> {
+        self.serializer.write_string(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<(), JsonError> {
+        value.serialize(self)
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<(), JsonError> {
+        self.quoted(value)
+    }
+
+    fn serialize_bool(self, _: bool) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_none(self) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_unit(self) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T) -> Result<(), JsonError>
+    {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize) -> Result<Self::SerializeTupleVariant, JsonError>
+    {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, JsonError> {
+        Err(JsonErrorKind::Serialize.into())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize) -> Result<Self::SerializeStructVariant, JsonError>
+    {
+        Err(JsonErrorKind::Serialize.into())
+    }
+}
@@ -0,0 +1,89 @@
+use {
+    std::sync::Mutex,
+
+    arya::JsonError,
+    arya::JsonSource,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::diagnostics,
+};
+
+
+
+/// a json syntax validator that can be shared between threads and tasks.
+///
+/// # remarks
+///
+/// `JsonVerifier` is a mutable state machine, so sharing one means serializing all validation behind a lock. this
+/// keeps a pool of verifiers instead: each call to `validate()` takes one out of the pool (or creates one, if they are
+/// all in use), validates without holding any lock, then returns it. the pool grows to the largest number of
+/// concurrent calls, and its verifiers are reused from then on.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonError, JsonErrorKind, JsonValidatorService};
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// #
+/// # fn main() {
+/// #
+/// let service = Arc::new(JsonValidatorService::new());
+///
+/// let threads = (0..4).map(|_| {
+///     let service = service.clone();
+///
+///     thread::spawn(move || service.validate(r#"{ "a": [1, 2] }"#))
+/// });
+///
+/// for thread in threads.collect::<Vec<_>>() {
+///     assert_eq!(thread.join().unwrap(), Ok(()));
+/// }
+///
+/// assert_eq!(service.validate("[1, 2"), Err(JsonError::at(JsonErrorKind::Invalid, 5)));
+/// # }
+/// ```
+pub struct JsonValidatorService {
+    options: JsonVerifierOptions,
+    pool:    Mutex<Vec<JsonVerifier>>,
+}
+
+impl JsonValidatorService {
+    pub fn new() -> JsonValidatorService {
+        JsonValidatorService::with_options(JsonVerifierOptions::default())
+    }
+
+    pub fn with_options(options: JsonVerifierOptions) -> JsonValidatorService {
+        assert![options.maximum_depth > 0];
+
+        JsonValidatorService {
+            options,
+            pool:    Mutex::new(vec![]),
+        }
+    }
+
+    /// validates `document`, stopping at the first error. a document that ends before its json object is complete is
+    /// reported like in [`validate_batch()`](./fn.validate_batch.html).
+    pub fn validate(&self, document: impl JsonSource) -> Result<(), JsonError> {
+        let mut verifier = self.take();
+        let result       = diagnostics::validate_with(&mut verifier, document.stream());
+
+        self.give(verifier);
+        result
+    }
+
+
+
+    fn take(&self) -> JsonVerifier {
+        let verifier = self.pool.lock().ok().and_then(|mut x| x.pop());
+
+        verifier.unwrap_or_else(|| JsonVerifier::with_options(self.options.clone()))
+    }
+
+    fn give(&self, verifier: JsonVerifier) {
+        // a poisoned pool is only ever missing verifiers, which are simply created again.
+        if let Ok(mut pool) = self.pool.lock() {
+            pool.push(verifier);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use {
+    std::mem,
+    std::ops::Range,
+
+    arya::JsonValueKind,
+};
+
+
+
+/// the expected shape of a json object, such as a known api response format, that a
+/// [`JsonBuilder`](./struct.JsonBuilder.html) uses to make better decisions when completing it.
+///
+/// # remarks
+///
+/// when the input ends part way through a value - or just before one - whose shape is known, completion:
+///
+/// - keeps and closes an incomplete `String`, instead of dropping it.
+/// - replaces the value with an empty string, object or array if it should be a `String`, `Object` or `Array` and has
+///   not been started - or for an `Object` or `Array`, if it was started as something else.
+/// - completes an incomplete `true` or `false` if it should be a `Boolean`.
+/// - drops the incomplete fraction or exponent of a `Number`, keeping the rest of it.
+///
+/// the shape of a value is found by following its json pointer from the top level. a key that an `Object` does not
+/// list, and every value inside `Any`, has no known shape, and is completed as usual. object keys are matched as they
+/// appear in the input, without unescaping.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonBuilderOptions, JsonShape};
+/// #
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.shape = Some(JsonShape::object()
+///     .member("choices", JsonShape::array(JsonShape::object()
+///         .member("text", JsonShape::String)
+///         .member("logprobs", JsonShape::array(JsonShape::Number)))));
+///
+/// let mut builder = JsonBuilder::with_options(options.clone());
+///
+/// builder.update(r#"{ "choices": [{ "text": "the quick br"#).unwrap();
+///
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "choices": [{ "text": "the quick br"}]}"#);
+///
+/// let mut builder = JsonBuilder::with_options(options);
+///
+/// builder.update(r#"{ "choices": [{ "text": "the", "logprobs": "#).unwrap();
+///
+/// assert_eq!(builder.completed_string().unwrap(), r#"{ "choices": [{ "text": "the", "logprobs": []}]}"#);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JsonShape {
+    /// any value.
+    Any,
+
+    /// an object with these keys, and the shapes of their values.
+    Object(Vec<(String, JsonShape)>),
+
+    /// an array whose elements all have this shape.
+    Array(Box<JsonShape>),
+
+    /// a string.
+    String,
+
+    /// a number.
+    Number,
+
+    /// `true` or `false`.
+    Boolean,
+}
+
+impl JsonShape {
+    /// an object whose members are not known yet. see `member()`.
+    pub fn object() -> JsonShape {
+        JsonShape::Object(vec![])
+    }
+
+    /// an array whose elements all have the shape `element`.
+    pub fn array(element: JsonShape) -> JsonShape {
+        JsonShape::Array(Box::new(element))
+    }
+
+    /// adds the member `key` with the shape `shape` to an object. any other shape is returned unchanged.
+    pub fn member(mut self, key: impl Into<String>, shape: JsonShape) -> JsonShape {
+        if let JsonShape::Object(members) = &mut self {
+            members.push((key.into(), shape));
+        }
+
+        self
+    }
+
+    // returns the heap memory held by this shape, and the shapes inside it.
+    crate fn memory_usage(&self) -> usize {
+        let mut total = 0;
+        let mut stack = vec![self];
+
+        while let Some(shape) = stack.pop() {
+            match shape {
+                JsonShape::Object(members) => {
+                    total += members.capacity() * mem::size_of::<(String, JsonShape)>();
+
+                    for (key, shape) in members {
+                        total += key.capacity();
+                        stack.push(shape);
+                    }
+                },
+                JsonShape::Array(element) => {
+                    total += mem::size_of::<JsonShape>();
+                    stack.push(element);
+                },
+                _ => {},
+            }
+        }
+
+        total
+    }
+
+    // returns the shape of the value at the json pointer `pointer`.
+    crate fn at(&self, pointer: &str) -> &JsonShape {
+        let mut shape = self;
+
+        for segment in pointer.split('/').skip(1) {
+            let segment = segment.replace("~1", "/").replace("~0", "~");
+
+            shape = match shape {
+                JsonShape::Object(members) => {
+                    match members.iter().find(|(key, _)| *key == segment) {
+                        Some((_, x)) => x,
+                        None         => return &JsonShape::Any,
+                    }
+                },
+                JsonShape::Array(element) => element,
+                _                         => return &JsonShape::Any,
+            };
+        }
+
+        shape
+    }
+}
+
+
+
+// returns the shape of the complete json object that the values of a template were read from, given the kind and
+// range of each of them in order. an array has the shape of its first element.
+crate fn from_template(values: &[(String, JsonValueKind, Range<usize>)]) -> JsonShape {
+    match values.is_empty() {
+        true  => JsonShape::Any,
+        false => shape_at(values, 0).0,
+    }
+}
+
+// returns the shape of `values[index]`, and the index of the first value after it and its contents.
+fn shape_at(values: &[(String, JsonValueKind, Range<usize>)], index: usize) -> (JsonShape, usize) {
+    let (_, kind, range) = &values[index];
+    let mut next         = index + 1;
+    let mut children     = vec![];
+
+    while next < values.len() && values[next].2.start < range.end {
+        let (shape, after) = shape_at(values, next);
+        let key            = values[next].0.rsplit('/').next().unwrap_or("").replace("~1", "/").replace("~0", "~");
+
+        children.push((key, shape));
+        next = after;
+    }
+
+    let shape = match kind {
+        JsonValueKind::Object  => JsonShape::Object(children),
+        JsonValueKind::Array   => JsonShape::array(children.into_iter().next().map_or(JsonShape::Any, |x| x.1)),
+        JsonValueKind::String  => JsonShape::String,
+        JsonValueKind::Number  => JsonShape::Number,
+        JsonValueKind::Boolean => JsonShape::Boolean,
+        JsonValueKind::Null    => JsonShape::Any,
+    };
+
+    (shape, next)
+}
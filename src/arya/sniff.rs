@@ -0,0 +1,110 @@
+use {
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::JsonWhitespace,
+};
+
+
+
+// the number of bytes inspected by `sniff()`.
+const SAMPLE_LENGTH: usize = 512;
+
+
+
+/// the format of a stream, as classified by [`sniff()`](./fn.sniff.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonFormat {
+    /// a single json object or array.
+    Json,
+
+    /// newline-delimited json: several json objects or arrays, separated by line breaks.
+    Lines,
+
+    /// anything else.
+    Other,
+}
+
+
+
+/// the result of [`sniff()`](./fn.sniff.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Confidence {
+    /// the most likely format of the stream.
+    pub format:  JsonFormat,
+
+    /// `true` if the inspected bytes contained at least one complete json document, and `false` if they were only a
+    /// valid prefix of one.
+    ///
+    /// this is always `false` for `JsonFormat::Other`.
+    pub certain: bool,
+}
+
+
+
+/// cheaply classifies `data` as json, newline-delimited json, or neither.
+///
+/// # remarks
+///
+/// only the first few hundred bytes of `data` are inspected, so a stream classified as json may still turn out to be
+/// invalid further on. a leading byte order mark is ignored.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonFormat, sniff};
+/// #
+/// # fn main() {
+/// #
+/// assert_eq!(sniff(br#"{ "a": [1, 2"#).format, JsonFormat::Json);
+/// assert_eq!(sniff(b"{ \"a\": 1 }\n{ \"a\": 2 }\n").format, JsonFormat::Lines);
+/// assert_eq!(sniff(b"<!doctype html>").format, JsonFormat::Other);
+///
+/// assert!(!sniff(br#"{ "a": [1, 2"#).certain);
+/// assert!(sniff(br#"{ "a": [1, 2] }"#).certain);
+/// # }
+/// ```
+pub fn sniff(data: &[u8]) -> Confidence {
+    const OTHER: Confidence = Confidence { format: JsonFormat::Other, certain: false };
+
+    let data   = data.strip_prefix("\u{feff}".as_bytes()).unwrap_or(data);
+    let sample = &data[..data.len().min(SAMPLE_LENGTH)];
+
+    let options = JsonVerifierOptions {
+        whitespace: JsonWhitespace::Strict,
+        ..Default::default()
+    };
+
+    let mut verifier  = JsonVerifier::with_options(options);
+    let mut documents = 0;
+    let mut newline   = false;
+
+    for &character in sample {
+        if verifier.update(character).is_ok() {
+            newline = verifier.status() == JsonStatus::Valid && (newline || character == b'\n');
+            continue;
+        }
+
+        // a new document may only begin on a new line, after a complete one.
+        match verifier.status() == JsonStatus::Valid && newline {
+            true  => documents += 1,
+            false => return OTHER,
+        }
+
+        verifier.reset();
+        newline = false;
+
+        if verifier.update(character).is_err() {
+            return OTHER;
+        }
+    }
+
+    let complete = verifier.status() == JsonStatus::Valid;
+    let format   = match (documents, verifier.depth() > 0 || complete) {
+        (0, false) => return OTHER,
+        (0, true)  => JsonFormat::Json,
+        (_, _)     => JsonFormat::Lines,
+    };
+
+    Confidence { format, certain: documents > 0 || complete }
+}
@@ -0,0 +1,225 @@
+use {
+    std::fs,
+    std::fs::File,
+    std::io,
+    std::io::Seek,
+    std::io::SeekFrom,
+    std::io::Write,
+    std::path::PathBuf,
+    std::process,
+    std::sync::atomic::AtomicUsize,
+    std::sync::atomic::Ordering,
+
+    arya::JsonBigNumbers,
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonClosers,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonForbiddenAction,
+    arya::JsonRepair,
+    arya::JsonSource,
+    arya::JsonStatus,
+};
+
+
+
+// the number of spill files created by this process, to give each one a unique name.
+static FILES: AtomicUsize = AtomicUsize::new(0);
+
+
+
+/// a [`JsonBuilder`](./struct.JsonBuilder.html) that spills the json object to a temporary file once it grows past a
+/// threshold, so that multi-gigabyte documents can be repaired in bounded memory.
+///
+/// # remarks
+///
+/// whenever the json object held in memory grows past `threshold` bytes, every byte that no completion could drop is
+/// appended to the file and released. the completed json object is produced by copying the file, and then the rest of
+/// the json object and its closing tokens, into a writer with `completed_to()`. the file is deleted when this builder
+/// is dropped.
+///
+/// a value is only spilled once it is complete, so a single string or number larger than `threshold` is held in
+/// memory until it ends.
+///
+/// repairs need the whole json object, so options with coercions, big number handling, defaults, indented closers, a
+/// shape, stripped forbidden keys or normalized keys fail with `JsonErrorKind::Options`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilderOptions, JsonSpillBuilder};
+/// #
+/// # fn main() {
+/// #
+/// let mut builder = JsonSpillBuilder::with_options(JsonBuilderOptions::default(), 16).unwrap();
+///
+/// builder.update(r#"{ "a": [1, 2, 3], "b": [4, 5, 6], "c": "lor"#).unwrap();
+///
+/// assert!(builder.spilled() > 0);
+///
+/// let mut output = vec![];
+///
+/// builder.completed_to(&mut output).unwrap();
+///
+/// assert_eq!(output, br#"{ "a": [1, 2, 3], "b": [4, 5, 6]}"#.to_vec());
+/// # }
+/// ```
+pub struct JsonSpillBuilder {
+    builder:   JsonBuilder,
+    threshold: usize,
+
+    // the directory spill files are created in, and the spill file once it has been created.
+    directory: PathBuf,
+    file:      Option<(PathBuf, File)>,
+
+    // the number of bytes spilled, the repairs made in them, and whether an update failed.
+    spilled:   usize,
+    repairs:   Vec<JsonRepair>,
+    invalid:   bool,
+}
+
+impl JsonSpillBuilder {
+    /// creates a builder that spills to the system's temporary directory past `threshold` bytes.
+    pub fn new(threshold: usize) -> JsonSpillBuilder {
+        JsonSpillBuilder::create(JsonBuilder::new(), threshold)
+    }
+
+    /// creates a builder with `options` that spills to the system's temporary directory past `threshold` bytes.
+    pub fn with_options(options: JsonBuilderOptions, threshold: usize) -> Result<JsonSpillBuilder, JsonError> {
+        let strip = match &options.forbidden_keys {
+            Some(forbidden) => forbidden.action == JsonForbiddenAction::Strip,
+            None            => false,
+        };
+
+        #[cfg(feature = "unicode")]
+        let nfc = options.normalize_keys;
+        #[cfg(not(feature = "unicode"))]
+        let nfc = false;
+
+        let unsupported = !options.coercions.is_empty()
+            || options.big_numbers != JsonBigNumbers::Ignore
+            || !options.defaults.is_empty()
+            || options.closers != JsonClosers::Inline
+            || options.shape.is_some()
+            || strip
+            || nfc;
+
+        match unsupported {
+            true  => Err(JsonErrorKind::Options.into()),
+            false => Ok(JsonSpillBuilder::create(JsonBuilder::with_options(options), threshold)),
+        }
+    }
+
+    fn create(builder: JsonBuilder, threshold: usize) -> JsonSpillBuilder {
+        JsonSpillBuilder {
+            builder,
+            threshold,
+
+            directory: std::env::temp_dir(),
+            file:      None,
+
+            spilled:   0,
+            repairs:   vec![],
+            invalid:   false,
+        }
+    }
+
+    /// spills to a file in `directory` instead of the system's temporary directory.
+    pub fn in_directory(mut self, directory: impl Into<PathBuf>) -> JsonSpillBuilder {
+        self.directory = directory.into();
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.spilled + self.builder.len()
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.builder.status()
+    }
+
+    /// returns the number of bytes spilled to the file.
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    /// returns the repairs made so far. see [`JsonBuilder::repairs()`](./struct.JsonBuilder.html#method.repairs).
+    pub fn repairs(&self) -> Vec<JsonRepair> {
+        let spilled = self.spilled;
+        let repairs = self.builder.repairs().iter().map(|x| JsonRepair { offset: x.offset + spilled, ..*x });
+
+        self.repairs.iter().cloned().chain(repairs).collect()
+    }
+
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        let result = self.builder.update(source).and_then(|()| {
+            match self.builder.len() > self.threshold {
+                true  => self.spill(),
+                false => Ok(()),
+            }
+        });
+
+        if result.is_err() {
+            self.invalid = true;
+        }
+
+        result
+    }
+
+    /// completes the json object like
+    /// [`JsonBuilder::completed_bytes()`](./struct.JsonBuilder.html#method.completed_bytes), and writes it to `writer`.
+    pub fn completed_to(mut self, mut writer: impl Write) -> Result<(), JsonError> {
+        let (data, tokens) = self.builder.completed_parts()?;
+
+        if let Some((_, file)) = &mut self.file {
+            file.seek(SeekFrom::Start(0)).map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+            io::copy(file, &mut writer).map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+        }
+
+        writer.write_all(data).map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+        writer.write_all(&tokens).map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+        writer.flush().map_err(|_| JsonError::from(JsonErrorKind::Io))
+    }
+
+    // appends every byte that no completion could drop to the spill file, creating it if needed.
+    fn spill(&mut self) -> Result<(), JsonError> {
+        if self.file.is_none() {
+            let name = format!("arya-{}-{}.json", process::id(), FILES.fetch_add(1, Ordering::Relaxed));
+            let path = self.directory.join(name);
+            let file = fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+
+            self.file = Some((path, file));
+        }
+
+        let (bytes, repairs) = self.builder.drain_committed();
+        let spilled          = self.spilled;
+
+        if let Some((_, file)) = &mut self.file {
+            file.write_all(&bytes).map_err(|_| JsonError::from(JsonErrorKind::Io))?;
+        }
+
+        self.repairs.extend(repairs.into_iter().map(|x| JsonRepair { offset: x.offset + spilled, ..x }));
+        self.spilled += bytes.len();
+
+        Ok(())
+    }
+}
+
+impl Drop for JsonSpillBuilder {
+    fn drop(&mut self) {
+        if let Some((path, file)) = self.file.take() {
+            drop(file);
+            let _ = fs::remove_file(path);
+        }
+    }
+}
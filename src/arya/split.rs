@@ -0,0 +1,158 @@
+use {
+    hina,
+
+    std::collections::VecDeque,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+};
+
+
+
+/// splits a stream containing one large json array into several smaller json arrays, each no larger than a maximum
+/// size.
+///
+/// # remarks
+///
+/// the input is split only at element boundaries: each output document is a valid json array containing a run of
+/// consecutive elements from the input. elements are never split, so an element that cannot fit into an output document
+/// by itself is an error.
+///
+/// like the [`JsonBuilder`](./struct.JsonBuilder.html), a splitter that has seen invalid input remains invalid.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonArraySplitter;
+/// #
+/// # fn main() {
+/// #
+/// let mut splitter = JsonArraySplitter::new(12);
+///
+/// splitter.update("[1, 2, 3, \"four\", ").unwrap();
+///
+/// assert_eq!(splitter.next_document(), Some(b"[1,2,3]".to_vec()));
+/// assert_eq!(splitter.next_document(), None);
+///
+/// splitter.update("[5, 6], 7]").unwrap();
+///
+/// assert_eq!(splitter.finish().unwrap(), vec![b"[\"four\"]".to_vec(), b"[[5, 6],7]".to_vec()]);
+/// # }
+/// ```
+pub struct JsonArraySplitter {
+    maximum:   usize,
+    invalid:   bool,
+    verifier:  JsonVerifier,
+
+    element:   Vec<u8>,
+    document:  Vec<u8>,
+    completed: VecDeque<Vec<u8>>,
+}
+
+impl JsonArraySplitter {
+    /// creates a splitter that emits json arrays no larger than `maximum_length` bytes.
+    pub fn new(maximum_length: usize) -> JsonArraySplitter {
+        JsonArraySplitter {
+            maximum:   maximum_length,
+            invalid:   false,
+            verifier:  JsonVerifier::new(),
+
+            element:   vec![],
+            document:  vec![b'['],
+            completed: VecDeque::new(),
+        }
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.verifier.status()
+    }
+
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        if self.invalid {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        for &character in source.stream() {
+            if let Err(e) = self.apply(character) {
+                self.invalid = true;
+                return Err(e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// takes the next completed json array, if any.
+    pub fn next_document(&mut self) -> Option<Vec<u8>> {
+        self.completed.pop_front()
+    }
+
+    /// finishes splitting, returning every remaining json array.
+    ///
+    /// fails if the input stream was not a complete json array.
+    pub fn finish(mut self) -> Result<Vec<Vec<u8>>, JsonError> {
+        match !self.invalid && self.verifier.status() == JsonStatus::Valid {
+            true  => Ok(self.completed.drain(..).collect()),
+            false => Err(JsonErrorKind::Invalid.into()),
+        }
+    }
+
+
+
+    fn apply(&mut self, character: u8) -> Result<(), JsonError> {
+        let depth     = self.verifier.depth();
+        let separator = depth == 1 && !self.verifier.in_string();
+
+        self.verifier.update(character)?;
+
+        match (depth, character) {
+            // only top-level arrays can be split.
+            (0, b'{') => Err(JsonErrorKind::Invalid.into()),
+            (0, _)    => Ok(()),
+
+            (_, b',') if separator => self.push_element(false),
+            (_, b']') if separator => self.push_element(true),
+
+            _ => {
+                self.element.push(character);
+                Ok(())
+            },
+        }
+    }
+
+    fn push_element(&mut self, last: bool) -> Result<(), JsonError> {
+        {
+            let element = hina::trim(&self.element);
+
+            if !element.is_empty() {
+                if element.len() + 2 > self.maximum {
+                    return Err(JsonErrorKind::TooLarge.into());
+                }
+
+                let separator = self.document.len() > 1;
+                let length    = self.document.len() + separator as usize + element.len() + 1;
+
+                if length > self.maximum {
+                    self.document.push(b']');
+                    self.completed.push_back(std::mem::replace(&mut self.document, vec![b'[']));
+                } else if separator {
+                    self.document.push(b',');
+                }
+
+                self.document.extend_from_slice(element);
+            }
+        }
+
+        self.element.clear();
+
+        if last {
+            self.document.push(b']');
+            self.completed.push_back(std::mem::take(&mut self.document));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use {
+    std::collections::VecDeque,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+};
+
+
+
+/// a json builder for server-sent event streams, where the `data` of every event is a separate json document.
+///
+/// # remarks
+///
+/// the stream is split into events as it arrives. the `data:` prefix is stripped from each line, the data lines of an
+/// event are joined with line breaks, and the result is built with a [`JsonBuilder`](./struct.JsonBuilder.html). other
+/// fields (`event:`, `id:`, `retry:`) and comments are ignored, as are events without data and the `data: [DONE]`
+/// sentinel that streaming apis such as openai's send at the end.
+///
+/// like [`JsonLines`](./struct.JsonLines.html), an invalid event does not poison the stream. an event that is
+/// dispatched before its json object is complete is invalid, except for the last one: streams are often cut off in the
+/// middle of their final event, so `finish()` completes it. set `incomplete_strings` in the builder options to keep a
+/// partial string, such as the text of a cut off completion, instead of dropping it.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, JsonServerEvents};
+/// #
+/// # fn main() {
+/// #
+/// let mut events = JsonServerEvents::new();
+///
+/// events.update("event: delta\ndata: {\"text\": \"hello\"}\n\n: keep-alive\n\ndata: {\"text\":\r\ndata:  \"wor");
+///
+/// assert_eq!(events.next_event(), Some(Ok(b"{\"text\": \"hello\"}".to_vec())));
+/// assert_eq!(events.next_event(), None);
+///
+/// events.update("ld\"}\n\ndata: [DONE]\n\ndata: {\"text\": \"!\", \"logprobs\": [1, 2");
+///
+/// assert_eq!(events.next_event(), Some(Ok(b"{\"text\":\n \"world\"}".to_vec())));
+/// assert_eq!(events.finish(), vec![Ok(b"{\"text\": \"!\", \"logprobs\": [1, 2]}".to_vec())]);
+/// # }
+/// ```
+pub struct JsonServerEvents {
+    options:   JsonBuilderOptions,
+    builder:   JsonBuilder,
+    line:      Vec<u8>,
+    carriage:  bool,
+    data:      bool,
+    error:     Option<JsonError>,
+    completed: VecDeque<Result<Vec<u8>, JsonError>>,
+}
+
+impl JsonServerEvents {
+    pub fn new() -> JsonServerEvents {
+        JsonServerEvents::with_options(JsonBuilderOptions::default())
+    }
+
+    /// creates a stream that builds the data of every event with a builder created with `options`.
+    pub fn with_options(options: JsonBuilderOptions) -> JsonServerEvents {
+        JsonServerEvents {
+            builder:   JsonBuilder::with_options(options.clone()),
+            options,
+            line:      vec![],
+            carriage:  false,
+            data:      false,
+            error:     None,
+            completed: VecDeque::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.builder.reset();
+        self.line.clear();
+        self.carriage = false;
+        self.data     = false;
+        self.error    = None;
+        self.completed.clear();
+    }
+
+    /// applies `source` to this stream.
+    ///
+    /// invalid events are reported through `next_event()` rather than here, so this never fails.
+    pub fn update(&mut self, source: impl JsonSource) {
+        for &character in source.stream() {
+            // lines end with `\r\n`, `\n` or `\r`.
+            let carriage  = self.carriage;
+            self.carriage = character == b'\r';
+
+            match character {
+                b'\n' if carriage => {},
+                b'\n' | b'\r'     => self.end_line(),
+                _                 => self.line.push(character),
+            }
+        }
+    }
+
+    /// takes the result of the next completed event, if any.
+    pub fn next_event(&mut self) -> Option<Result<Vec<u8>, JsonError>> {
+        self.completed.pop_front()
+    }
+
+    /// finishes the stream, returning the result of every remaining event. a final event that ends before its json
+    /// object is complete is completed.
+    pub fn finish(mut self) -> Vec<Result<Vec<u8>, JsonError>> {
+        if !self.line.is_empty() {
+            self.end_line();
+        }
+
+        if self.data {
+            let builder = std::mem::replace(&mut self.builder, JsonBuilder::new());
+            let result  = match self.error.take() {
+                Some(e) => Err(e),
+                None    => builder.completed_bytes(),
+            };
+
+            self.completed.push_back(result);
+        }
+
+        self.completed.drain(..).collect()
+    }
+
+
+
+    fn end_line(&mut self) {
+        if self.line.is_empty() {
+            return self.dispatch();
+        }
+
+        let line  = std::mem::take(&mut self.line);
+        let colon = line.iter().position(|x| *x == b':').unwrap_or(line.len());
+
+        // comments and fields other than `data` are ignored.
+        if &line[..colon] != b"data" {
+            return;
+        }
+
+        let value = match line.get(colon + 1) {
+            Some(b' ') => &line[colon + 2..],
+            Some(_)    => &line[colon + 1..],
+            None       => &b""[..],
+        };
+
+        if !self.data && value == b"[DONE]" {
+            return;
+        }
+
+        if self.data {
+            self.apply(b"\n");
+        }
+
+        self.apply(value);
+        self.data = true;
+    }
+
+    fn apply(&mut self, data: &[u8]) {
+        if self.error.is_none() {
+            if let Err(e) = self.builder.update(data) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn dispatch(&mut self) {
+        if !self.data {
+            return;
+        }
+
+        let builder = std::mem::replace(&mut self.builder, JsonBuilder::with_options(self.options.clone()));
+        let result  = match (self.error.take(), builder.status()) {
+            (Some(e), _)                => Err(e),
+            (None, JsonStatus::Valid)    => builder.bytes(),
+            (None, JsonStatus::Continue) => Err(JsonErrorKind::Invalid.into()),
+        };
+
+        self.completed.push_back(result);
+        self.data = false;
+    }
+}
@@ -0,0 +1,96 @@
+use {
+    std::task::Poll,
+
+    arya::JsonBuilder,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+};
+
+
+
+/// repairs and completes a json object in steps of a bounded number of bytes, so that a huge document can be repaired
+/// cooperatively - across executor ticks - instead of blocking a worker thread until it is done.
+///
+/// # remarks
+///
+/// each `step()` applies at most `budget` bytes of the source to the builder, and returns `Poll::Pending` while any
+/// remain. the step that applies the last of them also completes the json object, like
+/// [`JsonBuilder::completed_bytes()`](./struct.JsonBuilder.html#method.completed_bytes), and returns it. after that,
+/// or after an error, every step fails with `JsonErrorKind::Invalid`.
+///
+/// only applying the source is budgeted. completing the json object, and amending it - stripping forbidden members,
+/// normalizing keys and adding defaults - are not: they happen all at once in that last step, and each may take time
+/// in proportion to the whole json object rather than to `budget`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilder, JsonRepairTask};
+/// # use std::task::Poll;
+/// #
+/// # fn main() {
+/// #
+/// let mut task = JsonRepairTask::new(JsonBuilder::new(), r#"{ "a": [1, 2, 3], "b": "lorem"#);
+/// let mut steps = 1;
+///
+/// let output = loop {
+///     match task.step(8) {
+///         Poll::Ready(x) => break x,
+///         Poll::Pending  => steps += 1, // e.g. `tokio::task::yield_now().await`.
+///     }
+/// };
+///
+/// assert_eq!(output.unwrap(), br#"{ "a": [1, 2, 3]}"#.to_vec());
+/// assert_eq!(steps, 4);
+/// # }
+/// ```
+pub struct JsonRepairTask<S> {
+    builder: Option<JsonBuilder>,
+    source:  S,
+    offset:  usize,
+}
+
+impl<S: JsonSource> JsonRepairTask<S> {
+    pub fn new(builder: JsonBuilder, source: S) -> JsonRepairTask<S> {
+        JsonRepairTask { builder: Some(builder), source, offset: 0 }
+    }
+
+    /// the number of bytes of the source applied so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// the number of bytes of the source that remain to be applied.
+    pub fn remaining(&self) -> usize {
+        self.source.stream().len() - self.offset
+    }
+
+    /// applies at most `budget` bytes of the source, and returns the completed json object once every byte has been
+    /// applied. a `budget` of zero is treated as one, and completing the json object is not counted against it.
+    pub fn step(&mut self, budget: usize) -> Poll<Result<Vec<u8>, JsonError>> {
+        let mut builder = match self.builder.take() {
+            Some(x) => x,
+            None    => return Poll::Ready(Err(JsonErrorKind::Invalid.into())),
+        };
+
+        let source = self.source.stream();
+        let until  = source.len().min(self.offset + budget.max(1));
+
+        let (length, result) = builder.update_partial(&source[self.offset..until]);
+
+        self.offset += length;
+
+        if let Err(e) = result {
+            return Poll::Ready(Err(e));
+        }
+
+        match self.offset == source.len() {
+            true  => Poll::Ready(builder.completed_bytes()),
+            false => {
+                self.builder = Some(builder);
+                Poll::Pending
+            },
+        }
+    }
+}
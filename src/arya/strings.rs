@@ -0,0 +1,184 @@
+use {
+    std::borrow::Cow,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+};
+
+
+
+/// decodes the json string `span`, including its quotes, into the text it contains.
+///
+/// # remarks
+///
+/// `span` must be exactly one complete json string, such as the range of a
+/// [`JsonTokenKind::String`](./enum.JsonTokenKind.html#variant.String) token. escape sequences - including `\uxxxx`
+/// escapes and surrogate pairs of them - are decoded. escaped lone surrogates cannot be represented in utf8, and are
+/// replaced with u+fffd.
+///
+/// the text is borrowed from `span` if it contains no escape sequences, so decoding most strings does not allocate.
+///
+/// fails with `JsonErrorKind::Invalid` if `span` is not a json string, or with `JsonErrorKind::Utf8` if it is not valid
+/// utf8.
+///
+/// # examples
+///
+/// ```
+/// # use arya::decode_string;
+/// # use std::borrow::Cow;
+/// #
+/// # fn main() {
+/// #
+/// assert_eq!(decode_string(r#""annie""#), Ok(Cow::Borrowed("annie")));
+/// assert_eq!(decode_string(r#""a\tb \u00e9 \ud83d\ude00""#), Ok(Cow::Owned::<str>("a\tb é 😀".to_string())));
+/// # }
+/// ```
+pub fn decode_string<'a>(span: &'a (impl JsonSource + ?Sized)) -> Result<Cow<'a, str>, JsonError> {
+    let span    = span.stream();
+    let content = match span {
+        [b'"', content @ .., b'"'] => content,
+        _                          => return Err(JsonErrorKind::Invalid.into()),
+    };
+
+    if content.contains(&b'\\') {
+        match decode_prefix(span)? {
+            (content, Some(length)) if length == span.len() => {
+                String::from_utf8(content).map(Cow::Owned).map_err(|_| JsonError::from(JsonErrorKind::Utf8))
+            },
+            _ => {
+                Err(JsonErrorKind::Invalid.into())
+            },
+        }
+    } else if content.iter().any(|x| *x < 0x20 || *x == b'"') {
+        Err(JsonErrorKind::Invalid.into())
+    } else {
+        std::str::from_utf8(content).map(Cow::Borrowed).map_err(|_| JsonError::from(JsonErrorKind::Utf8))
+    }
+}
+
+
+
+// decodes the raw text of an object key. a key that cannot be decoded is kept as it is.
+crate fn decode_key(text: &[u8]) -> String {
+    let mut span = Vec::with_capacity(text.len() + 2);
+
+    span.push(b'"');
+    span.extend_from_slice(text);
+    span.push(b'"');
+
+    match decode_string(&span[..]) {
+        Ok(key) => key.into_owned(),
+        Err(_)  => String::from_utf8_lossy(text).into_owned(),
+    }
+}
+
+// decodes the json string at the start of `data`, returning its content and its length including quotes - or `None`
+// for its length if `data` ends before the string is closed, in which case an incomplete escape sequence at the end
+// is dropped.
+//
+// escaped lone surrogates cannot be represented in utf8, and are replaced with u+fffd.
+crate fn decode_prefix(data: &[u8]) -> Result<(Vec<u8>, Option<usize>), JsonError> {
+    if data.first() != Some(&b'"') {
+        return Err(JsonErrorKind::Invalid.into());
+    }
+
+    let mut content = vec![];
+    let mut i       = 1;
+
+    while i < data.len() {
+        let character = data[i];
+
+        match character {
+            b'"' => {
+                return Ok((content, Some(i + 1)));
+            },
+            b'\\' => {
+                let escape = match data.get(i + 1) {
+                    Some(x) => *x,
+                    None    => break,
+                };
+
+                let decoded = match escape {
+                    b'"'  => b'"',
+                    b'\\' => b'\\',
+                    b'/'  => b'/',
+                    b'b'  => 0x08,
+                    b'f'  => 0x0c,
+                    b'n'  => b'\n',
+                    b'r'  => b'\r',
+                    b't'  => b'\t',
+                    b'u'  => {
+                        match decode_unicode(&data[i..])? {
+                            Some((x, length)) => {
+                                let mut buffer = [0; 4];
+
+                                content.extend_from_slice(x.encode_utf8(&mut buffer).as_bytes());
+                                i += length;
+                                continue;
+                            },
+                            None => break,
+                        }
+                    },
+                    _ => return Err(JsonErrorKind::Invalid.into()),
+                };
+
+                content.push(decoded);
+                i += 2;
+            },
+            0x00..=0x1f => {
+                return Err(JsonErrorKind::Invalid.into());
+            },
+            _ => {
+                content.push(character);
+                i += 1;
+            },
+        }
+    }
+
+    Ok((content, None))
+}
+
+// decodes the `\uxxxx` escape (or surrogate pair of them) at the start of `data`, returning the character and the
+// number of bytes it used, or `None` if `data` ends too early.
+fn decode_unicode(data: &[u8]) -> Result<Option<(char, usize)>, JsonError> {
+    let high = match hex(data, 2)? {
+        Some(x) => x,
+        None    => return Ok(None),
+    };
+
+    if (0xd800..0xdc00).contains(&high) {
+        // the low surrogate must follow immediately. if there might still be one, wait for it.
+        if data.len() < 12 && data[6..].iter().zip(b"\\u").all(|(x, y)| x == y) {
+            return Ok(None);
+        }
+
+        if data.len() >= 12 && &data[6..8] == b"\\u" {
+            if let Some(low @ 0xdc00..=0xdfff) = hex(data, 8)? {
+                let x = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+
+                return Ok(Some((std::char::from_u32(x).unwrap_or('\u{fffd}'), 12)));
+            }
+        }
+    }
+
+    Ok(Some((std::char::from_u32(high).unwrap_or('\u{fffd}'), 6)))
+}
+
+// parses the four hex digits at `data[start..]`, or returns `None` if `data` ends too early.
+fn hex(data: &[u8], start: usize) -> Result<Option<u32>, JsonError> {
+    match data.get(start..start + 4) {
+        Some(digits) => {
+            if !digits.iter().all(u8::is_ascii_hexdigit) {
+                return Err(JsonErrorKind::Invalid.into());
+            }
+
+            let digits = std::str::from_utf8(digits).map_err(|_| JsonError::from(JsonErrorKind::Invalid))?;
+
+            u32::from_str_radix(digits, 16).map(Some).map_err(|_| JsonError::from(JsonErrorKind::Invalid))
+        },
+        None => {
+            Ok(None)
+        },
+    }
+}
@@ -8,6 +8,9 @@
 
 use {
     arya::JsonError,
+    arya::JsonErrorKind,
+    arya::fsm,
+    arya::verify::ValueType,
 };
 
 
@@ -50,7 +53,7 @@ crate enum CharacterType {
     Error,        // error-type. will never be returned / passed outside this module.
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(usize)]
 crate enum Token {
     Begin,      // <begin>
@@ -86,6 +89,61 @@ crate enum Token {
     NullNull,   // null
 }
 
+impl Token {
+    // true for every state inside a string, after its opening quote.
+    crate fn is_string(self) -> bool {
+        match self {
+            Token::String | Token::Escape | Token::U1 | Token::U2 | Token::U3 | Token::U4 => true,
+            _                                                                          => false,
+        }
+    }
+
+    // true for every state inside a number.
+    crate fn is_number(self) -> bool {
+        match self {
+            Token::Minus | Token::Zero | Token::Integer | Token::Fraction1 | Token::Fraction2 => true,
+            Token::Exponent1 | Token::Exponent2 | Token::Exponent3                            => true,
+            _                                                                                  => false,
+        }
+    }
+
+    crate fn name(self) -> &'static str {
+        match self {
+            Token::Begin      => "begin",
+            Token::Ok         => "ok",
+            Token::Object     => "object",
+            Token::Key        => "key",
+            Token::Colon      => "colon",
+            Token::Value      => "value",
+            Token::Array      => "array",
+            Token::String     => "string",
+            Token::Escape     => "escape",
+            Token::U1         => "u1",
+            Token::U2         => "u2",
+            Token::U3         => "u3",
+            Token::U4         => "u4",
+            Token::Minus      => "minus",
+            Token::Zero       => "zero",
+            Token::Integer    => "integer",
+            Token::Fraction1  => "fraction 1",
+            Token::Fraction2  => "fraction 2",
+            Token::Exponent1  => "exponent 1",
+            Token::Exponent2  => "exponent 2",
+            Token::Exponent3  => "exponent 3",
+            Token::TrueTr     => "tr",
+            Token::TrueTru    => "tru",
+            Token::TrueTrue   => "true",
+            Token::FalseFa    => "fa",
+            Token::FalseFal   => "fal",
+            Token::FalseFals  => "fals",
+            Token::FalseFalse => "false",
+            Token::NullNu     => "nu",
+            Token::NullNul    => "nul",
+            Token::NullNull   => "null",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 crate enum ComplexToken {
     BraceEmptyClose, // } - empty brace
@@ -106,8 +164,31 @@ crate enum Transition {
     Error, // error-type. will never be returned / passed outside this module.
 }
 
+// what a character does to the stack of open containers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+crate enum Effect {
+    None,                // the stack is unchanged.
+    Open(ValueType),     // a container is opened, and pushed.
+    Close,               // the innermost container is closed, and popped.
+    Separate(ValueType), // a comma ends a value, and the innermost container becomes this one.
+    Replace(ValueType),  // a colon ends a key, and the innermost container becomes this one.
+}
+
+// the number of states - one per `Token` - and of character types other than `CharacterType::Error`, which are the
+// rows and columns of `TRANSITIONS`.
+crate const STATES:     usize = Token::NullNull as usize + 1;
+crate const CHARACTERS: usize = CharacterType::Other as usize + 1;
+
+// `flatten()` writes states as their discriminants and container operations from `0x80` up, so every state must fit
+// below them. `CharacterType::Error` must come last, so that it is never a column.
+const _: () = assert!(STATES <= fsm::CLOSE_EMPTY_OBJECT as usize, "states overlap the container operations.");
+const _: () = assert!(CharacterType::Error as usize == CHARACTERS, "the error type must be the last character type.");
 
-const TRANSITIONS: [[Transition; 31]; 31] = {
+// the first two columns are rfc 8259 whitespace. it is accepted in every state between tokens - `continue` (before the
+// first token), `ok`, `object`, `key`, `colon`, `value` and `array` - where it leaves the state as it is, and it ends a
+// number that could be complete. it is rejected everywhere else, and a string only accepts a space. see
+// `tests/whitespace.rs`.
+const TRANSITIONS: [[Transition; CHARACTERS]; STATES] = {
     use self::{
         ComplexToken::*,
         Token::*,
@@ -150,6 +231,31 @@ const TRANSITIONS: [[Transition; 31]; 31] = {
     ]
 };
 
+// unicode whitespace, other than rfc 8259 whitespace, that `JsonWhitespace::Normalize` accepts between tokens.
+const EXTRA_WHITESPACE: [&[u8]; 21] = [
+    b"\x0b",         // vertical tab
+    b"\x0c",         // form feed
+    b"\xc2\xa0",     // no-break space
+    b"\xe1\x9a\x80", // ogham space mark
+    b"\xe2\x80\x80", // en quad
+    b"\xe2\x80\x81", // em quad
+    b"\xe2\x80\x82", // en space
+    b"\xe2\x80\x83", // em space
+    b"\xe2\x80\x84", // three-per-em space
+    b"\xe2\x80\x85", // four-per-em space
+    b"\xe2\x80\x86", // six-per-em space
+    b"\xe2\x80\x87", // figure space
+    b"\xe2\x80\x88", // punctuation space
+    b"\xe2\x80\x89", // thin space
+    b"\xe2\x80\x8a", // hair space
+    b"\xe2\x80\xa8", // line separator
+    b"\xe2\x80\xa9", // paragraph separator
+    b"\xe2\x80\xaf", // narrow no-break space
+    b"\xe2\x81\x9f", // medium mathematical space
+    b"\xe3\x80\x80", // ideographic space
+    b"\xef\xbb\xbf", // zero width no-break space (byte order mark)
+];
+
 const CATEGORIES: [CharacterType; 128] = {
     use self::{
         CharacterType::*,
@@ -183,16 +289,110 @@ crate fn character_type(character: u8) -> Result<CharacterType, JsonError> {
     debug_assert![character < 128];
 
     match CATEGORIES[character as usize] {
-        x if x == CharacterType::Error => Err(JsonError::Invalid),
+        x if x == CharacterType::Error => Err(JsonErrorKind::Invalid.into()),
         x                              => Ok(x),
     }
 }
 
+// returns `Some(true)` if `sequence` is non-standard whitespace, `Some(false)` if it is the start of some, and `None`
+// otherwise.
+crate fn extra_whitespace(sequence: &[u8]) -> Option<bool> {
+    let mut result = None;
+
+    for whitespace in EXTRA_WHITESPACE.iter() {
+        if whitespace.starts_with(sequence) {
+            result = Some(result.unwrap_or(false) || whitespace.len() == sequence.len());
+        }
+    }
+
+    result
+}
+
 crate fn transition(from: Token, ty: CharacterType) -> Result<Transition, JsonError> {
     debug_assert![ty != CharacterType::Error];
 
     match TRANSITIONS[from as usize][ty as usize] {
-        x if x == Transition::Error => Err(JsonError::Invalid),
+        x if x == Transition::Error => Err(JsonErrorKind::Invalid.into()),
         x                           => Ok(x),
     }
 }
+
+// applies `character` to `state`, where `innermost` is the innermost open container, returning the next state and what
+// happens to the stack of open containers. every verifier - and `arya::fsm::step()` - reads the grammar through this.
+crate fn step(state: Token, character: u8, innermost: Option<ValueType>) -> Result<(Token, Effect), JsonError> {
+    // utf8 continuation.
+    if character >= 128 {
+        return Ok((state, Effect::None));
+    }
+
+    let ty = match transition(state, character_type(character)?)? {
+        Transition::Simple(to)  => return Ok((to, Effect::None)),
+        Transition::Complex(ty) => ty,
+        Transition::Error       => return Err(JsonErrorKind::Invalid.into()),
+    };
+
+    match (ty, innermost) {
+        (ComplexToken::BraceEmptyClose, Some(ValueType::Key))    => Ok((Token::Ok, Effect::Close)),
+        (ComplexToken::BraceClose,      Some(ValueType::Object)) => Ok((Token::Ok, Effect::Close)),
+        (ComplexToken::BracketClose,    Some(ValueType::Array))  => Ok((Token::Ok, Effect::Close)),
+        (ComplexToken::BraceOpen,       _)                       => Ok((Token::Object, Effect::Open(ValueType::Key))),
+        (ComplexToken::BracketOpen,     _)                       => Ok((Token::Array, Effect::Open(ValueType::Array))),
+        (ComplexToken::Quote,           Some(ValueType::Key))    => Ok((Token::Colon, Effect::None)),
+        (ComplexToken::Quote,           Some(_))                 => Ok((Token::Ok, Effect::None)),
+        (ComplexToken::Comma,           Some(ValueType::Object)) => Ok((Token::Key, Effect::Separate(ValueType::Key))),
+        (ComplexToken::Comma,           Some(ValueType::Array))  => Ok((Token::Value, Effect::Separate(ValueType::Array))),
+        (ComplexToken::Kolon,           Some(ValueType::Key))    => Ok((Token::Value, Effect::Replace(ValueType::Object))),
+        (_,                             _)                       => Err(JsonErrorKind::Invalid.into()),
+    }
+}
+
+// the transition table as a flat table of bytes, with one row per state and one column per byte. see
+// `arya::fsm::TRANSITIONS` for the encoding.
+crate const fn flatten() -> [[u8; 256]; STATES] {
+    use arya::fsm::{
+        CLOSE_ARRAY,
+        CLOSE_EMPTY_OBJECT,
+        CLOSE_OBJECT,
+        COLON,
+        COMMA,
+        OPEN_ARRAY,
+        OPEN_OBJECT,
+        QUOTE,
+        REJECT,
+    };
+
+    let mut table = [[REJECT; 256]; STATES];
+    let mut from  = 0;
+
+    while from < STATES {
+        let mut character = 0;
+
+        while character < 256 {
+            table[from][character] = match character < 128 {
+                true  => match CATEGORIES[character] {
+                    CharacterType::Error => REJECT,
+                    ty                   => match TRANSITIONS[from][ty as usize] {
+                        Transition::Simple(to)                             => to as u8,
+                        Transition::Complex(ComplexToken::BraceEmptyClose) => CLOSE_EMPTY_OBJECT,
+                        Transition::Complex(ComplexToken::BraceClose)      => CLOSE_OBJECT,
+                        Transition::Complex(ComplexToken::BracketClose)    => CLOSE_ARRAY,
+                        Transition::Complex(ComplexToken::BraceOpen)       => OPEN_OBJECT,
+                        Transition::Complex(ComplexToken::BracketOpen)     => OPEN_ARRAY,
+                        Transition::Complex(ComplexToken::Quote)           => QUOTE,
+                        Transition::Complex(ComplexToken::Comma)           => COMMA,
+                        Transition::Complex(ComplexToken::Kolon)           => COLON,
+                        Transition::Error                                  => REJECT,
+                    },
+                },
+                // utf8 continuation.
+                false => from as u8,
+            };
+
+            character += 1;
+        }
+
+        from += 1;
+    }
+
+    table
+}
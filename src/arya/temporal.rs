@@ -0,0 +1,293 @@
+use {
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::events::push_value,
+};
+
+
+
+/// a string value that looks like an [rfc 3339](https://tools.ietf.org/html/rfc3339) timestamp.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonTimestamp {
+    /// the json pointer ([rfc 6901](https://tools.ietf.org/html/rfc6901)) to this value.
+    ///
+    /// object keys are used as they appear in the input, without unescaping.
+    pub path:  String,
+
+    /// what this value contains.
+    pub kind:  JsonTimestampKind,
+
+    /// the byte range of this value, including its quotes.
+    pub range: Range<usize>,
+}
+
+
+
+/// the kind of a [`JsonTimestamp`](./struct.JsonTimestamp.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonTimestampKind {
+    /// a date and time with an offset, such as `2024-02-29T13:45:00.5+09:00`.
+    DateTime,
+
+    /// a date alone, such as `2024-02-29`.
+    Date,
+
+    /// a time with an offset, such as `13:45:00Z`.
+    Time,
+}
+
+
+
+/// a json syntax validator that also records every string value that looks like an
+/// [rfc 3339](https://tools.ietf.org/html/rfc3339) timestamp, as hints for schema inference.
+///
+/// # remarks
+///
+/// a value is only recorded if it is nothing but a timestamp, and its fields are in range - `2023-02-29` is not a
+/// date. the separator between the date and time may be `T`, `t` or a space, and the offset may be `Z` or `z`.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonTimestampKind, JsonTimestamps};
+/// #
+/// # fn main() {
+/// #
+/// let mut timestamps = JsonTimestamps::new();
+///
+/// timestamps.update(r#"{ "id": 7, "events": [{ "at": "2024-02-29T13:45:00Z", "on": "2024-03-01" }] }"#).unwrap();
+///
+/// let entries = timestamps.entries().unwrap();
+///
+/// assert_eq!(entries[0].path, "/events/0/at");
+/// assert_eq!(entries[0].kind, JsonTimestampKind::DateTime);
+///
+/// assert_eq!(entries[1].path, "/events/0/on");
+/// assert_eq!(entries[1].kind, JsonTimestampKind::Date);
+/// # }
+/// ```
+pub struct JsonTimestamps {
+    reader:  EventReader,
+    entries: Vec<JsonTimestamp>,
+
+    // one frame per open container: the length of its path, and the number of values seen. then the json pointer to
+    // the innermost container, the key of the value currently being read, and the content of the string currently
+    // being read - up to the length of the longest timestamp worth checking.
+    frames:  Vec<(usize, usize)>,
+    path:    String,
+    key:     Option<String>,
+    text:    Vec<u8>,
+}
+
+impl JsonTimestamps {
+    pub fn new() -> JsonTimestamps {
+        JsonTimestamps::with_maximum_depth(std::usize::MAX)
+    }
+
+    pub fn with_maximum_depth(maximum_depth: usize) -> JsonTimestamps {
+        JsonTimestamps {
+            reader:  EventReader::new(JsonVerifier::with_maximum_depth(maximum_depth)),
+            entries: vec![],
+
+            frames:  vec![],
+            path:    String::new(),
+            key:     None,
+            text:    vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.reader.offset()
+    }
+
+    pub fn status(&self) -> JsonStatus {
+        self.reader.verifier().status()
+    }
+
+    pub fn reset(&mut self) {
+        self.reader.reset();
+        self.entries.clear();
+        self.frames.clear();
+        self.path.clear();
+        self.text.clear();
+        self.key = None;
+    }
+
+    /// applies `source` to this json object.
+    ///
+    /// like [`JsonVerifier::update()`](./struct.JsonVerifier.html#method.update), an invalid character is rejected
+    /// without changing any state.
+    pub fn update(&mut self, source: impl JsonSource) -> Result<(), JsonError> {
+        let JsonTimestamps { reader, entries, frames, path, key, text } = self;
+
+        for &character in source.stream() {
+            let string = reader.verifier().in_string();
+
+            reader.update(character, |event| {
+                match event {
+                    Event::Begin(_, _) => {
+                        let length = path.len();
+
+                        push_value(frames, path, key);
+                        frames.push((length, 0));
+                    },
+                    Event::End(_) => {
+                        if let Some((length, _)) = frames.pop() {
+                            path.truncate(length);
+                        }
+                    },
+                    Event::Key(_, name) => {
+                        *key = Some(String::from_utf8_lossy(name).into_owned());
+                    },
+                    Event::Scalar(kind, range) => {
+                        let length = path.len();
+
+                        push_value(frames, path, key);
+
+                        if kind == JsonValueKind::String {
+                            if let Some(kind) = timestamp(text) {
+                                entries.push(JsonTimestamp { path: path.clone(), kind, range });
+                            }
+                        }
+
+                        path.truncate(length);
+                    },
+                }
+            })?;
+
+            match (string, reader.verifier().in_string()) {
+                (false, true)                         => text.clear(),
+                (true, true) if text.len() <= MAXIMUM => text.push(character),
+                _                                     => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    /// returns every timestamp, in the order they appear in the input, if this json object is complete.
+    pub fn entries(&self) -> Option<&[JsonTimestamp]> {
+        match self.status() {
+            JsonStatus::Valid    => Some(&self.entries),
+            JsonStatus::Continue => None,
+        }
+    }
+
+    /// consumes this validator, returning every timestamp if this json object is complete.
+    pub fn into_entries(self) -> Result<Vec<JsonTimestamp>, JsonError> {
+        match self.status() {
+            JsonStatus::Valid    => Ok(self.entries),
+            JsonStatus::Continue => Err(JsonErrorKind::Invalid.into()),
+        }
+    }
+}
+
+
+
+// the length of the longest string that is checked. fractional seconds may be arbitrarily long, but a timestamp with
+// nanosecond precision and an offset is only 35 bytes.
+const MAXIMUM: usize = 64;
+
+// returns the kind of timestamp `text` is, if it is one.
+fn timestamp(text: &[u8]) -> Option<JsonTimestampKind> {
+    if text.len() > MAXIMUM {
+        return None;
+    }
+
+    match (date(text), time(text)) {
+        (Some(10), _) if text.len() == 10 => Some(JsonTimestampKind::Date),
+        (Some(10), _)                     => {
+            match (text[10], time(&text[11..])) {
+                (b'T', Some(n)) | (b't', Some(n)) | (b' ', Some(n)) if 11 + n == text.len() => {
+                    Some(JsonTimestampKind::DateTime)
+                },
+                _ => {
+                    None
+                },
+            }
+        },
+        (_, Some(n)) if n == text.len() => Some(JsonTimestampKind::Time),
+        _                                => None,
+    }
+}
+
+// returns the length of the `full-date` at the start of `text`, if there is one.
+fn date(text: &[u8]) -> Option<usize> {
+    let year  = number(text, 0, 4)?;
+    let month = number(text, 5, 2)?;
+    let day   = number(text, 8, 2)?;
+
+    if text[4] != b'-' || text[7] != b'-' {
+        return None;
+    }
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        2 if leap      => 29,
+        2              => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12         => 31,
+        _              => return None,
+    };
+
+    match (1..=days).contains(&day) {
+        true  => Some(10),
+        false => None,
+    }
+}
+
+// returns the length of the `full-time` - a time with an offset - at the start of `text`, if there is one.
+fn time(text: &[u8]) -> Option<usize> {
+    let hour   = number(text, 0, 2)?;
+    let minute = number(text, 3, 2)?;
+    let second = number(text, 6, 2)?;
+
+    if text[2] != b':' || text[5] != b':' || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut i = 8;
+
+    if text.get(i) == Some(&b'.') {
+        match text[i + 1..].iter().take_while(|x| x.is_ascii_digit()).count() {
+            0 => return None,
+            n => i += n + 1,
+        }
+    }
+
+    match text.get(i) {
+        Some(b'Z') | Some(b'z') => {
+            Some(i + 1)
+        },
+        Some(b'+') | Some(b'-') => {
+            let hour   = number(text, i + 1, 2)?;
+            let minute = number(text, i + 4, 2)?;
+
+            match text[i + 3] == b':' && hour <= 23 && minute <= 59 {
+                true  => Some(i + 6),
+                false => None,
+            }
+        },
+        _ => {
+            None
+        },
+    }
+}
+
+// parses the `length` decimal digits at `text[start..]`.
+fn number(text: &[u8], start: usize, length: usize) -> Option<u32> {
+    let digits = text.get(start..start + length)?;
+
+    match digits.iter().all(u8::is_ascii_digit) {
+        true  => Some(digits.iter().fold(0, |x, y| x * 10 + (y - b'0') as u32)),
+        false => None,
+    }
+}
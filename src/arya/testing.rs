@@ -0,0 +1,119 @@
+//! support for testing and fuzzing code that uses arya.
+//!
+//! [`replay()`](./fn.replay.html) runs a fuzz corpus entry through the verifier and every builder preset, and checks
+//! that they agree with each other. nothing arya does depends on the platform, the locale, the clock or the
+//! environment, so a replay gives the same result on ci, in wasm and on a developer machine - a fuzz finding can be
+//! reproduced anywhere from its corpus entry alone.
+
+
+
+use {
+    arya::JsonBuilder,
+    arya::JsonError,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::diagnostics,
+};
+
+
+
+// the builder presets every corpus entry is replayed through.
+const PRESETS: [(&str, fn() -> JsonBuilder); 4] = [
+    ("default", JsonBuilder::new),
+    ("strict",  JsonBuilder::strict),
+    ("lenient", JsonBuilder::lenient),
+    ("llm",     JsonBuilder::llm),
+];
+
+
+
+/// the result of replaying a fuzz corpus entry. see [`replay()`](./fn.replay.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    /// the status of a [`JsonVerifier`](../struct.JsonVerifier.html) after reading the entry, or the first error.
+    pub status:  Result<JsonStatus, JsonError>,
+
+    /// the name of each builder preset - `default`, `strict`, `lenient` and `llm` - and its completed output.
+    pub outputs: Vec<(&'static str, Result<Vec<u8>, JsonError>)>,
+}
+
+
+
+/// replays the fuzz corpus entry `entry`, and panics if arya breaks one of its invariants.
+///
+/// # remarks
+///
+/// the entry is read by a [`JsonVerifier`](../struct.JsonVerifier.html), and completed by a
+/// [`JsonBuilder`](../struct.JsonBuilder.html) for each preset. this panics if:
+///
+/// - the verifier and [`validate_with_context()`](../fn.validate_with_context.html) disagree about the entry.
+/// - splitting the entry into two chunks changes a builder's completed output.
+/// - a builder completes the entry into output that is not valid json.
+///
+/// # examples
+///
+/// ```
+/// # use arya::JsonStatus;
+/// #
+/// # fn main() {
+/// #
+/// let replay = arya::replay(br#"{ "a": [1, 2"#);
+///
+/// assert_eq!(replay.status, Ok(JsonStatus::Continue));
+/// assert_eq!(replay.outputs[0], ("default", Ok(br#"{ "a": [1, 2]}"#.to_vec())));
+/// # }
+/// ```
+pub fn replay(entry: &[u8]) -> Replay {
+    let status  = verify(entry);
+    let context = match diagnostics::validate_with_context(entry) {
+        Ok(())                        => Ok(JsonStatus::Valid),
+        Err(x) if x.found().is_none() => Ok(JsonStatus::Continue),
+        Err(x)                        => Err(JsonError::at(x.kind(), x.offset().unwrap_or(entry.len()))),
+    };
+
+    assert_eq!(status, context, "the verifier and `validate_with_context()` disagree");
+
+    let outputs = PRESETS
+        .iter()
+        .map(|&(name, create)| {
+            let output = complete(create(), &[entry]);
+            let split  = complete(create(), &[&entry[..entry.len() / 2], &entry[entry.len() / 2..]]);
+
+            assert_eq!(output, split, "splitting the input changed the output of the `{}` preset", name);
+
+            if let Ok(output) = &output {
+                assert_eq!(
+                    verify(output),
+                    Ok(JsonStatus::Valid),
+                    "the `{}` preset completed the input into invalid json",
+                    name);
+            }
+
+            (name, output)
+        })
+        .collect();
+
+    Replay { status, outputs }
+}
+
+
+
+// reads `data` with a verifier.
+fn verify(data: &[u8]) -> Result<JsonStatus, JsonError> {
+    let mut verifier = JsonVerifier::new();
+
+    for (offset, &character) in data.iter().enumerate() {
+        verifier.update(character).map_err(|error| error.with_offset(offset))?;
+    }
+
+    Ok(verifier.status())
+}
+
+// applies each of `chunks` to `builder`, and completes it.
+fn complete(mut builder: JsonBuilder, chunks: &[&[u8]]) -> Result<Vec<u8>, JsonError> {
+    for chunk in chunks {
+        builder.update(*chunk)?;
+    }
+
+    builder.completed_bytes()
+}
@@ -0,0 +1,179 @@
+use {
+    std::ops::Range,
+
+    arya::JsonSource,
+};
+
+
+
+/// the kind of a [`JsonToken`](./struct.JsonToken.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonTokenKind {
+    /// `{`
+    BeginObject,
+
+    /// `}`
+    EndObject,
+
+    /// `[`
+    BeginArray,
+
+    /// `]`
+    EndArray,
+
+    /// `:`
+    Colon,
+
+    /// `,`
+    Comma,
+
+    /// a string, including its quotes. `terminated` is `false` if the input ended before its closing quote.
+    String { terminated: bool },
+
+    /// a `-` or digit, and the run of characters after it that may make up a number: digits, `-`, `+`, `.`, `e` and
+    /// `E`.
+    Number,
+
+    /// a run of ascii letters, such as `true`, `null` or `None`.
+    Literal,
+
+    /// a run of the whitespace characters allowed by rfc 8259.
+    Whitespace,
+
+    /// a run of any other bytes, which cannot begin a token.
+    Other,
+}
+
+
+
+/// a token read by a [`JsonTokenizer`](./struct.JsonTokenizer.html), and the byte offsets of the input it spans.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonToken {
+    pub kind:  JsonTokenKind,
+    pub range: Range<usize>,
+}
+
+
+
+/// splits json into raw tokens - punctuation, strings, numbers, literals and whitespace - along with their offsets.
+///
+/// # remarks
+///
+/// this is only a lexer: it does not check that tokens appear in a valid order, or that numbers, literals and escape
+/// sequences are well formed, and it never fails. every byte of the input belongs to exactly one token, so the ranges
+/// of the tokens together cover the whole input. use a [`JsonVerifier`](./struct.JsonVerifier.html) to validate it.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonToken, JsonTokenKind, JsonTokenizer};
+/// #
+/// # fn main() {
+/// #
+/// let tokens = JsonTokenizer::new(r#"{"a": [1.5, tru"#).collect::<Vec<_>>();
+///
+/// assert_eq!(tokens, vec![
+///     JsonToken { kind: JsonTokenKind::BeginObject,                 range: 0..1   },
+///     JsonToken { kind: JsonTokenKind::String { terminated: true }, range: 1..4   },
+///     JsonToken { kind: JsonTokenKind::Colon,                       range: 4..5   },
+///     JsonToken { kind: JsonTokenKind::Whitespace,                  range: 5..6   },
+///     JsonToken { kind: JsonTokenKind::BeginArray,                  range: 6..7   },
+///     JsonToken { kind: JsonTokenKind::Number,                      range: 7..10  },
+///     JsonToken { kind: JsonTokenKind::Comma,                       range: 10..11 },
+///     JsonToken { kind: JsonTokenKind::Whitespace,                  range: 11..12 },
+///     JsonToken { kind: JsonTokenKind::Literal,                     range: 12..15 },
+/// ]);
+/// # }
+/// ```
+pub struct JsonTokenizer<'a> {
+    data:   &'a [u8],
+    offset: usize,
+}
+
+impl<'a> JsonTokenizer<'a> {
+    pub fn new(source: &'a (impl JsonSource + ?Sized)) -> JsonTokenizer<'a> {
+        JsonTokenizer { data: source.stream(), offset: 0 }
+    }
+
+    /// returns the offset of the next token.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for JsonTokenizer<'a> {
+    type Item = JsonToken;
+
+    fn next(&mut self) -> Option<JsonToken> {
+        let data  = self.data;
+        let start = self.offset;
+        let first = *data.get(start)?;
+
+        // the first byte is always part of the token, so that `Other` makes progress even if a number or literal
+        // could begin at the byte after it.
+        let run = |predicate: fn(u8) -> bool| {
+            start + 1 + data[start + 1..].iter().take_while(|x| predicate(**x)).count()
+        };
+
+        let (kind, end) = match first {
+            b'{' => (JsonTokenKind::BeginObject, start + 1),
+            b'}' => (JsonTokenKind::EndObject,   start + 1),
+            b'[' => (JsonTokenKind::BeginArray,  start + 1),
+            b']' => (JsonTokenKind::EndArray,    start + 1),
+            b':' => (JsonTokenKind::Colon,       start + 1),
+            b',' => (JsonTokenKind::Comma,       start + 1),
+            b'"' => string(data, start),
+
+            b'-' | b'0'..=b'9'        => (JsonTokenKind::Number,     run(is_number)),
+            _ if is_whitespace(first) => (JsonTokenKind::Whitespace, run(is_whitespace)),
+            _ if is_literal(first)    => (JsonTokenKind::Literal,    run(is_literal)),
+            _                         => (JsonTokenKind::Other,      run(is_other)),
+        };
+
+        self.offset = end;
+
+        Some(JsonToken { kind, range: start..end })
+    }
+}
+
+
+
+// reads the string that begins at `start`, skipping over escaped characters.
+fn string(data: &[u8], start: usize) -> (JsonTokenKind, usize) {
+    let mut i = start + 1;
+
+    while i < data.len() {
+        match data[i] {
+            b'"'  => return (JsonTokenKind::String { terminated: true }, i + 1),
+            b'\\' => i += 2,
+            _     => i += 1,
+        }
+    }
+
+    (JsonTokenKind::String { terminated: false }, data.len())
+}
+
+fn is_whitespace(character: u8) -> bool {
+    match character {
+        b' ' | b'\t' | b'\n' | b'\r' => true,
+        _                            => false,
+    }
+}
+
+fn is_number(character: u8) -> bool {
+    match character {
+        b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' => true,
+        _                                              => false,
+    }
+}
+
+fn is_literal(character: u8) -> bool {
+    character.is_ascii_alphabetic()
+}
+
+// true for every byte that does not begin another kind of token.
+fn is_other(character: u8) -> bool {
+    let token = b"{}[]:,\"-".contains(&character) || character.is_ascii_digit();
+
+    !token && !is_whitespace(character) && !is_literal(character)
+}
@@ -0,0 +1,96 @@
+use {
+    std::fmt,
+
+    arya::table::Token,
+};
+
+
+
+/// a state of the json state machine that arya's parsers are built on.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JsonState(crate Token);
+
+impl JsonState {
+    /// a short, human-readable name for this state.
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+}
+
+impl fmt::Debug for JsonState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+
+
+/// a single transition recorded by a [`JsonVerifier`](./struct.JsonVerifier.html) in trace mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JsonTransition {
+    /// the number of characters accepted before this one.
+    pub offset:    usize,
+
+    /// the character that was applied.
+    pub character: u8,
+
+    /// the state before `character` was applied.
+    pub before:    JsonState,
+
+    /// the state after `character` was applied. if `character` was rejected, this is the same as `before`.
+    pub after:     JsonState,
+
+    /// the number of containers open after `character` was applied.
+    pub depth:     usize,
+
+    /// whether `character` was accepted.
+    pub accepted:  bool,
+}
+
+
+
+/// receives every transition a [`JsonVerifier`](./struct.JsonVerifier.html) makes, so that validation and event
+/// emission share a single pass over the input.
+///
+/// # remarks
+///
+/// a verifier created with `JsonVerifier::new()` has the sink `()`, which does nothing and costs nothing.
+///
+/// only accepted characters reach the sink - in the same order, and with the same states, as trace mode records them.
+/// a multi-byte character that `update_char()` rejects part way through reports none of its bytes.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonTransition, JsonVerifier, JsonVerifierOptions, TokenSink};
+/// #
+/// #[derive(Default)]
+/// struct Strings(usize);
+///
+/// impl TokenSink for Strings {
+///     fn transition(&mut self, transition: &JsonTransition) {
+///         if transition.after.name() == "ok" && transition.character == b'"' {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+///
+/// # fn main() {
+/// #
+/// let mut json = JsonVerifier::with_sink(JsonVerifierOptions::default(), Strings::default());
+///
+/// for character in br#"{ "a": "b", "c": ["d", 1, "e"] }"#.iter() {
+///     json.update(*character).unwrap();
+/// }
+///
+/// assert_eq!(json.sink().0, 3);
+/// # }
+/// ```
+pub trait TokenSink {
+    /// called after each character is accepted, with the transition it caused.
+    fn transition(&mut self, transition: &JsonTransition) {
+        let _ = transition;
+    }
+}
+
+impl TokenSink for () {}
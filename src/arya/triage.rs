@@ -0,0 +1,152 @@
+use {
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonRepair,
+    arya::JsonSource,
+    arya::JsonStatus,
+};
+
+
+
+/// receives the payloads sorted by [`JsonTriage`](./struct.JsonTriage.html).
+pub trait JsonPartition {
+    /// called with a payload that is valid json as it is.
+    fn ok(&mut self, payload: &[u8]);
+
+    /// called with a payload that was repaired or completed into valid json, the repaired json, and the repairs made.
+    fn repaired(&mut self, payload: &[u8], repaired: &[u8], repairs: &[JsonRepair]);
+
+    /// called with a payload that could not be repaired, and the first error in it.
+    fn failed(&mut self, payload: &[u8], error: JsonError);
+}
+
+
+
+/// validates and repairs a stream of independent json payloads - such as messages consumed from kafka or another
+/// event bus - and sorts each one into ok, repaired or failed.
+///
+/// # remarks
+///
+/// one builder is reused for every payload. the repairs that are attempted are set by the builder options, and
+/// payloads that end before their json object is complete are only completed if `complete` is set.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonBuilderOptions, JsonError, JsonPartition, JsonRepair, JsonTriage};
+/// #
+/// #[derive(Default)]
+/// struct Topics {
+///     ok:       Vec<Vec<u8>>,
+///     repaired: Vec<Vec<u8>>,
+///     failed:   Vec<Option<usize>>,
+/// }
+///
+/// impl JsonPartition for Topics {
+///     fn ok(&mut self, payload: &[u8]) {
+///         self.ok.push(payload.to_vec());
+///     }
+///
+///     fn repaired(&mut self, _: &[u8], repaired: &[u8], _: &[JsonRepair]) {
+///         self.repaired.push(repaired.to_vec());
+///     }
+///
+///     fn failed(&mut self, _: &[u8], error: JsonError) {
+///         self.failed.push(error.offset());
+///     }
+/// }
+///
+/// # fn main() {
+/// #
+/// let mut options = JsonBuilderOptions::default();
+///
+/// options.lenient.commas = true;
+///
+/// let mut triage = JsonTriage::with_options(options, true);
+/// let mut topics = Topics::default();
+///
+/// for payload in &[r#"{ "id": 1 }"#, r#"{ "id": 2 "tags": [1 2"#, r#"{ "id": }"#] {
+///     triage.route(*payload, &mut topics);
+/// }
+///
+/// assert_eq!(topics.ok,       vec![br#"{ "id": 1 }"#.to_vec()]);
+/// assert_eq!(topics.repaired, vec![br#"{ "id": 2, "tags": [1, 2]}"#.to_vec()]);
+/// assert_eq!(topics.failed,   vec![Some(8)]);
+/// # }
+/// ```
+pub struct JsonTriage {
+    builder:  JsonBuilder,
+    complete: bool,
+}
+
+impl JsonTriage {
+    /// creates a triage that only accepts valid json.
+    pub fn new() -> JsonTriage {
+        JsonTriage::with_options(JsonBuilderOptions::default(), false)
+    }
+
+    /// creates a triage that repairs payloads with a builder created with `options`, and completes truncated payloads
+    /// if `complete` is set.
+    pub fn with_options(options: JsonBuilderOptions, complete: bool) -> JsonTriage {
+        JsonTriage {
+            builder: JsonBuilder::with_options(options),
+            complete,
+        }
+    }
+
+    /// validates and repairs `payload`.
+    pub fn triage(&mut self, payload: impl JsonSource) -> JsonOutcome {
+        let payload = payload.stream();
+        let builder = &mut self.builder;
+        let mut at  = 0;
+
+        builder.reset();
+
+        if let Err(e) = builder.update_at(payload, &mut at) {
+            return JsonOutcome::Failed(e);
+        }
+
+        match (builder.status(), self.complete) {
+            (JsonStatus::Valid, _) if builder.repairs().is_empty() && builder.len() == payload.len() => {
+                JsonOutcome::Ok
+            },
+            (JsonStatus::Valid, _) | (JsonStatus::Continue, true) => {
+                match builder.completed_snapshot() {
+                    Ok(x)  => JsonOutcome::Repaired(x),
+                    Err(e) => JsonOutcome::Failed(e.with_offset(payload.len())),
+                }
+            },
+            (JsonStatus::Continue, false) => {
+                JsonOutcome::Failed(JsonError::at(JsonErrorKind::Invalid, payload.len()))
+            },
+        }
+    }
+
+    /// validates and repairs `payload`, then hands it to the matching method of `sink`.
+    pub fn route(&mut self, payload: impl JsonSource, sink: &mut impl JsonPartition) {
+        let payload = payload.stream();
+
+        match self.triage(payload) {
+            JsonOutcome::Ok          => sink.ok(payload),
+            JsonOutcome::Repaired(x) => sink.repaired(payload, &x, self.builder.repairs()),
+            JsonOutcome::Failed(e)   => sink.failed(payload, e),
+        }
+    }
+}
+
+
+
+/// the result of triaging a single payload with [`JsonTriage`](./struct.JsonTriage.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonOutcome {
+    /// the payload is valid json as it is.
+    Ok,
+
+    /// the payload was repaired or completed into this json.
+    Repaired(Vec<u8>),
+
+    /// the payload could not be repaired.
+    Failed(JsonError),
+}
@@ -0,0 +1,174 @@
+use {
+    std::ops::Range,
+
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonValueKind,
+    arya::JsonVerifier,
+    arya::events::Event,
+    arya::events::EventReader,
+};
+
+
+
+// elides values from the complete json object `data` until it is no longer than `maximum_length` bytes - the most
+// deeply nested first, and of those at the same depth, the last first. an object member or array element is dropped
+// whole along with its separator if `members` or `elements` is `None`, or otherwise has its value replaced with them -
+// only if that makes it shorter. the outermost container is never elided.
+//
+// the values are read in a single pass into a flat list, so that deeply nested input cannot overflow the stack.
+crate fn truncate(
+    data:           &[u8],
+    maximum_length: usize,
+    members:        Option<&[u8]>,
+    elements:       Option<&[u8]>) -> Result<Vec<u8>, JsonError>
+{
+    let mut nodes  = parse(data)?;
+    let mut length = data.len();
+
+    // the values that may be elided, deepest first, then last first.
+    let mut order = (0..nodes.len()).filter(|x| nodes[*x].parent.is_some()).collect::<Vec<_>>();
+
+    order.sort_by_key(|x| (std::cmp::Reverse(nodes[*x].depth), std::cmp::Reverse(nodes[*x].range.start)));
+
+    // the ranges of `data` that were elided, and what replaces them.
+    let mut elisions = vec![];
+
+    for index in order {
+        if length <= maximum_length {
+            break;
+        }
+
+        let node   = &nodes[index];
+        let parent = match node.parent {
+            Some(x) => x,
+            None    => continue,
+        };
+
+        let replacement = match nodes[parent].object {
+            true  => members,
+            false => elements,
+        };
+
+        let (range, replacement) = match (replacement, node.previous) {
+            (Some(x), _)           => (node.range.clone(), x),
+            // the values after it are elided first, so a first value is the only one left once it is dropped.
+            (None, Some(previous)) => (nodes[previous].range.end..node.range.end, &b""[..]),
+            (None, None)           => (node.start..node.range.end, &b""[..]),
+        };
+
+        // the bytes that eliding its own descendants has already saved are not saved again.
+        let current = range.len() - node.saved;
+
+        if replacement.len() >= current {
+            continue;
+        }
+
+        let saved = current - replacement.len();
+        let mut x = Some(parent);
+
+        while let Some(ancestor) = x {
+            nodes[ancestor].saved += saved;
+            x = nodes[ancestor].parent;
+        }
+
+        length -= saved;
+        elisions.push((range, replacement));
+    }
+
+    if length > maximum_length {
+        return Err(JsonErrorKind::TooLarge.into());
+    }
+
+    // an elided value takes its elided descendants with it.
+    elisions.sort_by_key(|(range, _)| (range.start, std::cmp::Reverse(range.end)));
+
+    let mut output = Vec::with_capacity(length);
+    let mut offset = 0;
+
+    for (range, replacement) in elisions {
+        if range.start < offset {
+            continue;
+        }
+
+        output.extend_from_slice(&data[offset..range.start]);
+        output.extend_from_slice(replacement);
+
+        offset = range.end;
+    }
+
+    output.extend_from_slice(&data[offset..]);
+
+    debug_assert_eq!(output.len(), length);
+    Ok(output)
+}
+
+
+
+// a value in a complete json object: its range, whether it is an object, how many containers it is in, the container
+// it is in and the value before it there, if any, and the offset its member starts at - its key, for an object member.
+// `saved` is the number of bytes that eliding its descendants has saved so far.
+struct Node {
+    range:    Range<usize>,
+    object:   bool,
+    depth:    usize,
+    parent:   Option<usize>,
+    previous: Option<usize>,
+    start:    usize,
+    saved:    usize,
+}
+
+// reads the complete json object `data` into a list of its values, in the order they begin.
+fn parse(data: &[u8]) -> Result<Vec<Node>, JsonError> {
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut nodes  = Vec::<Node>::new();
+    let mut key    = None;
+
+    // the containers that are open, and the last value that began in each.
+    let mut containers: Vec<(usize, Option<usize>)> = vec![];
+
+    for &character in data {
+        reader.update(character, |event| {
+            let (range, object, container) = match event {
+                Event::Begin(kind, offset) => (offset..offset, kind == JsonValueKind::Object, true),
+                Event::Scalar(_, range)    => (range, false, false),
+                Event::Key(range, _)       => {
+                    key = Some(range.start);
+                    return;
+                },
+                Event::End(offset) => {
+                    if let Some((index, _)) = containers.pop() {
+                        nodes[index].range.end = offset + 1;
+                    }
+
+                    return;
+                },
+            };
+
+            let index = nodes.len();
+
+            nodes.push(Node {
+                start:    key.take().unwrap_or(range.start),
+                range,
+                object,
+                depth:    containers.len(),
+                parent:   containers.last().map(|x| x.0),
+                previous: containers.last().and_then(|x| x.1),
+                saved:    0,
+            });
+
+            if let Some(parent) = containers.last_mut() {
+                parent.1 = Some(index);
+            }
+
+            if container {
+                containers.push((index, None));
+            }
+        })?;
+    }
+
+    match nodes.is_empty() || !containers.is_empty() {
+        true  => Err(JsonErrorKind::Invalid.into()),
+        false => Ok(nodes),
+    }
+}
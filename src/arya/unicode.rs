@@ -0,0 +1,93 @@
+use {
+    std::ops::Range,
+
+    unicode_normalization::UnicodeNormalization,
+    unicode_normalization::is_nfc,
+
+    arya::JsonError,
+    arya::JsonVerifier,
+    arya::diagnostics,
+    arya::events::Event,
+    arya::events::EventReader,
+    arya::strings::decode_key,
+};
+
+
+
+// characters that render as nothing, and are dropped from skeletons.
+const INVISIBLE: [char; 7] = [
+    '\u{ad}',   // soft hyphen
+    '\u{34f}',  // combining grapheme joiner
+    '\u{200b}', // zero width space
+    '\u{200c}', // zero width non-joiner
+    '\u{200d}', // zero width joiner
+    '\u{2060}', // word joiner
+    '\u{feff}', // zero width no-break space
+];
+
+// cyrillic and greek letters that look like a latin letter, and the letter they look like.
+const HOMOGLYPHS: [(char, char); 36] = [
+    ('а', 'a'), ('е', 'e'), ('к', 'k'), ('о', 'o'), ('р', 'p'), ('с', 'c'), ('у', 'y'), ('х', 'x'),
+    ('і', 'i'), ('ј', 'j'), ('ѕ', 's'), ('ԁ', 'd'),
+    ('А', 'A'), ('В', 'B'), ('Е', 'E'), ('К', 'K'), ('М', 'M'), ('Н', 'H'), ('О', 'O'), ('Р', 'P'),
+    ('С', 'C'), ('Т', 'T'), ('Х', 'X'), ('Ѕ', 'S'), ('І', 'I'), ('Ј', 'J'),
+    ('ο', 'o'), ('ν', 'v'), ('Α', 'A'), ('Β', 'B'), ('Ε', 'E'), ('Η', 'H'), ('Ι', 'I'), ('Κ', 'K'),
+    ('Ο', 'O'), ('Ρ', 'P'),
+];
+
+
+
+// returns the complete json object `data` with every key that is not in unicode normalization form c rewritten in it,
+// or nothing if every key already is. a rewritten key is escaped only as much as json requires.
+crate fn normalize_keys(data: &[u8]) -> Result<Option<Vec<u8>>, JsonError> {
+    let keys = normalized_keys(data)?;
+
+    if keys.is_empty() {
+        return Ok(None);
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    let mut start  = 0;
+
+    for (range, key) in keys {
+        output.extend_from_slice(&data[start..range.start]);
+        output.extend_from_slice(key.as_bytes());
+
+        start = range.end;
+    }
+
+    output.extend_from_slice(&data[start..]);
+
+    Ok(Some(output))
+}
+
+// returns the range of every key in the complete json object `data` that is not in unicode normalization form c,
+// quotes included, and what `normalize_keys()` rewrites it as - in order.
+crate fn normalized_keys(data: &[u8]) -> Result<Vec<(Range<usize>, String)>, JsonError> {
+    let mut reader = EventReader::new(JsonVerifier::new());
+    let mut keys   = vec![];
+
+    for &character in data {
+        reader.update(character, |event| {
+            if let Event::Key(range, text) = event {
+                let key = decode_key(text);
+
+                if !is_nfc(&key) {
+                    keys.push((range, diagnostics::json_string(&key.nfc().collect::<String>())));
+                }
+            }
+        })?;
+    }
+
+    Ok(keys)
+}
+
+// returns what `key` looks like: its compatibility normalization (nfkc), without invisible characters, and with the
+// cyrillic and greek letters that look like latin letters replaced by them. keys that look alike have the same
+// skeleton.
+crate fn skeleton(key: &str) -> String {
+    key.nfkc()
+        .filter(|x| !INVISIBLE.contains(x))
+        .map(|x| HOMOGLYPHS.iter().find(|(y, _)| *y == x).map_or(x, |(_, z)| *z))
+        .collect()
+}
@@ -0,0 +1,67 @@
+use {
+    hina,
+
+    arya::JsonBuilder,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::strings,
+};
+
+
+
+impl JsonBuilder {
+    /// creates a builder from `source`, first unwrapping it if it is a json string that contains escaped json.
+    ///
+    /// # remarks
+    ///
+    /// a document that was json encoded twice (`"{\"a\": 1}"`) is a frequent bug in webhook payloads. this unescapes
+    /// the outer string - as many times as needed - and builds the json inside it, so it can be validated, repaired and
+    /// completed like any other.
+    ///
+    /// the outer string may itself be incomplete; whatever part of it is present is unwrapped. documents that are not a
+    /// string are built as they are.
+    ///
+    /// fails with `JsonErrorKind::NotEscaped` if the innermost string does not begin a json object or array, such as
+    /// `""` or `"hello"`, since those are plain strings rather than escaped documents.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonBuilder, JsonErrorKind};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let builder = JsonBuilder::unwrap_escaped(r#""{\"name\": \"annie\", \"tags\": [1, 2"#).unwrap();
+    ///
+    /// assert_eq!(builder.completed_string().unwrap(), r#"{"name": "annie", "tags": [1, 2]}"#);
+    /// assert_eq!(JsonBuilder::unwrap_escaped(r#""hello""#).err(), Some(JsonErrorKind::NotEscaped.into()));
+    /// # }
+    /// ```
+    pub fn unwrap_escaped(source: impl JsonSource) -> Result<JsonBuilder, JsonError> {
+        let mut data    = hina::trim(source.stream()).to_vec();
+        let mut escaped = false;
+
+        while data.first() == Some(&b'"') {
+            let (content, length) = strings::decode_prefix(&data)?;
+
+            if let Some(length) = length {
+                if !hina::trim(&data[length..]).is_empty() {
+                    return Err(JsonErrorKind::Invalid.into());
+                }
+            }
+
+            data    = hina::trim(&content).to_vec();
+            escaped = true;
+        }
+
+        if escaped && data.first() != Some(&b'{') && data.first() != Some(&b'[') {
+            return Err(JsonErrorKind::NotEscaped.into());
+        }
+
+        let mut builder = JsonBuilder::new();
+
+        builder.update(data)?;
+        Ok(builder)
+    }
+}
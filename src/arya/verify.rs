@@ -1,21 +1,158 @@
 use {
+    std::collections::VecDeque,
+    std::mem,
+    std::time::Duration,
+    std::time::Instant,
+
     arya,
+    arya::JsonBookkeeping,
     arya::JsonError,
+    arya::JsonErrorKind,
     arya::JsonStatus,
-    arya::table::ComplexToken,
+    arya::JsonStrings,
+    arya::JsonTrailing,
+    arya::JsonWhitespace,
+    arya::table::CharacterType,
+    arya::table::Effect,
     arya::table::Token,
     arya::table::Transition,
+    arya::trace::JsonState,
+    arya::trace::JsonTransition,
+    arya::trace::TokenSink,
 };
 
 
 
+/// how far through its input a [`JsonVerifier`](./struct.JsonVerifier.html) is. see
+/// [`JsonVerifier::progress()`](./struct.JsonVerifier.html#method.progress).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct JsonProgress {
+    /// the number of bytes read.
+    pub bytes:   usize,
+
+    /// the number of objects and arrays that are open.
+    pub depth:   usize,
+
+    /// the number of objects and arrays that have been opened.
+    pub opened:  usize,
+
+    /// the number of objects and arrays that have been closed.
+    pub closed:  usize,
+
+    /// the time since `start_clock()` was called, if it was.
+    pub elapsed: Option<Duration>,
+}
+
+impl JsonProgress {
+    /// returns the average number of bytes read per second since the clock was started, if it was.
+    pub fn throughput(&self) -> Option<f64> {
+        self.elapsed.map(|x| self.bytes as f64 / x.as_secs_f64().max(std::f64::MIN_POSITIVE))
+    }
+}
+
+
+
+/// the heap memory held by a [`JsonVerifier`](./struct.JsonVerifier.html) or [`JsonBuilder`](./struct.JsonBuilder.html),
+/// in bytes. memory is counted by capacity, so it includes space that was reserved but is not in use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct JsonMemoryUsage {
+    /// the buffer holding the json object. a verifier has none.
+    pub buffer: usize,
+
+    /// the stack of open objects and arrays.
+    pub stack:  usize,
+
+    /// everything else, such as recorded repairs, and transitions in trace mode.
+    pub other:  usize,
+}
+
+impl JsonMemoryUsage {
+    /// returns the total heap memory held.
+    pub fn total(&self) -> usize {
+        self.buffer + self.stack + self.other
+    }
+}
+
+
+
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum ValueType {
+crate enum ValueType {
     Key,
     Array,
     Object,
 }
 
+// the parts of a verifier that applying a byte can change, other than its stack and the strings it defers - enough to
+// undo bytes that change neither, such as a minus sign or the first bytes of a character. see `JsonVerifier::mark()`.
+#[derive(Debug, Clone, Copy)]
+crate struct Mark {
+    state:        Token,
+    pending:      ([u8; 3], usize),
+    string:       (usize, bool),
+    token_length: usize,
+    length:       usize,
+    last_ok:      usize,
+    ok_depth:     usize,
+}
+
+impl Mark {
+    // accounts for the bytes before this mark having been rewritten from `before` bytes to `after` bytes, like
+    // `JsonVerifier::resize()`.
+    crate fn resize(&mut self, before: usize, after: usize) {
+        self.length  = self.length  - before + after;
+        self.last_ok = self.last_ok - before + after;
+    }
+}
+
+
+
+/// expanded options for constructing a [`JsonVerifier`](./struct.JsonVerifier.html) instance.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub struct JsonVerifierOptions {
+    /// the maximum number of nested objects and arrays.
+    pub maximum_depth: usize,
+
+    /// how non-standard whitespace between tokens is handled.
+    pub whitespace:    JsonWhitespace,
+
+    /// the maximum length of a string value, in bytes as they appear in the input, excluding its quotes.
+    pub maximum_string_length: usize,
+
+    /// the maximum length of an object key, in bytes as they appear in the input, excluding its quotes.
+    pub maximum_key_length:    usize,
+
+    /// the maximum length of a number, in bytes as they appear in the input (including its sign, decimal point, and
+    /// exponent).
+    pub maximum_number_length: usize,
+
+    /// how bytes after a complete json object are handled.
+    pub trailing:              JsonTrailing,
+
+    /// when the contents of strings are checked.
+    pub strings:               JsonStrings,
+
+    /// how much the verifier records about its input, beyond whether it is valid.
+    pub bookkeeping:           JsonBookkeeping,
+}
+
+impl Default for JsonVerifierOptions {
+    fn default() -> JsonVerifierOptions {
+        JsonVerifierOptions {
+            maximum_depth: std::usize::MAX,
+            whitespace:    JsonWhitespace::Permissive,
+
+            maximum_string_length: std::usize::MAX,
+            maximum_key_length:    std::usize::MAX,
+            maximum_number_length: std::usize::MAX,
+
+            trailing:              JsonTrailing::Reject,
+            strings:               JsonStrings::Eager,
+            bookkeeping:           JsonBookkeeping::Full,
+        }
+    }
+}
+
 
 
 /// a fast json syntax validator for utf8 sequences.
@@ -82,13 +219,41 @@ enum ValueType {
 /// //     } - Ok(()) - Valid
 /// # }
 /// ```
-pub struct JsonVerifier {
+#[derive(Clone)]
+pub struct JsonVerifier<S: TokenSink = ()> {
     maximum: usize,
     state:   Token,
     stack:   Vec<ValueType>,
 
-    length:  usize,
-    last_ok: usize,
+    whitespace: JsonWhitespace,
+    pending:    ([u8; 3], usize),
+    trailing:   JsonTrailing,
+
+    // when string contents are checked, and - if they are deferred - the start of the current string, whether it needs
+    // checking, and the ranges of the strings that need checking.
+    strings:    JsonStrings,
+    string:     (usize, bool),
+    deferred:   Vec<(usize, usize)>,
+
+    // the per-token limits, and the length of the string, key or number currently being read.
+    maximum_string: usize,
+    maximum_key:    usize,
+    maximum_number: usize,
+    token_length:   usize,
+
+    // the number of bytes accepted, and the last cut point - unless bookkeeping is minimal.
+    bookkeeping: JsonBookkeeping,
+    length:      usize,
+    last_ok:     usize,
+    ok_depth:    usize,
+
+    // the number of containers opened and closed, and when the clock for `progress()` was started, if it was.
+    opened:   usize,
+    closed:   usize,
+    started:  Option<Instant>,
+
+    trace:    Option<(usize, VecDeque<JsonTransition>)>,
+    sink:     S,
 }
 
 impl JsonVerifier {
@@ -97,18 +262,68 @@ impl JsonVerifier {
     }
 
     pub fn with_maximum_depth(maximum_depth: usize) -> JsonVerifier {
-        assert![maximum_depth > 0];
+        JsonVerifier::with_options(JsonVerifierOptions { maximum_depth, ..Default::default() })
+    }
+
+    pub fn with_options(options: JsonVerifierOptions) -> JsonVerifier {
+        JsonVerifier::with_sink(options, ())
+    }
+}
+
+impl<S: TokenSink> JsonVerifier<S> {
+    /// creates a json verifier that reports every transition it makes to `sink`, so that a single pass over the input
+    /// both validates it and drives `sink`. see [`TokenSink`](./trait.TokenSink.html).
+    pub fn with_sink(options: JsonVerifierOptions, sink: S) -> JsonVerifier<S> {
+        assert![options.maximum_depth > 0];
 
         JsonVerifier {
             stack:   vec![],
             state:   Token::Begin,
-            maximum: maximum_depth,
+            maximum: options.maximum_depth,
+
+            whitespace: options.whitespace,
+            pending:    ([0; 3], 0),
+            trailing:   options.trailing,
+
+            // deferred strings are recorded by their offsets, which minimal bookkeeping does not count.
+            strings:    match options.bookkeeping {
+                JsonBookkeeping::Full    => options.strings,
+                JsonBookkeeping::Minimal => JsonStrings::Eager,
+            },
+            string:     (0, false),
+            deferred:   vec![],
+
+            maximum_string: options.maximum_string_length,
+            maximum_key:    options.maximum_key_length,
+            maximum_number: options.maximum_number_length,
+            token_length:   0,
+
+            bookkeeping: options.bookkeeping,
+            length:      0,
+            last_ok:     0,
+            ok_depth:    0,
 
-            length:  0,
-            last_ok: 0,
+            opened:   0,
+            closed:   0,
+            started:  None,
+
+            trace:    None,
+            sink,
         }
     }
 
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    pub fn sink_mut(&mut self) -> &mut S {
+        &mut self.sink
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
     pub fn len(&self) -> usize {
         self.length
     }
@@ -121,11 +336,248 @@ impl JsonVerifier {
     }
 
     pub fn reset(&mut self) {
-        self.length  = 0;
-        self.last_ok = 0;
-        self.state   = Token::Begin;
+        self.length   = 0;
+        self.last_ok  = 0;
+        self.ok_depth = 0;
+        self.opened   = 0;
+        self.closed   = 0;
+        self.started  = None;
+        self.state    = Token::Begin;
+        self.pending  = ([0; 3], 0);
 
+        self.token_length = 0;
         self.stack.clear();
+        self.deferred.clear();
+
+        if let Some((_, transitions)) = &mut self.trace {
+            transitions.clear();
+        }
+    }
+
+    /// resets this json object, then finds the next position in `data` that a new json document could begin at.
+    ///
+    /// # remarks
+    ///
+    /// a position is chosen if it holds a `{` or `[`, and the rest of `data` from that position is either a complete
+    /// json document followed by anything, or a valid prefix of one. the caller should continue by applying `data` from
+    /// the returned offset onwards.
+    ///
+    /// returns `None` if no such position exists. this verifier is reset either way.
+    ///
+    /// the brackets nested inside a rejected position are resolved from what was read for it, so that most bytes are
+    /// read once. only brackets that were read inside a string are read again.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonStatus, JsonVerifier};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut json = JsonVerifier::new();
+    /// let data     = br#"{ "a": ]] [garbage { "b": [1, 2] }"#;
+    ///
+    /// let offset = data.iter().position(|x| json.update(*x).is_err()).unwrap();
+    /// let offset = offset + json.resync(&data[offset..]).unwrap();
+    ///
+    /// for character in &data[offset..] {
+    ///     json.update(*character).unwrap();
+    /// }
+    ///
+    /// assert_eq!(json.status(), JsonStatus::Valid);
+    /// # }
+    /// ```
+    ///
+    /// ```
+    /// # use arya::JsonVerifier;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut json = JsonVerifier::new();
+    ///
+    /// // a complete document nested inside a rejected one, and one that begins inside a string of a rejected one.
+    /// assert_eq!(json.resync(br#"[1, { "a": 1 }}"#), Some(4));
+    /// assert_eq!(json.resync(br#"[" { "a": 1 }"#), Some(3));
+    /// assert_eq!(json.resync(br#"[[1, 2 }"#), None);
+    /// # }
+    /// ```
+    pub fn resync(&mut self, data: &[u8]) -> Option<usize> {
+        self.reset();
+
+        let options  = self.options();
+        let mut from = 0;
+
+        loop {
+            let start = from + data[from..].iter().position(|x| *x == b'{' || *x == b'[')?;
+
+            let (rejected, error, brackets) = match probe(&options, &data[start..]) {
+                Some(x) => x,
+                None    => return Some(start),
+            };
+
+            // other rejections - such as the depth limit - can depend on where the probe began, so every later position
+            // must be probed again.
+            if error != JsonErrorKind::Invalid {
+                from = start + 1;
+                continue;
+            }
+
+            // a bracket that the probe read as an opening bracket begins the same containers from there: it is
+            // plausible if its container was closed, and rejected at the same byte otherwise. a bracket that was read
+            // in any other way - inside a string, or the rejected byte itself - is where the next probe begins.
+            let mut brackets = brackets.into_iter().skip(1).peekable();
+
+            from = start + rejected + 1;
+
+            for offset in (1..=rejected).filter(|x| data[start + x] == b'{' || data[start + x] == b'[') {
+                match brackets.peek() {
+                    Some(&(x, closed)) if x == offset => {
+                        brackets.next();
+
+                        if closed {
+                            return Some(start + offset);
+                        }
+                    },
+                    _ => {
+                        from = start + offset;
+                        break;
+                    },
+                }
+            }
+        }
+    }
+
+    /// starts the clock for the elapsed time reported by `progress()`, or restarts it. `reset()` stops it.
+    ///
+    /// `wasm32-unknown-unknown` has no clock, so there this does nothing and no time is reported.
+    pub fn start_clock(&mut self) {
+        if cfg!(not(all(target_arch = "wasm32", target_os = "unknown"))) {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// returns how far through its input this json object is, for progress reporting on long streams.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonVerifier;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut json = JsonVerifier::new();
+    ///
+    /// json.start_clock();
+    ///
+    /// for character in br#"{ "a": [1, 2], "b": [{ "c": "#.iter() {
+    ///     json.update(*character).unwrap();
+    /// }
+    ///
+    /// let progress = json.progress();
+    ///
+    /// assert_eq!((progress.bytes, progress.depth, progress.opened, progress.closed), (28, 3, 4, 1));
+    /// assert!(progress.elapsed.is_some());
+    /// # }
+    /// ```
+    pub fn progress(&self) -> JsonProgress {
+        JsonProgress {
+            bytes:   self.length,
+            depth:   self.stack.len(),
+            opened:  self.opened,
+            closed:  self.closed,
+            elapsed: self.started.map(|x| x.elapsed()),
+        }
+    }
+
+    /// enables trace mode, which records the last `capacity` transitions of this json object for debugging.
+    ///
+    /// # remarks
+    ///
+    /// both accepted and rejected characters are recorded. a `capacity` of zero disables trace mode.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::JsonVerifier;
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut json = JsonVerifier::new();
+    ///
+    /// json.trace(2);
+    ///
+    /// for character in br#"{ "a": x"#.iter() {
+    ///     let _ = json.update(*character);
+    /// }
+    ///
+    /// for transition in json.transitions() {
+    ///     println!("{:?}", transition);
+    /// }
+    ///
+    /// // => JsonTransition { offset: 6, character: 32, before: value, after: value, depth: 1, accepted: true }
+    /// // => JsonTransition { offset: 7, character: 120, before: value, after: value, depth: 1, accepted: false }
+    /// # }
+    /// ```
+    pub fn trace(&mut self, capacity: usize) {
+        self.trace = match capacity {
+            0 => None,
+            _ => Some((capacity, VecDeque::with_capacity(capacity))),
+        };
+    }
+
+    /// returns the heap memory held by this json object.
+    pub fn memory_usage(&self) -> JsonMemoryUsage {
+        let trace = self.trace.as_ref().map_or(0, |(_, x)| x.capacity() * mem::size_of::<JsonTransition>());
+
+        JsonMemoryUsage {
+            buffer: 0,
+            stack:  self.stack.capacity() * mem::size_of::<ValueType>(),
+            other:  trace + self.deferred.capacity() * mem::size_of::<(usize, usize)>(),
+        }
+    }
+
+    /// releases the memory this json object reserved but is not using, such as the stack left behind by deeply nested
+    /// input that has since been closed.
+    pub fn shrink(&mut self) {
+        self.stack.shrink_to_fit();
+        self.deferred.shrink_to_fit();
+
+        if let Some((_, transitions)) = &mut self.trace {
+            transitions.shrink_to_fit();
+        }
+    }
+
+    /// checks the escape sequences and utf8 of the strings whose checks were deferred by `JsonStrings::Deferred`.
+    ///
+    /// # remarks
+    ///
+    /// `source` must be every byte applied to this verifier since it was created or last reset. only strings that have
+    /// ended are checked, and each is checked once - so this may be called again as more of the input is read.
+    ///
+    /// if a string is not valid, the offset of the first byte that is not is returned with `JsonErrorKind::Invalid` for
+    /// an escape sequence, or `JsonErrorKind::Utf8`. see [`JsonStrings`](./enum.JsonStrings.html).
+    pub fn finalize_strings(&mut self, source: impl arya::JsonSource) -> Result<(), JsonError> {
+        let source = source.stream();
+
+        for (index, &(start, end)) in self.deferred.iter().enumerate() {
+            let result = match source.get(start..end) {
+                Some(x) => check_string(x).map_err(|(offset, error)| error.with_offset(start + offset)),
+                None    => Err(JsonError::at(JsonErrorKind::Invalid, source.len())),
+            };
+
+            if let Err(e) = result {
+                self.deferred.drain(..index);
+                return Err(e);
+            }
+        }
+
+        self.deferred.clear();
+        Ok(())
+    }
+
+    /// returns the transitions recorded in trace mode, oldest first.
+    pub fn transitions(&self) -> impl Iterator<Item = &JsonTransition> {
+        self.trace.iter().flat_map(|(_, transitions)| transitions.iter())
     }
 
     /// applies `character` to this json object.
@@ -135,124 +587,639 @@ impl JsonVerifier {
     /// if `character` would cause this json object to become invalid, this method returns an error, but keeps its
     /// state. the next invocation of `update()` will operate as if the bad character had never been applied.
     pub fn update(&mut self, character: u8) -> Result<(), JsonError> {
-        // utf8 continuation.
-        if character >= 128 {
-            return self.state(self.state);
+        let (transition, result) = self.step(character);
+
+        self.record(&transition, &result);
+        result
+    }
+
+    /// applies the utf8 encoding of `character` to this json object.
+    ///
+    /// # remarks
+    ///
+    /// like `update()`, if any byte of `character` would cause this json object to become invalid, this method returns
+    /// an error and keeps the state from before `character`. its first bytes reach neither the sink nor the trace -
+    /// only the byte that was rejected is recorded.
+    pub fn update_char(&mut self, character: char) -> Result<(), JsonError> {
+        let mut buffer      = [0; 4];
+        let mut transitions = [None; 4];
+
+        // a multi-byte character never changes the stack, so the rest of the state is enough to undo its first bytes.
+        let mark = self.mark();
+
+        for (index, &x) in character.encode_utf8(&mut buffer).as_bytes().iter().enumerate() {
+            let (transition, result) = self.step(x);
+
+            if result.is_err() {
+                self.rewind(mark);
+                self.record(&transition, &result);
+
+                return result;
+            }
+
+            transitions[index] = Some(transition);
         }
 
+        for transition in transitions.iter().flatten() {
+            self.record(transition, &Ok(()));
+        }
 
-        let character_type = arya::table::character_type(character)?;
-        let transition     = arya::table::transition(self.state, character_type)?;
+        Ok(())
+    }
 
-        match transition {
-            Transition::Error => {
-                panic!("invariant broken: transition::error should never escape `mod table`.");
-            },
+    /// applies `characters` to this json object, for text that has already been decoded.
+    ///
+    /// # examples
+    ///
+    /// ```
+    /// # use arya::{JsonStatus, JsonVerifier};
+    /// #
+    /// # fn main() {
+    /// #
+    /// let mut verifier = JsonVerifier::new();
+    ///
+    /// verifier.update_chars("{ \"名前\": \"アニー\" }".chars()).unwrap();
+    ///
+    /// assert_eq!(verifier.status(), JsonStatus::Valid);
+    /// assert_eq!(verifier.len(), 25);
+    /// # }
+    /// ```
+    pub fn update_chars(&mut self, characters: impl IntoIterator<Item = char>) -> Result<(), JsonError> {
+        for character in characters {
+            self.update_char(character)?;
+        }
 
-            Transition::Simple(state) => {
-                self.state(state)
-            },
+        Ok(())
+    }
 
-            Transition::Complex(ty) => {
-                match ty {
-                    ComplexToken::BraceEmptyClose => {
-                        self.pop(ValueType::Key)?;
-                        self.state(Token::Ok)
-                    },
-                    ComplexToken::BraceClose => {
-                        self.pop(ValueType::Object)?;
-                        self.state(Token::Ok)
-                    },
-                    ComplexToken::BracketClose => {
-                        self.pop(ValueType::Array)?;
-                        self.state(Token::Ok)
-                    },
-                    ComplexToken::BraceOpen => {
-                        self.push(ValueType::Key)?;
-                        self.state(Token::Object)
-                    },
-                    ComplexToken::BracketOpen => {
-                        self.push(ValueType::Array)?;
-                        self.state(Token::Array)
-                    },
-                    ComplexToken::Quote => {
-                        match self.stack.last() {
-                            Some(ValueType::Key)    => self.state(Token::Colon),
-                            Some(ValueType::Array)  => self.state(Token::Ok),
-                            Some(ValueType::Object) => self.state(Token::Ok),
-                            _                       => Err(JsonError::Invalid),
-                        }
-                    },
-                    ComplexToken::Comma => {
-                        match self.stack.last() {
-                            Some(ValueType::Object) => {
-                                self.switch(ValueType::Object, ValueType::Key)?;
-                                self.state(Token::Key)
-                            },
-                            Some(ValueType::Array) => {
-                                self.state(Token::Value)
-                            },
-                            _ => {
-                                Err(JsonError::Invalid)
-                            },
-                        }
-                    },
-                    ComplexToken::Kolon => {
-                        self.switch(ValueType::Key, ValueType::Object)?;
-                        self.state(Token::Value)
-                    },
+
+
+    // returns the length this json object should be truncated to, and the tokens that should then be appended to it,
+    // to complete it into a valid json object.
+    crate fn complete(&self) -> (usize, impl Iterator<Item = u8> + '_) {
+        // a number at the very end of the input is already complete - there is nothing left that could extend it.
+        let (until, depth) = match self.in_number() {
+            true  => (self.length, self.stack.len()),
+            false => (self.last_ok, self.ok_depth),
+        };
+
+        (until, closing_tokens(&self.stack[..depth]))
+    }
+
+    // returns the tokens that close every open container, innermost first.
+    crate fn closing_tokens(&self) -> impl Iterator<Item = u8> + '_ {
+        closing_tokens(&self.stack)
+    }
+
+    // true if the innermost container is an object that is expecting or reading a key.
+    crate fn in_key(&self) -> bool {
+        self.stack.last() == Some(&ValueType::Key)
+    }
+
+    // true if the innermost container is an object that is expecting or reading a value.
+    crate fn in_object_value(&self) -> bool {
+        self.stack.last() == Some(&ValueType::Object)
+    }
+
+    // true if the innermost container is an array.
+    crate fn in_array(&self) -> bool {
+        self.stack.last() == Some(&ValueType::Array)
+    }
+
+    // returns the length of the string, key or number currently being read.
+    crate fn token_length(&self) -> usize {
+        self.token_length
+    }
+
+    // returns the number of bytes at the end of the input that belong to an incomplete escape sequence.
+    crate fn partial_escape(&self) -> usize {
+        match self.state {
+            Token::Escape => 1,
+            Token::U1     => 2,
+            Token::U2     => 3,
+            Token::U3     => 4,
+            Token::U4     => 5,
+            _             => 0,
+        }
+    }
+
+    // returns the length of the prefix of this json object that every completion keeps.
+    crate fn committed(&self) -> usize {
+        self.last_ok
+    }
+
+    // accounts for a complete value before the last cut point having been rewritten from `before` bytes to `after`
+    // bytes, such as a string that was rewritten as a number.
+    crate fn resize(&mut self, before: usize, after: usize) {
+        self.length  = self.length  - before + after;
+        self.last_ok = self.last_ok - before + after;
+    }
+
+    // moves the last cut point back to `length` if it is past it, for a separator that was inserted there - in front of
+    // whitespace that followed a value, which could otherwise be kept by a completion without the value after it.
+    crate fn uncut(&mut self, length: usize) {
+        self.last_ok = self.last_ok.min(length);
+    }
+
+    // returns the state that `rewind()` restores, which undoes the bytes applied after it if none of them changed the
+    // stack or ended a string.
+    crate fn mark(&self) -> Mark {
+        Mark {
+            state:        self.state,
+            pending:      self.pending,
+            string:       self.string,
+            token_length: self.token_length,
+            length:       self.length,
+            last_ok:      self.last_ok,
+            ok_depth:     self.ok_depth,
+        }
+    }
+
+    crate fn rewind(&mut self, mark: Mark) {
+        self.state        = mark.state;
+        self.pending      = mark.pending;
+        self.string       = mark.string;
+        self.token_length = mark.token_length;
+        self.length       = mark.length;
+        self.last_ok      = mark.last_ok;
+        self.ok_depth     = mark.ok_depth;
+    }
+
+    crate fn token(&self) -> Token {
+        self.state
+    }
+
+    crate fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    // true if this verifier accepts or rejects the rest of any input exactly as `other` does - it is in the same state,
+    // with the same containers open and the same partial token - wherever in the input either of them is.
+    crate fn resumes_as(&self, other: &JsonVerifier<S>) -> bool {
+        self.state == other.state
+            && self.stack == other.stack
+            && self.pending == other.pending
+            && self.string.1 == other.string.1
+            && self.token_length == other.token_length
+    }
+
+    // true if the verifier is currently inside a string, where structural characters have no meaning.
+    crate fn in_string(&self) -> bool {
+        self.state.is_string()
+    }
+
+    // true if the verifier is currently at the end of a number that would be valid if no more digits followed.
+    crate fn in_number(&self) -> bool {
+        match self.state {
+            Token::Zero | Token::Integer | Token::Fraction2 | Token::Exponent3 => true,
+            _                                                                  => false,
+        }
+    }
+
+    // true if the last character applied completed a value, and a separator or closing token may follow.
+    crate fn is_value_end(&self) -> bool {
+        self.state == Token::Ok || self.in_number()
+    }
+
+    // true if a value separator must be written before another value can be added at the current depth.
+    crate fn needs_separator(&self) -> bool {
+        !self.stack.is_empty() && self.is_value_end()
+    }
+
+    // returns a description of every token that may be applied next, other than whitespace.
+    crate fn expected(&self) -> Vec<&'static str> {
+        let end: &[&'static str] = match self.stack.last() {
+            Some(ValueType::Array)  => &[",", "]"],
+            Some(ValueType::Object) => &[",", "}"],
+            _                       => &["end of input"],
+        };
+
+        let next: &[&'static str] = match self.state {
+            Token::Begin                                       => &["{", "["],
+            Token::Value                                       => &["value"],
+            Token::Ok                                          => &[],
+            Token::Object                                      => &["string", "}"],
+            Token::Key                                         => &["string"],
+            Token::Colon                                       => &[":"],
+            Token::Array                                       => &["value", "]"],
+            Token::String                                      => &["character", "\""],
+            Token::Escape                                      => &["escape character"],
+            Token::U1 | Token::U2 | Token::U3 | Token::U4      => &["hex digit"],
+            Token::Minus | Token::Fraction1                    => &["digit"],
+            Token::Zero                                        => &[".", "e"],
+            Token::Integer                                     => &["digit", ".", "e"],
+            Token::Fraction2                                   => &["digit", "e"],
+            Token::Exponent1                                   => &["digit", "+", "-"],
+            Token::Exponent2 | Token::Exponent3                => &["digit"],
+            Token::TrueTr                                      => &["r"],
+            Token::TrueTru | Token::NullNu                     => &["u"],
+            Token::TrueTrue | Token::FalseFalse                => &["e"],
+            Token::FalseFa                                     => &["a"],
+            Token::FalseFal | Token::NullNul | Token::NullNull => &["l"],
+            Token::FalseFals                                   => &["s"],
+        };
+
+        match self.state == Token::Ok || self.in_number() {
+            true  => next.iter().chain(end).cloned().collect(),
+            false => next.to_vec(),
+        }
+    }
+
+    // returns a description of what this verifier is reading, for explaining errors to people.
+    crate fn describe(&self) -> &'static str {
+        match self.state {
+            Token::Begin                                                            => "the start of the input",
+            Token::Ok if self.stack.is_empty()                                      => "the end of the input",
+            Token::Ok                                                               => "the space after a value",
+            Token::Object                                                           => "the start of an object",
+            Token::Key                                                              => "the next key of an object",
+            Token::Colon                                                            => "the colon after an object key",
+            Token::Value if self.in_object_value()                                  => "the value of an object member",
+            Token::Value                                                            => "the next element of an array",
+            Token::Array                                                            => "the start of an array",
+            Token::String if self.in_key()                                          => "an object key",
+            Token::String                                                           => "a string",
+            Token::Escape                                                           => "an escape sequence in a string",
+            Token::U1 | Token::U2 | Token::U3 | Token::U4                           => "a unicode escape sequence in a string",
+            Token::Minus | Token::Zero | Token::Integer                             => "a number",
+            Token::Fraction1 | Token::Fraction2                                     => "the fraction of a number",
+            Token::Exponent1 | Token::Exponent2 | Token::Exponent3                  => "the exponent of a number",
+            Token::TrueTr | Token::TrueTru | Token::TrueTrue                        => "the literal `true`",
+            Token::FalseFa | Token::FalseFal | Token::FalseFals | Token::FalseFalse => "the literal `false`",
+            Token::NullNu | Token::NullNul | Token::NullNull                        => "the literal `null`",
+        }
+    }
+
+    // applies `fragment` to a copy of this verifier, succeeding only if `fragment` is exactly one complete json value
+    // (with optional surrounding whitespace) that may be placed at the current position.
+    crate fn splice_value(&self, fragment: &[u8]) -> Result<JsonVerifier<S>, JsonError> where S: Clone {
+        match self.state {
+            Token::Begin | Token::Value | Token::Array => {},
+            _                                          => return Err(JsonErrorKind::Invalid.into()),
+        }
+
+        let depth        = self.stack.len();
+        let mut verifier = self.clone();
+
+        for &character in fragment {
+            // a separator at the starting depth means `fragment` contains more than one value.
+            if character == b',' && !verifier.in_string() && verifier.stack.len() == depth {
+                return Err(JsonErrorKind::Invalid.into());
+            }
+
+            verifier.update(character)?;
+
+            if verifier.stack.len() < depth {
+                return Err(JsonErrorKind::Invalid.into());
+            }
+        }
+
+        match verifier.stack.len() == depth && verifier.is_value_end() {
+            true  => Ok(verifier),
+            false => Err(JsonErrorKind::Invalid.into()),
+        }
+    }
+
+    // accounts for `length` bytes of a complete json value, without reading them, as if they were placed at the
+    // current position. the caller is trusted that they are exactly one such value - only the position is checked.
+    crate fn skip_value(&mut self, length: usize) -> Result<(), JsonError> {
+        match self.state {
+            Token::Begin | Token::Value | Token::Array if self.pending.1 == 0 => {},
+            _                                                                 => Err(JsonErrorKind::Invalid)?,
+        }
+
+        self.state        = Token::Ok;
+        self.length       = self.length + length;
+        self.token_length = 0;
+
+        self.cut(self.length);
+        Ok(())
+    }
+
+    // returns the options this json object was created with.
+    crate fn options(&self) -> JsonVerifierOptions {
+        JsonVerifierOptions {
+            maximum_depth: self.maximum,
+            whitespace:    self.whitespace,
+
+            maximum_string_length: self.maximum_string,
+            maximum_key_length:    self.maximum_key,
+            maximum_number_length: self.maximum_number,
+
+            trailing:              self.trailing,
+            strings:               self.strings,
+            bookkeeping:           self.bookkeeping,
+        }
+    }
+
+
+
+    // applies `character`, and returns the transition it caused - without reporting it.
+    fn step(&mut self, character: u8) -> (JsonTransition, Result<(), JsonError>) {
+        let offset = self.length;
+        let before = self.state;
+        let result = self.apply(character);
+
+        let transition = JsonTransition {
+            offset,
+            character,
+            before:   JsonState(before),
+            after:    JsonState(self.state),
+            depth:    self.stack.len(),
+            accepted: result.is_ok(),
+        };
+
+        (transition, result)
+    }
+
+    // reports `transition` to the sink if it was accepted, and records it in trace mode.
+    fn record(&mut self, transition: &JsonTransition, result: &Result<(), JsonError>) {
+        if result.is_ok() {
+            self.sink.transition(transition);
+        }
+
+        if let Some((capacity, transitions)) = &mut self.trace {
+            if transitions.len() == *capacity {
+                transitions.pop_front();
+            }
+
+            transitions.push_back(*transition);
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            if let Err(e) = result {
+                let character = transition.character;
+
+                tracing::debug!(offset = self.length, character, state = ?self.state, error = ?e, "rejected character");
+            }
+        }
+    }
+
+    fn apply(&mut self, character: u8) -> Result<(), JsonError> {
+        if self.status() == JsonStatus::Valid {
+            match self.trailing {
+                JsonTrailing::Reject => {},
+                JsonTrailing::Ignore => {
+                    self.pending = ([0; 3], 0);
+                    self.advance();
+
+                    return Ok(());
+                },
+                JsonTrailing::BeginNextDocument => {
+                    if (character == b'{' || character == b'[') && self.pending.1 == 0 {
+                        self.state = Token::Begin;
+                    }
+                },
+            }
+        }
+
+        let whitespace = character >= 128 || character == 0x0b || character == 0x0c || self.pending.1 > 0;
+
+        if whitespace && self.whitespace != JsonWhitespace::Permissive && !self.in_string() {
+            return self.apply_whitespace(character);
+        }
+
+        let (state, length) = (self.state, self.length);
+
+        match self.strings {
+            JsonStrings::Eager    => self.transition(character)?,
+            JsonStrings::Deferred => {
+                // any byte is accepted after a backslash - it is checked by `finalize_strings()`.
+                match state {
+                    Token::Escape => self.state(Token::String)?,
+                    _             => self.transition(character)?,
                 }
+
+                self.defer(state, character, length);
             },
         }
+
+        self.limit(state, length)
     }
 
+    // records the range of a string that ended with `character` if it contains escape sequences or non-ascii bytes.
+    fn defer(&mut self, state: Token, character: u8, length: usize) {
+        match (state.is_string(), self.state.is_string()) {
+            (false, true)                                          => self.string = (length + 1, false),
+            (true, true) if character == b'\\' || character >= 128 => self.string.1 = true,
+            (true, false) if self.string.1                         => self.deferred.push((self.string.0, length)),
+            _                                                      => {},
+        }
+    }
 
+    fn transition(&mut self, character: u8) -> Result<(), JsonError> {
+        let (state, effect) = arya::table::step(self.state, character, self.stack.last().cloned())?;
 
-    crate fn complete(&self) -> (usize, impl Iterator<Item = u8> + '_) {
-        let tokens = self.stack.iter().rev().filter_map(|ty| {
-            match ty {
-                ValueType::Array  => Some(b']'),
-                ValueType::Object => Some(b'}'),
-                ValueType::Key    => None,
+        match effect {
+            Effect::None => {},
+            Effect::Open(ty) => {
+                self.push(ty)?;
+                self.opened += 1;
+            },
+            Effect::Close => {
+                self.stack.pop();
+                self.closed += 1;
+            },
+            Effect::Separate(ty) => {
+                self.replace(ty);
+                self.cut(self.length);
+            },
+            Effect::Replace(ty) => {
+                self.replace(ty);
+            },
+        }
+
+        self.state(state)
+    }
+
+    // checks the length of the string, key or number that the last character was part of. if it is too long, that
+    // character is undone - strings and numbers never change the stack or cut points, so only `state` and `length`
+    // need to be restored.
+    fn limit(&mut self, state: Token, length: usize) -> Result<(), JsonError> {
+        // an opening quote is not part of its string, but the first character of a number is part of it.
+        let (maximum, error, token_length) = if self.state.is_string() {
+            let token_length = match state.is_string() {
+                true  => self.token_length + 1,
+                false => 0,
+            };
+
+            match self.stack.last() {
+                Some(ValueType::Key) => (self.maximum_key, JsonErrorKind::KeyTooLong.into(), token_length),
+                _                    => (self.maximum_string, JsonErrorKind::StringTooLong.into(), token_length),
             }
-        });
+        } else if self.state.is_number() {
+            let token_length = match state.is_number() {
+                true  => self.token_length + 1,
+                false => 1,
+            };
+
+            (self.maximum_number, JsonErrorKind::NumberTooLong.into(), token_length)
+        } else {
+            return Ok(());
+        };
+
+        if token_length > maximum {
+            self.state  = state;
+            self.length = length;
+
+            return Err(error);
+        }
 
-        (self.last_ok, tokens)
+        self.token_length = token_length;
+        Ok(())
     }
 
+    // applies a character that may be part of non-standard whitespace.
+    fn apply_whitespace(&mut self, character: u8) -> Result<(), JsonError> {
+        let (mut sequence, length) = self.pending;
+
+        if self.whitespace != JsonWhitespace::Normalize || length == sequence.len() {
+            return Err(JsonErrorKind::Invalid.into());
+        }
+
+        sequence[length] = character;
+
+        let transition = arya::table::transition(self.state, CharacterType::Space)?;
 
+        match arya::table::extra_whitespace(&sequence[..=length]) {
+            Some(true) => {
+                self.pending = ([0; 3], 0);
+
+                match transition {
+                    Transition::Simple(state) => self.state(state),
+                    _                         => Err(JsonErrorKind::Invalid.into()),
+                }
+            },
+            Some(false) => {
+                self.pending = (sequence, length + 1);
+                self.advance();
+
+                Ok(())
+            },
+            None => {
+                Err(JsonErrorKind::Invalid.into())
+            },
+        }
+    }
 
     fn push(&mut self, ty: ValueType) -> Result<(), JsonError> {
         if self.stack.len() < self.maximum {
             self.stack.push(ty);
             Ok(())
         } else {
-            Err(JsonError::Exceeded)
+            Err(JsonErrorKind::Exceeded.into())
         }
     }
 
-    fn pop(&mut self, ty: ValueType) -> Result<(), JsonError> {
-        if self.stack.pop() == Some(ty) {
-            Ok(())
-        } else {
-            Err(JsonError::Invalid)
+    // replaces the innermost container, which `arya::table::step()` has checked there is.
+    fn replace(&mut self, ty: ValueType) {
+        if let Some(last) = self.stack.last_mut() {
+            *last = ty;
         }
     }
 
-    fn switch(&mut self, from: ValueType, to: ValueType) -> Result<(), JsonError> {
-        self.pop(from)?;
-        self.push(to)?;
-        Ok(())
-    }
-
     fn state(&mut self, state: Token) -> Result<(), JsonError> {
         self.state = state;
-        self.length += 1;
+        self.advance();
 
-        if self.state == Token::Ok {
-            self.last_ok = self.length;
+        match self.state {
+            Token::Ok | Token::Object | Token::Array => self.cut(self.length),
+            _                                        => {},
         }
 
         Ok(())
     }
+
+    // counts an accepted byte.
+    fn advance(&mut self) {
+        if self.bookkeeping == JsonBookkeeping::Full {
+            self.length += 1;
+        }
+    }
+
+    // records `length` as the latest point this json object can be truncated to, and then completed by closing every
+    // container that is currently open.
+    fn cut(&mut self, length: usize) {
+        if self.bookkeeping == JsonBookkeeping::Minimal {
+            return;
+        }
+
+        self.last_ok  = length;
+        self.ok_depth = self.stack.len();
+    }
+}
+
+
+
+fn closing_tokens(stack: &[ValueType]) -> impl Iterator<Item = u8> + '_ {
+    stack.iter().rev().map(|ty| {
+        match ty {
+            ValueType::Array                   => b']',
+            ValueType::Object | ValueType::Key => b'}',
+        }
+    })
+}
+
+
+
+// applies `data` to a new verifier until it becomes complete, returning `None` if it is a complete json document or the
+// valid prefix of one. otherwise, returns the offset of the byte that was rejected, the error for it, and the offsets of
+// the opening brackets before it - in order, starting with the first byte, and whether their containers were closed.
+fn probe(options: &JsonVerifierOptions, data: &[u8]) -> Option<(usize, JsonError, Vec<(usize, bool)>)> {
+    let mut verifier = JsonVerifier::with_options(options.clone());
+    let mut brackets = vec![];
+    let mut open     = vec![];
+
+    for (offset, &character) in data.iter().enumerate() {
+        let depth = verifier.depth();
+
+        if let Err(e) = verifier.apply(character) {
+            return Some((offset, e, brackets));
+        }
+
+        if verifier.status() == JsonStatus::Valid {
+            return None;
+        }
+
+        if verifier.depth() > depth {
+            open.push(brackets.len());
+            brackets.push((offset, false));
+        } else if verifier.depth() < depth {
+            if let Some(i) = open.pop() {
+                brackets[i].1 = true;
+            }
+        }
+    }
+
+    None
+}
+
+// checks the escape sequences and utf8 of the contents of a string, returning the offset of the first byte that is not
+// valid and the error for it.
+fn check_string(bytes: &[u8]) -> Result<(), (usize, JsonError)> {
+    if let Err(e) = std::str::from_utf8(bytes) {
+        return Err((e.valid_up_to(), JsonErrorKind::Utf8.into()));
+    }
+
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] != b'\\' {
+            index += 1;
+            continue;
+        }
+
+        let hex = |x: usize| bytes.get(x).map_or(false, |x| x.is_ascii_hexdigit());
+
+        index += match bytes.get(index + 1) {
+            Some(b'"') | Some(b'\\') | Some(b'/')        => 2,
+            Some(b'b') | Some(b'f') | Some(b'n')         => 2,
+            Some(b'r') | Some(b't')                      => 2,
+            Some(b'u') if (2..6).all(|x| hex(index + x)) => 6,
+            _                                            => return Err((index + 1, JsonErrorKind::Invalid.into())),
+        };
+    }
+
+    Ok(())
 }
@@ -0,0 +1,129 @@
+//! exports of the json state machine, for auditing the grammar arya implements.
+//!
+//! everything here is generated from the same transition table that the verifier uses.
+
+
+
+use {
+    std::fmt::Write,
+
+    arya::table,
+    arya::table::CharacterType,
+    arya::table::ComplexToken,
+    arya::table::Token,
+    arya::table::Transition,
+};
+
+
+
+// every state and character type, in table order.
+const TOKENS: [Token; table::STATES] = {
+    use arya::table::Token::*;
+
+    [
+        Begin, Ok, Object, Key, Colon, Value, Array, String, Escape, U1, U2, U3, U4, Minus, Zero, Integer, Fraction1,
+        Fraction2, Exponent1, Exponent2, Exponent3, TrueTr, TrueTru, TrueTrue, FalseFa, FalseFal, FalseFals, FalseFalse,
+        NullNu, NullNul, NullNull,
+    ]
+};
+
+const CHARACTER_TYPES: [(CharacterType, &str); table::CHARACTERS] = {
+    use arya::table::CharacterType::*;
+
+    [
+        (Space, "<space>"), (Whitespace, "<other-white-space>"), (BraceOpen, "{"), (BraceClose, "}"),
+        (BracketOpen, "["), (BracketClose, "]"), (Colon, ":"), (Comma, ","), (Quote, "\""), (Backslash, "\\"),
+        (Slash, "/"), (Plus, "+"), (Minus, "-"), (Dot, "."), (Zero, "0"), (Digit, "<123456789>"), (LowA, "a"),
+        (LowB, "b"), (LowC, "c"), (LowD, "d"), (LowE, "e"), (LowF, "f"), (LowL, "l"), (LowN, "n"), (LowR, "r"),
+        (LowS, "s"), (LowT, "t"), (LowU, "u"), (Abcdf, "<ABCDF>"), (E, "E"), (Other, "<...>"),
+    ]
+};
+
+
+
+/// renders the json state machine as a graphviz `dot` digraph.
+///
+/// # remarks
+///
+/// transitions that depend on the container stack (closing a container, a quote ending either a key or a value, and
+/// so on) are drawn to each state they may lead to, with the stack operation in the label.
+pub fn dot() -> String {
+    let mut output = String::from("digraph arya {\n    rankdir = LR;\n    node [shape = circle];\n\n");
+
+    for &from in TOKENS.iter() {
+        let mut edges: Vec<(Token, &str, Vec<&str>)> = vec![];
+
+        for &(ty, name) in CHARACTER_TYPES.iter() {
+            for (to, note) in targets(from, ty) {
+                match edges.iter_mut().find(|(x, y, _)| *x == to && *y == note) {
+                    Some((_, _, names)) => names.push(name),
+                    None                => edges.push((to, note, vec![name])),
+                }
+            }
+        }
+
+        for (to, note, names) in edges {
+            let label = names.join(" ").replace('\\', "\\\\").replace('"', "\\\"");
+
+            let _ = match note.is_empty() {
+                true  => writeln!(output, "    \"{}\" -> \"{}\" [label = \"{}\"];", from.name(), to.name(), label),
+                false => writeln!(output, "    \"{}\" -> \"{}\" [label = \"{} ({})\"];", from.name(), to.name(), label, note),
+            };
+        }
+    }
+
+    output.push_str("}\n");
+    output
+}
+
+/// renders the json state machine as a markdown table, with one row per state and one column per character type.
+///
+/// # remarks
+///
+/// empty cells are errors. cells for transitions that depend on the container stack list every state they may lead
+/// to.
+pub fn markdown() -> String {
+    let mut output = String::from("| state |");
+
+    for &(_, name) in CHARACTER_TYPES.iter() {
+        let _ = write!(output, " `{}` |", name.replace('|', "\\|"));
+    }
+
+    output.push_str("\n|---|");
+
+    for _ in CHARACTER_TYPES.iter() {
+        output.push_str("---|");
+    }
+
+    for &from in TOKENS.iter() {
+        let _ = write!(output, "\n| {} |", from.name());
+
+        for &(ty, _) in CHARACTER_TYPES.iter() {
+            let cell = targets(from, ty).iter().map(|(to, _)| to.name()).collect::<Vec<_>>().join(" / ");
+            let _ = write!(output, " {} |", cell);
+        }
+    }
+
+    output.push('\n');
+    output
+}
+
+
+
+// the states a transition may lead to, with the stack operation (if any) that decides between them.
+fn targets(from: Token, ty: CharacterType) -> Vec<(Token, &'static str)> {
+    match table::transition(from, ty) {
+        Ok(Transition::Simple(to))   => vec![(to, "")],
+        Ok(Transition::Complex(ty))  => match ty {
+            ComplexToken::BraceEmptyClose => vec![(Token::Ok, "pop key")],
+            ComplexToken::BraceClose      => vec![(Token::Ok, "pop object")],
+            ComplexToken::BracketClose    => vec![(Token::Ok, "pop array")],
+            ComplexToken::BraceOpen       => vec![(Token::Object, "push key")],
+            ComplexToken::BracketOpen     => vec![(Token::Array, "push array")],
+            ComplexToken::Quote           => vec![(Token::Colon, "in key"), (Token::Ok, "in value")],
+            ComplexToken::Comma           => vec![(Token::Key, "in object"), (Token::Value, "in array")],
+            ComplexToken::Kolon           => vec![(Token::Value, "key to object")],
+        },
+        Ok(Transition::Error) | Err(_) => vec![],
+    }
+}
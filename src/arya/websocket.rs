@@ -0,0 +1,132 @@
+use {
+    hina,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonError,
+    arya::JsonErrorKind,
+    arya::JsonSource,
+    arya::JsonStatus,
+};
+
+
+
+/// a per-connection json builder for websocket messages.
+///
+/// # remarks
+///
+/// this is independent of any websocket library: pass it the payload of every text or binary frame with `frame()`, or
+/// of every whole message with `message()`, as your library delivers them.
+///
+/// a document is emitted when a message ends with its json object complete. a document that is incomplete when its
+/// message ends is continued by the next message, so a document may be split across frames or messages. an invalid
+/// document is reported when its message ends, and the next message starts a new one.
+///
+/// when the connection closes in the middle of a document, `close()` completes it if the close was abnormal (such as a
+/// dropped connection), and reports it as invalid otherwise.
+///
+/// # examples
+///
+/// ```
+/// # use arya::{JsonErrorKind, JsonWebSocket};
+/// #
+/// # fn main() {
+/// #
+/// let mut socket = JsonWebSocket::new();
+///
+/// assert_eq!(socket.frame(r#"{ "type": "ping" "#, false), None);
+/// assert_eq!(socket.frame("}", true), Some(Ok(br#"{ "type": "ping" }"#.to_vec())));
+///
+/// assert_eq!(socket.message(r#"{ "type": "message", "#), None);
+/// assert_eq!(socket.message(r#""text": "hello" }"#), Some(Ok(br#"{ "type": "message", "text": "hello" }"#.to_vec())));
+///
+/// assert_eq!(socket.message(r#"{ "type": ]"#), Some(Err(JsonErrorKind::Invalid.into())));
+///
+/// socket.message(r#"{ "type": "message", "parts": [1, 2"#);
+///
+/// assert_eq!(socket.close(false), Some(Ok(br#"{ "type": "message", "parts": [1, 2]}"#.to_vec())));
+/// # }
+/// ```
+pub struct JsonWebSocket {
+    options: JsonBuilderOptions,
+    builder: JsonBuilder,
+    content: bool,
+    error:   Option<JsonError>,
+}
+
+impl JsonWebSocket {
+    pub fn new() -> JsonWebSocket {
+        JsonWebSocket::with_options(JsonBuilderOptions::default())
+    }
+
+    /// creates an adapter that builds every document with a builder created with `options`.
+    pub fn with_options(options: JsonBuilderOptions) -> JsonWebSocket {
+        JsonWebSocket {
+            builder: JsonBuilder::with_options(options.clone()),
+            options,
+            content: false,
+            error:   None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.builder.reset();
+        self.content = false;
+        self.error   = None;
+    }
+
+    /// applies the payload of a text or binary frame, returning the document it completes, if any. `fin` is set on the
+    /// last frame of a message.
+    pub fn frame(&mut self, payload: impl JsonSource, fin: bool) -> Option<Result<Vec<u8>, JsonError>> {
+        let payload = payload.stream();
+
+        if self.error.is_none() {
+            if let Err(e) = self.builder.update(payload) {
+                self.error = Some(e);
+            }
+        }
+
+        self.content |= !hina::trim(payload).is_empty();
+
+        if !fin {
+            return None;
+        }
+
+        match (self.error.clone(), self.builder.status()) {
+            (Some(e), _)                 => Some(self.take().and(Err(e))),
+            (None, JsonStatus::Valid)    => Some(self.take()),
+            (None, JsonStatus::Continue) => None,
+        }
+    }
+
+    /// applies the payload of a whole text or binary message, returning the document it completes, if any.
+    pub fn message(&mut self, payload: impl JsonSource) -> Option<Result<Vec<u8>, JsonError>> {
+        self.frame(payload, true)
+    }
+
+    /// closes the connection, returning the document it was in the middle of, if any. `normal` is set if the close
+    /// was a normal closure, rather than the connection being dropped or failing.
+    pub fn close(self, normal: bool) -> Option<Result<Vec<u8>, JsonError>> {
+        if !self.content {
+            return None;
+        }
+
+        match (self.error, normal) {
+            (Some(e), _)  => Some(Err(e)),
+            (None, true)  => Some(Err(JsonErrorKind::Invalid.into())),
+            (None, false) => Some(self.builder.completed_bytes()),
+        }
+    }
+
+
+
+    // takes the current document, and starts a new one.
+    fn take(&mut self) -> Result<Vec<u8>, JsonError> {
+        let builder = std::mem::replace(&mut self.builder, JsonBuilder::with_options(self.options.clone()));
+
+        self.content = false;
+        self.error   = None;
+
+        builder.bytes()
+    }
+}
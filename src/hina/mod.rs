@@ -13,3 +13,22 @@ crate fn as_slice<T>(item: &T) -> &[T] {
     // safe: the memory layout of a singular `T` is always the same as an array of one `T`.
     unsafe { std::slice::from_raw_parts(item, 1) }
 }
+
+// strips leading and trailing ascii whitespace from `data`.
+crate fn trim(mut data: &[u8]) -> &[u8] {
+    while let Some((first, rest)) = data.split_first() {
+        match first.is_ascii_whitespace() {
+            true  => data = rest,
+            false => break,
+        }
+    }
+
+    while let Some((last, rest)) = data.split_last() {
+        match last.is_ascii_whitespace() {
+            true  => data = rest,
+            false => break,
+        }
+    }
+
+    data
+}
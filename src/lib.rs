@@ -9,6 +9,19 @@
 //! - [`JsonBuilder`](./struct.JsonBuilder.html) - a string builder for json that can repair and complete incomplete ("damaged") json.
 //! - [`JsonVerifier`](./struct.JsonVerifier.html) - a fast json syntax validator.
 //!
+//! ## features.
+//!
+//! - `actix` - accept sloppy json request bodies in `actix-web` with the [`RepairedJson`](./struct.RepairedJson.html) extractor.
+//! - `cbor` - convert between json and cbor with [`JsonBuilder::completed_cbor()`](./struct.JsonBuilder.html#method.completed_cbor) and [`JsonBuilder::from_cbor()`](./struct.JsonBuilder.html#method.from_cbor).
+//! - `conformance` - run the [JSONTestSuite](https://github.com/nst/JSONTestSuite) conformance tests in `tests/conformance.rs`.
+//! - `metrics` - report validation and repair counters to a [`JsonMetrics`](./trait.JsonMetrics.html) recorder.
+//! - `msgpack` - convert between json and messagepack with [`JsonBuilder::completed_msgpack()`](./struct.JsonBuilder.html#method.completed_msgpack) and [`JsonBuilder::from_msgpack()`](./struct.JsonBuilder.html#method.from_msgpack).
+//! - `rayon` - validate many documents in parallel with [`validate_batch_parallel()`](./fn.validate_batch_parallel.html).
+//! - `serde` - serialize values into a `JsonBuilder` with [`JsonSerializer`](./struct.JsonSerializer.html).
+//! - `tower` - validate and repair json request bodies in `tower`, `axum` and `hyper` services with [`JsonBodyLayer`](./struct.JsonBodyLayer.html).
+//! - `tracing` - emit `tracing` events for updates, repairs, and errors.
+//! - `visualize` - export the json state machine as a graphviz graph or markdown table with [`visualize`](./visualize/index.html).
+//!
 //! ## example: json validation + repair.
 //!
 //! ```rust
@@ -98,6 +111,42 @@
     nll,
 )]
 
+#[cfg(feature = "actix")]
+extern crate actix_web;
+
+#[cfg(feature = "actix")]
+extern crate futures_core;
+
+#[cfg(feature = "actix")]
+extern crate serde_json;
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "tower")]
+extern crate bytes;
+
+#[cfg(feature = "tower")]
+extern crate http;
+
+#[cfg(feature = "tower")]
+extern crate http_body;
+
+#[cfg(feature = "tower")]
+extern crate tower_layer;
+
+#[cfg(feature = "tower")]
+extern crate tower_service;
+
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+#[cfg(feature = "unicode")]
+extern crate unicode_normalization;
+
 mod arya;
 mod hina;
 
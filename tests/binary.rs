@@ -0,0 +1,45 @@
+//! decoding cbor documents that nest too deeply to decode without overflowing the stack - through arrays, and through
+//! chains of tags, which nest the same way.
+//!
+//! requires the `cbor` feature:
+//!
+//! ```text
+//! cargo test --features cbor --test binary
+//! ```
+
+#![cfg(feature = "cbor")]
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonErrorKind,
+};
+
+
+
+#[test]
+fn nested_arrays() {
+    let mut cbor = vec![0x81; 100_000];
+
+    cbor.push(0x01);
+
+    assert_eq!(JsonBuilder::from_cbor(&cbor[..]).err(), Some(JsonErrorKind::Exceeded.into()));
+}
+
+#[test]
+fn long_tag_chains() {
+    // tag 6, repeated, around `[1]`.
+    let mut cbor = vec![0xc6; 100_000];
+
+    cbor.extend_from_slice(&[0x81, 0x01]);
+
+    assert_eq!(JsonBuilder::from_cbor(&cbor[..]).err(), Some(JsonErrorKind::Exceeded.into()));
+}
+
+#[test]
+fn short_tag_chains() {
+    let cbor = [0xc6, 0xc6, 0xc1, 0x81, 0x01];
+
+    assert_eq!(JsonBuilder::from_cbor(&cbor[..]).unwrap().string().unwrap(), "[1]");
+}
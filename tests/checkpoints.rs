@@ -0,0 +1,79 @@
+//! revalidating edited documents from checkpoints: whether it resumes from a snapshot before the edit or stops at one
+//! after it, every edit must give the same result as validating the edited document from the start.
+
+extern crate arya;
+
+use {
+    arya::JsonCheckpoints,
+    arya::JsonError,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+};
+
+
+
+const DOCUMENT: &str = concat!(
+    r#"{ "a": [1, 2, 3], "b": { "c": "lorem ipsum", "d": [true, false, null] }, "#,
+    r#""e": -1.5e3, "f": [] }"#,
+);
+
+// edits of `DOCUMENT`, each a range of the document before it and the bytes that replace it.
+const EDITS: [(usize, usize, &str); 12] = [
+    (8, 9, "10"),
+    (8, 10, "1"),
+    (31, 36, "dolor"),
+    (31, 31, "x"),
+    (0, 1, "["),
+    (0, 1, "{"),
+    (52, 56, "false"),
+    (2, 2, r#""z": 0, "#),
+    (20, 20, "}"),
+    (20, 21, ""),
+    (104, 105, ""),
+    (104, 104, "}"),
+];
+
+#[test]
+fn edits_match_validating_from_the_start() {
+    for interval in &[0, 1, 4, 8, 64] {
+        let mut document    = DOCUMENT.as_bytes().to_vec();
+        let mut checkpoints = JsonCheckpoints::new(*interval);
+
+        assert_eq!(checkpoints.validate(&document[..]), validate(&document));
+
+        for &(start, end, replacement) in EDITS.iter() {
+            document.splice(start..end, replacement.bytes());
+
+            let expected = validate(&document);
+            let actual   = checkpoints.revalidate(&document[..], start..end);
+
+            if actual.is_ok() {
+                assert_eq!(checkpoints.status(), status(&document));
+            }
+
+            assert_eq!(actual.map_err(|x| x.offset()), expected.map_err(|x| x.offset()), "{:?}", replacement);
+        }
+    }
+}
+
+
+
+fn validate(document: &[u8]) -> Result<(), JsonError> {
+    let mut verifier = JsonVerifier::new();
+
+    for (i, &character) in document.iter().enumerate() {
+        verifier.update(character).map_err(|x| JsonError::at(x.kind(), i))?;
+    }
+
+    Ok(())
+}
+
+fn status(document: &[u8]) -> JsonStatus {
+    let mut verifier = JsonVerifier::new();
+
+    for &character in document {
+        verifier.update(character).unwrap();
+    }
+
+    verifier.status()
+}
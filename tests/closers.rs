@@ -0,0 +1,45 @@
+//! indented closing tokens, which drop the whitespace before them - but never the whitespace at the end of an
+//! incomplete string that is kept.
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonClosers,
+    arya::JsonIncomplete,
+};
+
+
+
+#[test]
+fn whitespace_before_closers() {
+    let source = "{\n  \"a\": [\n    1,\n    2,\n    ";
+
+    assert_eq!(completed(JsonIncomplete::Drop, source), "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+}
+
+#[test]
+fn whitespace_in_kept_strings() {
+    assert_eq!(completed(JsonIncomplete::Keep, "{\n  \"a\": \"hello,  "), "{\n  \"a\": \"hello,  \"\n}");
+    assert_eq!(completed(JsonIncomplete::Keep, "{\n  \"a\": [\n    \"b   "), "{\n  \"a\": [\n    \"b   \"\n  ]\n}");
+}
+
+#[test]
+fn whitespace_after_dropped_strings() {
+    assert_eq!(completed(JsonIncomplete::Drop, "{\n  \"a\": 1,\n  \"b\": \"hello,  "), "{\n  \"a\": 1\n}");
+}
+
+
+
+fn completed(strings: JsonIncomplete, source: &str) -> String {
+    let mut options = JsonBuilderOptions::default();
+
+    options.closers            = JsonClosers::Indented;
+    options.incomplete_strings = strings;
+
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.update(source).unwrap();
+    builder.completed_string().unwrap()
+}
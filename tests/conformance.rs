@@ -0,0 +1,120 @@
+//! conformance with rfc 8259, measured by the [JSONTestSuite](https://github.com/nst/JSONTestSuite) parsing tests.
+//!
+//! requires the `conformance` feature, and a checkout of the test suite:
+//!
+//! ```text
+//! git clone https://github.com/nst/JSONTestSuite tests/JSONTestSuite
+//! cargo test --features conformance --test conformance -- --nocapture
+//! ```
+//!
+//! the `test_parsing` directory can also be given with the `JSON_TEST_SUITE` environment variable.
+//!
+//! every `y_` case must be accepted and every `n_` case rejected, except for the deviations listed in `DEVIATIONS`.
+//! `i_` cases may go either way, and are only reported.
+
+#![cfg(feature = "conformance")]
+
+extern crate arya;
+
+use {
+    std::env,
+    std::fs,
+    std::path::PathBuf,
+
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::JsonWhitespace,
+};
+
+
+
+// the cases that the verifier knowingly disagrees with, and why.
+//
+// like rfc 4627, the verifier only accepts an object or an array as the outermost value of a json object. rfc 8259
+// also allows any other value there, so every case that is only a string, number or literal is rejected.
+const DEVIATIONS: [(&str, &str); 8] = [
+    ("y_string_space.json",                   TOP_LEVEL),
+    ("y_structure_lonely_false.json",         TOP_LEVEL),
+    ("y_structure_lonely_int.json",           TOP_LEVEL),
+    ("y_structure_lonely_negative_real.json", TOP_LEVEL),
+    ("y_structure_lonely_null.json",          TOP_LEVEL),
+    ("y_structure_lonely_string.json",        TOP_LEVEL),
+    ("y_structure_lonely_true.json",          TOP_LEVEL),
+    ("y_structure_string_empty.json",         TOP_LEVEL),
+];
+
+const TOP_LEVEL: &str = "only objects and arrays are accepted as the outermost value";
+
+#[test]
+fn conformance() {
+    let directory = env::var_os("JSON_TEST_SUITE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/JSONTestSuite/test_parsing"));
+
+    let entries = fs::read_dir(&directory).unwrap_or_else(|e| {
+        panic!("cannot read the test suite at {} ({}), see the top of this file.", directory.display(), e)
+    });
+
+    let mut cases = entries
+        .map(|x| x.unwrap().path())
+        .filter(|x| x.extension().map_or(false, |x| x == "json"))
+        .collect::<Vec<_>>();
+
+    cases.sort();
+
+    let mut failures = vec![];
+    let mut passed   = 0;
+
+    for case in &cases {
+        let name     = case.file_name().unwrap().to_string_lossy().into_owned();
+        let accepted = accepts(&fs::read(case).unwrap());
+        let deviates = DEVIATIONS.iter().find(|(x, _)| *x == name).map(|(_, reason)| *reason);
+
+        let expected = match &name[..2] {
+            "y_" => Some(true),
+            "n_" => Some(false),
+            _    => None,
+        };
+
+        let outcome = match (expected, deviates) {
+            (None, _)                           => "implementation defined",
+            (Some(x), None) if x == accepted    => "pass",
+            (Some(_), None)                     => "FAIL",
+            (Some(x), Some(_)) if x != accepted => "expected deviation",
+            (Some(_), Some(_))                  => "FAIL (listed as a deviation, but passed)",
+        };
+
+        match outcome {
+            "pass"                     => passed += 1,
+            x if x.starts_with("FAIL") => failures.push(name.clone()),
+            _                          => {},
+        }
+
+        let verdict = match accepted {
+            true  => "accepted",
+            false => "rejected",
+        };
+
+        match deviates {
+            Some(reason) => println!("{:<56} {:<9} {} - {}", name, verdict, outcome, reason),
+            None         => println!("{:<56} {:<9} {}", name, verdict, outcome),
+        }
+    }
+
+    println!("\n{} of {} cases passed, {} failed.", passed, cases.len(), failures.len());
+
+    assert!(!cases.is_empty(), "no test cases found in {}", directory.display());
+    assert!(failures.is_empty(), "{} cases failed: {:?}", failures.len(), failures);
+}
+
+// true if the verifier accepts `document` as one complete json object, allowing only rfc 8259 whitespace.
+fn accepts(document: &[u8]) -> bool {
+    let mut options = JsonVerifierOptions::default();
+
+    options.whitespace = JsonWhitespace::Strict;
+
+    let mut verifier = JsonVerifier::with_options(options);
+
+    document.iter().all(|x| verifier.update(*x).is_ok()) && verifier.status() == JsonStatus::Valid
+}
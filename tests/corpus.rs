@@ -0,0 +1,89 @@
+//! golden-output tests for validation and repair.
+//!
+//! every `tests/corpus/<name>.input` is run through the verifier and through each builder preset, and the result is
+//! compared against `tests/corpus/<name>.<strategy>.expected`. a strategy without an expected file is skipped, so a
+//! sample only needs the outputs it is interested in.
+//!
+//! to add a sample, write its `.input` file and run the tests with `ARYA_BLESS=1` to write every expected file, then
+//! check that they are right.
+
+extern crate arya;
+
+use {
+    std::env,
+    std::fs,
+    std::path::Path,
+
+    arya::JsonBuilder,
+    arya::validate_with_context,
+};
+
+
+
+// the strategies every sample is run through, and how each one is run.
+const STRATEGIES: [(&str, fn(&[u8]) -> String); 5] = [
+    ("verify",  verify),
+    ("default", |x| complete(JsonBuilder::new(), x)),
+    ("strict",  |x| complete(JsonBuilder::strict(), x)),
+    ("lenient", |x| complete(JsonBuilder::lenient(), x)),
+    ("llm",     |x| complete(JsonBuilder::llm(), x)),
+];
+
+#[test]
+fn corpus() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("corpus");
+    let bless     = env::var_os("ARYA_BLESS").is_some();
+
+    let mut inputs = fs::read_dir(&directory)
+        .unwrap()
+        .map(|x| x.unwrap().path())
+        .filter(|x| x.extension().map_or(false, |x| x == "input"))
+        .collect::<Vec<_>>();
+
+    inputs.sort();
+
+    let mut failures = vec![];
+
+    for input in &inputs {
+        let name   = input.file_stem().unwrap().to_string_lossy().into_owned();
+        let source = fs::read(input).unwrap();
+
+        // panics if the verifier and builders disagree about the sample.
+        arya::replay(&source);
+
+        for (strategy, run) in &STRATEGIES {
+            let path   = directory.join(format!("{}.{}.expected", name, strategy));
+            let actual = run(&source);
+
+            if bless {
+                fs::write(&path, &actual).unwrap();
+            } else if let Ok(expected) = fs::read_to_string(&path) {
+                if expected != actual {
+                    failures.push(format!("{} ({}):\n  expected: {}\n    actual: {}", name, strategy, expected, actual));
+                }
+            }
+        }
+    }
+
+    assert!(!inputs.is_empty(), "no corpus samples found in {}", directory.display());
+    assert!(failures.is_empty(), "{} corpus samples failed:\n\n{}", failures.len(), failures.join("\n\n"));
+}
+
+// describes whether `source` is valid, incomplete, or where it is invalid.
+fn verify(source: &[u8]) -> String {
+    match validate_with_context(source) {
+        Ok(())                            => "valid".to_string(),
+        Err(ref e) if e.found().is_none() => "incomplete".to_string(),
+        Err(e)                            => format!("error: {}", e),
+    }
+}
+
+// applies `source` to `builder`, and returns its completed output or its error.
+fn complete(mut builder: JsonBuilder, source: &[u8]) -> String {
+    let result = builder.update(source).and_then(|()| builder.completed_string());
+
+    match result {
+        Ok(x)  => x,
+        Err(e) => format!("error: {}", e),
+    }
+}
@@ -0,0 +1,52 @@
+//! the limits on how much completing a json object may change it, which count every byte it inserts - closing tokens,
+//! and the members added by defaults and templates alike.
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonDefault,
+    arya::JsonError,
+    arya::JsonErrorKind,
+};
+
+
+
+#[test]
+fn defaults_count_towards_maximum_inserted() {
+    let complete   = completed(8, r#"{ "a": 1 }"#);
+    let incomplete = completed(8, r#"{ "a": [1"#);
+
+    assert_eq!(complete.err(), Some(JsonErrorKind::CompletionTooLarge.into()));
+    assert_eq!(incomplete.err(), Some(JsonErrorKind::CompletionTooLarge.into()));
+
+    assert!(completed(64, r#"{ "a": [1"#).unwrap().contains(r#""b""#));
+}
+
+#[test]
+fn templates_count_towards_maximum_inserted() {
+    let mut options = JsonBuilderOptions::template(r#"{ "host": "", "cpu": 0.0, "ok": true }"#).unwrap();
+
+    options.maximum_inserted = 4;
+
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.update(r#"{ "host": "a""#).unwrap();
+
+    assert_eq!(builder.completed_bytes().err(), Some(JsonErrorKind::CompletionTooLarge.into()));
+}
+
+
+
+fn completed(maximum_inserted: usize, source: &str) -> Result<String, JsonError> {
+    let mut options = JsonBuilderOptions::default();
+
+    options.defaults.push(JsonDefault::at("/b", r#""a long default value""#));
+    options.maximum_inserted = maximum_inserted;
+
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.update(source).unwrap();
+    builder.completed_string()
+}
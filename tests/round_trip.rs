@@ -0,0 +1,205 @@
+//! the round-trip guarantee: a builder given json that is already valid, and that none of its options change, returns
+//! it byte for byte from `completed_bytes()` - in the buffer it was built in - and reports that it was not modified.
+//! anything else is reported as modified, and fails to complete in round trip mode.
+
+extern crate arya;
+
+use {
+    arya::JsonBorrowedBuilder,
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonCoercion,
+    arya::JsonCoercionKind,
+    arya::JsonDefault,
+    arya::JsonErrorKind,
+    arya::JsonForbiddenAction,
+    arya::JsonForbiddenKeys,
+    arya::JsonTrailing,
+};
+
+
+
+// valid documents, including whitespace, escapes and numbers that a careless rewrite would change.
+const VALID: [&str; 7] = [
+    "{}",
+    "[]",
+    "  {\n    \"a\": [1, 2.50, -3e+4],\n    \"b\": { \"c\": null }\n}\n",
+    r#"{"A":"\n\"\\","b":[true,false,null,"“x”"]}"#,
+    "[\r\n\t1 ,\t2 ]",
+    r#"{ "a": "42", "b": "TRUE", "__proto": {} }"#,
+    "{ \"ключ\": \"значение\", \"é\": \"😀\" }",
+];
+
+#[test]
+fn valid_input_is_kept() {
+    let presets = [
+        JsonBuilderOptions::default(),
+        JsonBuilderOptions::strict(),
+        JsonBuilderOptions::lenient(),
+        JsonBuilderOptions::llm(),
+        round_trip(JsonBuilderOptions::lenient()),
+    ];
+
+    for document in VALID.iter() {
+        for options in presets.iter() {
+            let mut builder = JsonBuilder::with_options(options.clone());
+
+            builder.update(*document).unwrap();
+
+            assert!(!builder.was_modified(), "{:?} was modified with {:?}", document, options);
+            assert_eq!(builder.completed_cow().unwrap(), (document.as_bytes().into(), vec![]));
+            assert_eq!(builder.completed_snapshot().unwrap(), document.as_bytes());
+            assert_eq!(builder.completed_bytes().unwrap(), document.as_bytes());
+        }
+    }
+}
+
+#[test]
+fn valid_input_is_not_copied() {
+    for document in VALID.iter() {
+        let buffer  = Vec::with_capacity(1024);
+        let pointer = buffer.as_ptr();
+
+        let mut builder = JsonBuilder::with_buffer(buffer);
+
+        builder.update(*document).unwrap();
+
+        let data = builder.completed_bytes().unwrap();
+
+        assert_eq!(data, document.as_bytes());
+        assert_eq!(data.as_ptr(), pointer, "{:?} was copied", document);
+    }
+}
+
+#[test]
+fn valid_input_is_not_copied_from_a_borrowed_buffer() {
+    for document in VALID.iter() {
+        let mut buffer = Vec::with_capacity(1024);
+        let pointer    = buffer.as_ptr();
+
+        {
+            let mut builder = JsonBorrowedBuilder::new(&mut buffer);
+
+            builder.update(*document).unwrap();
+            builder.completed().unwrap();
+        }
+
+        assert_eq!(buffer, document.as_bytes());
+        assert_eq!(buffer.as_ptr(), pointer, "{:?} was copied", document);
+    }
+}
+
+#[test]
+fn options_that_keep_valid_input() {
+    // options that could change a json object, but have nothing to change in this one.
+    let mut options = JsonBuilderOptions::default();
+
+    options.defaults.push(JsonDefault::at("/a", "0"));
+    options.coercions.push(JsonCoercion::at("/a", JsonCoercionKind::Numbers));
+    options.forbidden_keys = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Strip));
+
+    let document    = r#"{ "a": 1, "b": "2" }"#;
+    let mut builder = JsonBuilder::with_options(round_trip(options));
+
+    builder.update(document).unwrap();
+
+    assert!(!builder.was_modified());
+    assert_eq!(builder.completed_string().unwrap(), document);
+}
+
+#[test]
+fn modified_input() {
+    let mut trailing = JsonBuilderOptions::default();
+    let mut defaults = JsonBuilderOptions::default();
+    let mut coercion = JsonBuilderOptions::default();
+    let mut strip    = JsonBuilderOptions::default();
+    let mut report   = JsonBuilderOptions::default();
+
+    trailing.trailing = JsonTrailing::Ignore;
+    defaults.defaults.push(JsonDefault::at("/b", "0"));
+    coercion.coercions.push(JsonCoercion::everywhere(JsonCoercionKind::Numbers));
+    strip.forbidden_keys  = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Strip));
+    report.forbidden_keys = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Report));
+
+    let cases = [
+        // a repair, whitespace normalization, and ignored trailing bytes.
+        (JsonBuilderOptions::lenient(),  r#"{ "a": [1 2] }"#,              true),
+        (JsonBuilderOptions::lenient(),  "{ \"a\":\u{a0}1 }",              true),
+        (trailing.clone(),               r#"{ "a": 1 } { "b": 2 }"#,       true),
+
+        // incomplete input, which completion changes.
+        (JsonBuilderOptions::default(),  r#"{ "a": [1, 2"#,                true),
+        (JsonBuilderOptions::default(),  r#"{ "a": 1 "#,                   true),
+
+        // members that are added or stripped, and values that are converted.
+        (defaults,                       r#"{ "a": 1 }"#,                  true),
+        (coercion,                       r#"{ "a": "1" }"#,                true),
+        (strip.clone(),                  r#"{ "a": 1, "__proto__": {} }"#, true),
+
+        // members that are only reported are kept.
+        (report,                         r#"{ "a": 1, "__proto__": {} }"#, false),
+    ];
+
+    for (options, document, modified) in cases.iter() {
+        let mut builder = JsonBuilder::with_options(options.clone());
+
+        builder.update(*document).unwrap();
+
+        assert_eq!(builder.was_modified(), *modified, "{:?}", document);
+
+        let mut builder = JsonBuilder::with_options(round_trip(options.clone()));
+
+        builder.update(*document).unwrap();
+
+        let result = match *modified {
+            true  => Err(JsonErrorKind::Modified.into()),
+            false => Ok(document.as_bytes()),
+        };
+
+        assert_eq!(builder.completed_cow().map(|x| x.0).as_deref().map_err(|x| x.clone()), result, "{:?}", document);
+        assert_eq!(builder.completed_snapshot().as_deref().map_err(|x| x.clone()), result, "{:?}", document);
+        assert_eq!(builder.completed_bytes().as_deref().map_err(|x| x.clone()), result, "{:?}", document);
+    }
+
+    // invalid input fails as it always does.
+    let mut builder = JsonBuilder::with_options(round_trip(strip));
+
+    assert_eq!(builder.update("{ ]"), Err(JsonErrorKind::Invalid.into()));
+    assert!(builder.was_modified());
+    assert_eq!(builder.completed_bytes(), Err(JsonErrorKind::Invalid.into()));
+}
+
+#[test]
+fn reset_clears_modifications() {
+    let mut builder = JsonBuilder::with_options(round_trip(JsonBuilderOptions::lenient()));
+
+    builder.update("{ \"a\":\u{a0}1 }").unwrap();
+    assert!(builder.was_modified());
+
+    builder.reset();
+    builder.update(r#"{ "a": 1 }"#).unwrap();
+
+    assert!(!builder.was_modified());
+    assert_eq!(builder.completed_string().unwrap(), r#"{ "a": 1 }"#);
+}
+
+#[test]
+fn ignored_whitespace() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.trailing = JsonTrailing::Ignore;
+
+    let mut builder = JsonBuilder::with_options(round_trip(options));
+
+    builder.update("{ \"a\": 1 }\r\n").unwrap();
+
+    assert!(!builder.was_modified());
+    assert_eq!(builder.completed_string().unwrap(), r#"{ "a": 1 }"#);
+}
+
+
+
+fn round_trip(mut options: JsonBuilderOptions) -> JsonBuilderOptions {
+    options.round_trip = true;
+    options
+}
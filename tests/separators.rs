@@ -0,0 +1,104 @@
+//! lenient repair of missing separators: a missing comma or colon is inserted right after the value or key before it,
+//! in front of any whitespace - where a formatter would have written it.
+
+extern crate arya;
+
+use {
+    std::io,
+    std::io::Write,
+    std::sync::Arc,
+    std::sync::Mutex,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonCoercion,
+    arya::JsonCoercionKind,
+};
+
+
+
+#[test]
+fn separators_before_whitespace() {
+    let cases = [
+        ("[1 2]",                        "[1, 2]"),
+        ("[1\n  2]",                     "[1,\n  2]"),
+        (r#"{"a" 1}"#,                   r#"{"a": 1}"#),
+        ("{ \"a\" 1, \"b\"\t\"x\" }",    "{ \"a\": 1, \"b\":\t\"x\" }"),
+        (r#"{ "a" [1 2] "b" {} }"#,      r#"{ "a": [1, 2], "b": {} }"#),
+        ("[1 None]",                     "[1, null]"),
+        ("[1]",                          "[1]"),
+    ];
+
+    for (source, expected) in cases.iter() {
+        assert_eq!(completed(JsonBuilder::lenient(), &[*source]), *expected, "{:?}", source);
+    }
+}
+
+#[test]
+fn separators_across_updates() {
+    assert_eq!(completed(JsonBuilder::lenient(), &["[1 ", " 2]"]), "[1,  2]");
+    assert_eq!(completed(JsonBuilder::lenient(), &[r#"{"a" "#, "1}"]), r#"{"a": 1}"#);
+}
+
+#[test]
+fn separators_in_incomplete_input() {
+    // the value after the separator is dropped, and the separator along with it.
+    assert_eq!(completed(JsonBuilder::lenient(), &[r#"[1 "lor"#]), "[1]");
+    assert_eq!(completed(JsonBuilder::lenient(), &[r#"{"a" "lor"#]), "{}");
+    assert_eq!(completed(JsonBuilder::lenient(), &["[1 2"]), "[1, 2]");
+}
+
+#[test]
+fn separators_with_coercions() {
+    let mut options = JsonBuilderOptions::lenient();
+
+    options.coercions.push(JsonCoercion::everywhere(JsonCoercionKind::Numbers));
+
+    assert_eq!(completed(JsonBuilder::with_options(options), &[r#"["1" "2"]"#]), "[1, 2]");
+}
+
+#[test]
+fn separators_after_written_whitespace() {
+    let sink        = Shared::default();
+    let mut builder = JsonBuilder::lenient();
+
+    builder.set_sink(sink.clone());
+    builder.update("[1 ").unwrap();
+
+    assert_eq!(sink.string(), "[1 ");
+
+    // whitespace that was already written to the sink stays in front of the separator.
+    builder.update("2]").unwrap();
+
+    assert_eq!(builder.completed_string().unwrap(), "[1 ,2]");
+    assert_eq!(sink.string(), "[1 ,2]");
+}
+
+
+
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Shared {
+    fn string(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Shared {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn completed(mut builder: JsonBuilder, chunks: &[&str]) -> String {
+    for chunk in chunks {
+        builder.update(*chunk).unwrap();
+    }
+
+    builder.completed_string().unwrap()
+}
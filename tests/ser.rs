@@ -0,0 +1,70 @@
+//! the serde serializer, with the numbers that are hardest to write: floats whose shortest display runs to hundreds of
+//! digits, and integers at the edges of their types.
+//!
+//! requires the `serde` feature:
+//!
+//! ```text
+//! cargo test --features serde --test ser
+//! ```
+
+#![cfg(feature = "serde")]
+
+extern crate arya;
+extern crate serde;
+
+use {
+    arya::JsonBuilder,
+};
+
+
+
+#[test]
+fn extreme_floats() {
+    let floats = [
+        0.0, -0.0, 1.0, -1.5, 0.1, 1e300, -1e300, 1e-300, std::f64::MAX, std::f64::MIN, std::f64::MIN_POSITIVE,
+        std::f64::EPSILON, 5e-324, -5e-324, 123456789012345680000.0,
+    ];
+
+    for &float in floats.iter() {
+        let text = element(&float);
+
+        assert_eq!(text.parse::<f64>().unwrap().to_bits(), float.to_bits(), "{:?} was written as {}", float, text);
+    }
+
+    assert_eq!(element(&std::f32::MAX).parse::<f64>().unwrap(), std::f32::MAX as f64);
+    assert_eq!(element(&std::f32::MIN_POSITIVE).parse::<f64>().unwrap(), std::f32::MIN_POSITIVE as f64);
+}
+
+#[test]
+fn non_finite_floats() {
+    assert_eq!(serialize(&[std::f64::NAN, std::f64::INFINITY, std::f64::NEG_INFINITY]), "[null,null,null]");
+}
+
+#[test]
+fn extreme_integers() {
+    assert_eq!(element(&std::i64::MIN), "-9223372036854775808");
+    assert_eq!(element(&std::u64::MAX), "18446744073709551615");
+    assert_eq!(element(&std::i128::MIN), "-170141183460469231731687303715884105728");
+    assert_eq!(element(&std::u128::MAX), "340282366920938463463374607431768211455");
+}
+
+#[test]
+fn floats_in_containers() {
+    assert_eq!(serialize(&[1e300, 2.5]), format!("[1{},2.5]", "0".repeat(300)));
+}
+
+
+
+// serializes `value` as the only element of an array, since a json object must begin with an object or array.
+fn element<T: serde::Serialize>(value: &T) -> String {
+    let text = serialize(&[value]);
+
+    text[1..text.len() - 1].to_string()
+}
+
+fn serialize<T: serde::Serialize + ?Sized>(value: &T) -> String {
+    let mut builder = JsonBuilder::new();
+
+    builder.serialize(value).unwrap();
+    builder.string().unwrap()
+}
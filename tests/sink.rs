@@ -0,0 +1,121 @@
+//! what a builder writes to its sink: the bytes it has validated as they are built, and - for builders that strip,
+//! normalize or add members - nothing until the json object is finished, and then exactly what it was finished as.
+
+extern crate arya;
+
+use {
+    std::io,
+    std::io::Write,
+    std::sync::Arc,
+    std::sync::Mutex,
+
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonDefault,
+    arya::JsonForbiddenAction,
+    arya::JsonForbiddenKeys,
+    arya::JsonTrailing,
+};
+
+
+
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Shared {
+    fn string(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Shared {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+
+
+#[test]
+fn forbidden_keys_are_not_written() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.forbidden_keys = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Strip));
+
+    let sink        = Shared::default();
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.set_sink(sink.clone());
+    builder.update(r#"{ "a": 1, "__proto__": { "admin": true }, "b": [{ "constructor": {}, "c": 2 }"#).unwrap();
+
+    assert_eq!(sink.string(), "");
+
+    let data = builder.completed_bytes().unwrap();
+
+    assert_eq!(sink.string(), r#"{ "a": 1, "b": [{ "c": 2 }]}"#);
+    assert_eq!(sink.string().as_bytes(), &data[..]);
+}
+
+#[test]
+fn defaults_are_written() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.defaults.push(JsonDefault::at("/b", "0"));
+
+    let sink        = Shared::default();
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.set_sink(sink.clone());
+    builder.update(r#"{ "a": [1"#).unwrap();
+
+    let data = builder.completed_bytes().unwrap();
+
+    assert_eq!(sink.string().as_bytes(), &data[..]);
+    assert!(sink.string().contains(r#""b""#));
+}
+
+#[test]
+fn every_document_is_amended() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.trailing       = JsonTrailing::BeginNextDocument;
+    options.forbidden_keys = Some(JsonForbiddenKeys::prototype(JsonForbiddenAction::Strip));
+
+    let sink        = Shared::default();
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.set_sink(sink.clone());
+    builder.update(r#"{ "__proto__": 1, "a": 1 }{ "b": 2, "constructor": 3 }"#).unwrap();
+    builder.bytes().unwrap();
+
+    assert_eq!(sink.string(), r#"{ "a": 1 }{ "b": 2 }"#);
+}
+
+#[test]
+fn other_builders_write_as_they_build() {
+    let sink        = Shared::default();
+    let mut builder = JsonBuilder::with_sink(sink.clone());
+
+    builder.update(r#"{ "a": [1, 2], "b": "#).unwrap();
+    assert_eq!(sink.string(), r#"{ "a": [1, 2]"#);
+
+    builder.completed_bytes().unwrap();
+    assert_eq!(sink.string(), r#"{ "a": [1, 2]}"#);
+}
+
+#[test]
+fn builders_with_sinks_are_sync() {
+    fn sync<T: Send + Sync>(_: &T) {}
+
+    // a `Cell` is `Send` but not `Sync`.
+    let counted     = std::cell::Cell::new(0);
+    let mut builder = JsonBuilder::with_sink(Shared::default());
+
+    builder.on_complete(move |_| counted.set(counted.get() + 1));
+
+    sync(&builder);
+}
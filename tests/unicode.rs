@@ -0,0 +1,108 @@
+//! unicode handling of object keys: normalizing them to nfc, and linting keys that look alike.
+//!
+//! requires the `unicode` feature:
+//!
+//! ```text
+//! cargo test --features unicode --test unicode
+//! ```
+
+#![cfg(feature = "unicode")]
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonForbiddenAction,
+    arya::JsonForbiddenKeys,
+    arya::JsonLint,
+    arya::JsonLintRules,
+};
+
+
+
+#[test]
+fn normalized_keys() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.normalize_keys = true;
+
+    // a decomposed and an escaped decomposed `é`, a composed one, and a value that is left as it is.
+    let cases = [
+        ("{\"e\u{301}\": \"e\u{301}\"}",   "{\"\u{e9}\": \"e\u{301}\"}"),
+        (r#"{"a": {"cafe\u0301": 1}}"#, "{\"a\": {\"caf\u{e9}\": 1}}"),
+        ("{\"\u{e9}\": 1, \"b\\n\": 2}",   "{\"\u{e9}\": 1, \"b\\n\": 2}"),
+    ];
+
+    for (source, expected) in cases.iter() {
+        let mut builder = JsonBuilder::with_options(options.clone());
+
+        builder.update(*source).unwrap();
+
+        let (data, tokens) = builder.completed_cow().unwrap();
+
+        assert_eq!([&data[..], &tokens].concat(), expected.as_bytes(), "{:?}", source);
+        assert_eq!(builder.completed_string().unwrap(), *expected, "{:?}", source);
+    }
+
+    // incomplete input is completed before its keys are normalized.
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.update("{\"a\": [{\"e\u{301}\": 1").unwrap();
+
+    assert_eq!(builder.completed_string().unwrap(), "{\"a\": [{\"\u{e9}\": 1}]}");
+}
+
+#[test]
+fn forbidden_keys_are_normalized_first() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.normalize_keys = true;
+    options.forbidden_keys = Some(JsonForbiddenKeys::new(vec!["caf\u{e9}"], JsonForbiddenAction::Strip));
+
+    // the decomposed key only matches the forbidden key once it is normalized.
+    let mut builder = JsonBuilder::with_options(options);
+
+    builder.update("{\"cafe\u{301}\": 1, \"a\": [2").unwrap();
+
+    let (data, tokens) = builder.completed_cow().unwrap();
+
+    assert_eq!([&data[..], &tokens].concat(), br#"{"a": [2]}"#);
+    assert_eq!(builder.completed_string().unwrap(), r#"{"a": [2]}"#);
+}
+
+#[test]
+fn confusable_keys() {
+    let rules = JsonLintRules::default();
+
+    let confusable = [
+        "{\"admin\": 1, \"\u{430}dmin\": 2}",        // cyrillic `а`
+        "{\"admin\": 1, \"ad\u{200b}min\": 2}",      // zero width space
+        "{\"\u{e9}\": 1, \"e\u{301}\": 2}",          // decomposed `é`
+        "{\"fi\": 1, \"\u{fb01}\": 2}",              // `ﬁ` ligature
+        r#"{"id": 1, "\u0456d": 2}"#,                // escaped cyrillic `і`
+    ];
+
+    for source in confusable.iter() {
+        let lints = arya::lint(*source, &rules).into_iter().map(|x| x.lint).collect::<Vec<_>>();
+
+        assert_eq!(lints, vec![JsonLint::ConfusableKey], "{:?}", source);
+    }
+
+    // keys in different objects, exact duplicates, and the same key escaped differently are not confusable.
+    let distinct = [
+        "{\"admin\": {\"\u{430}dmin\": 1}}",
+        r#"{"a": 1, "a": 2}"#,
+        r#"{"a": 1, "\u0061": 2}"#,
+    ];
+
+    for source in distinct.iter() {
+        assert!(arya::lint(*source, &rules).iter().all(|x| x.lint != JsonLint::ConfusableKey), "{:?}", source);
+    }
+
+    let mut rules = JsonLintRules::default();
+
+    rules.confusable_keys = false;
+
+    assert!(arya::lint(confusable[0], &rules).is_empty());
+}
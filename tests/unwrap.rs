@@ -0,0 +1,46 @@
+//! unwrapping json documents that were encoded as a json string, once or more, and rejecting strings that only hold
+//! text.
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonErrorKind,
+};
+
+
+
+#[test]
+fn escaped_documents() {
+    let cases = [
+        (r#""{\"a\": 1}""#,                   r#"{"a": 1}"#),
+        (r#"  "[1, 2]"  "#,                   "[1, 2]"),
+        (r#""\"{\\\"a\\\": [true]}\"""#,      r#"{"a": [true]}"#),
+        (r#"" \n {\"a\": \"b\"} ""#,          r#"{"a": "b"}"#),
+        (r#""{\"a\": [1, 2"#,                 r#"{"a": [1, 2]}"#),
+        (r#"{ "a": "{}" }"#,                  r#"{ "a": "{}" }"#),
+    ];
+
+    for (source, expected) in cases.iter() {
+        let builder = JsonBuilder::unwrap_escaped(*source).unwrap();
+
+        assert_eq!(builder.completed_string().unwrap(), *expected, "{:?}", source);
+    }
+}
+
+#[test]
+fn strings_that_hold_text() {
+    let cases = [r#""""#, r#""hello""#, r#""  ""#, r#""\"hello\"""#, r#""42""#, r#""null""#, r#""\"""#, r#"""#];
+
+    for source in cases.iter() {
+        assert_eq!(JsonBuilder::unwrap_escaped(*source).err(), Some(JsonErrorKind::NotEscaped.into()), "{:?}", source);
+    }
+}
+
+#[test]
+fn invalid_documents() {
+    // a string followed by anything else, and an escaped document that is not valid json.
+    assert_eq!(JsonBuilder::unwrap_escaped(r#""{}" 1"#).err(), Some(JsonErrorKind::Invalid.into()));
+    assert_eq!(JsonBuilder::unwrap_escaped(r#""{\"a\" 1}""#).err(), Some(JsonErrorKind::Invalid.into()));
+    assert_eq!(JsonBuilder::unwrap_escaped("hello").err(), Some(JsonErrorKind::Invalid.into()));
+}
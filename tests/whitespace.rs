@@ -0,0 +1,125 @@
+//! conformance with the whitespace rules of rfc 8259: the four whitespace characters (space, tab, line feed, and
+//! carriage return) are accepted, in any number, before the first token, after the last one, and between any two
+//! tokens - and nowhere else outside of strings.
+//!
+//! every document is split into its tokens, and rejoined with whitespace in one gap at a time and then in every gap at
+//! once. each result is checked with every verifier, and with a strict builder, which must keep it byte for byte.
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonFixedVerifier,
+    arya::JsonStatus,
+    arya::JsonVerifier,
+    arya::JsonVerifierOptions,
+    arya::JsonWhitespace,
+};
+
+
+
+// the documents that whitespace is inserted into, as their tokens.
+const DOCUMENTS: [&[&str]; 8] = [
+    &["{", "}"],
+    &["[", "]"],
+    &["{", "\"a\"", ":", "{", "}", "}"],
+    &["{", "\"a\"", ":", "[", "]", ",", "\"b\"", ":", "{", "}", "}"],
+    &["[", "0", ",", "-1", ",", "2.5", ",", "-3e+4", ",", "5E-6", "]"],
+    &["[", "true", ",", "false", ",", "null", ",", "\"\"", ",", "\" x \"", "]"],
+    &["{", "\"a\"", ":", "1", ",", "\"b\"", ":", "[", "{", "\"c\"", ":", "null", "}", ",", "[", "]", "]", "}"],
+    &["[", "[", "[", "]", "]", ",", "{", "\"\\u0041\"", ":", "\"\\n\"", "}", "]"],
+];
+
+// the runs of whitespace that are inserted, such as the indentation and line endings of formatters.
+const WHITESPACE: [&str; 8] = [" ", "\t", "\n", "\r", "\r\n", "\n    ", "\r\n\t\t", " \t\n\r \t\n\r"];
+
+#[test]
+fn whitespace_between_tokens() {
+    for tokens in DOCUMENTS.iter() {
+        for &whitespace in WHITESPACE.iter() {
+            // one gap at a time, including the ones before the first token and after the last.
+            for gap in 0..=tokens.len() {
+                let document = join(tokens, |x| match x == gap {
+                    true  => whitespace,
+                    false => "",
+                });
+
+                assert_accepted(&document);
+            }
+
+            // every gap at once.
+            assert_accepted(&join(tokens, |_| whitespace));
+        }
+    }
+}
+
+#[test]
+fn whitespace_inside_tokens() {
+    let documents = [
+        "[- 1]", "[1 .5]", "[1. 5]", "[1e 5]", "[1e+ 5]", "[t rue]", "[fals e]", "[nu\nll]", "[\"\\ n\"]",
+        "[\"\t\"]", "[\"\n\"]", "[\"\r\"]",
+    ];
+
+    for document in documents.iter() {
+        assert!(!accepts(&mut JsonVerifier::new(), document.as_bytes()), "{:?} was accepted", document);
+    }
+}
+
+#[test]
+fn other_whitespace() {
+    // vertical tab, form feed, no-break space and the byte order mark are not rfc 8259 whitespace.
+    let documents = ["[\x0b1]", "[1\x0c]", "[1,\u{a0}2]", "\u{feff}[1]"];
+
+    for document in documents.iter() {
+        assert!(!accepts(&mut strict(), document.as_bytes()), "{:?} was accepted", document);
+    }
+}
+
+
+
+// joins `tokens`, with `gap(i)` before the token at `i` - and `gap(tokens.len())` after the last one.
+fn join<'a>(tokens: &[&str], gap: impl Fn(usize) -> &'a str) -> String {
+    let mut document = String::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        document.push_str(gap(i));
+        document.push_str(token);
+    }
+
+    document.push_str(gap(tokens.len()));
+    document
+}
+
+fn assert_accepted(document: &str) {
+    let source = document.as_bytes();
+
+    assert!(accepts(&mut JsonVerifier::new(), source), "{:?} was rejected", document);
+    assert!(accepts(&mut strict(), source), "{:?} was rejected with strict whitespace", document);
+
+    let mut fixed = JsonFixedVerifier::<8>::new();
+
+    assert!(source.iter().all(|x| fixed.update(*x).is_ok()), "{:?} was rejected by a fixed verifier", document);
+    assert_eq!(fixed.status(), JsonStatus::Valid, "{:?} was incomplete for a fixed verifier", document);
+
+    let mut builder = JsonBuilder::strict();
+
+    builder.update(document).unwrap();
+
+    assert_eq!(builder.string().unwrap(), document);
+
+    // panics if the verifier and builders disagree about the document.
+    assert_eq!(arya::replay(source).status, Ok(JsonStatus::Valid));
+}
+
+// true if `verifier` accepts `document` as one complete json object.
+fn accepts(verifier: &mut JsonVerifier, document: &[u8]) -> bool {
+    document.iter().all(|x| verifier.update(*x).is_ok()) && verifier.status() == JsonStatus::Valid
+}
+
+fn strict() -> JsonVerifier {
+    let mut options = JsonVerifierOptions::default();
+
+    options.whitespace = JsonWhitespace::Strict;
+
+    JsonVerifier::with_options(options)
+}
@@ -0,0 +1,65 @@
+//! the methods that write structure into a builder - `value()`, `key()`, `begin_object()` and the rest - while the end
+//! of the last update is still held back, such as a partial literal. they must fail without changing the builder, so
+//! that the held back bytes are not written after them.
+
+extern crate arya;
+
+use {
+    arya::JsonBuilder,
+    arya::JsonBuilderOptions,
+    arya::JsonErrorKind,
+    arya::JsonWhitespace,
+};
+
+
+
+#[test]
+fn partial_literals() {
+    let mut builder = JsonBuilder::lenient();
+
+    builder.update("[Tru").unwrap();
+
+    assert_eq!(builder.value("1"), Err(JsonErrorKind::Invalid.into()));
+    assert_eq!(builder.insert_raw_value("1"), Err(JsonErrorKind::Invalid.into()));
+    assert_eq!(builder.begin_object(), Err(JsonErrorKind::Invalid.into()));
+    assert_eq!(builder.end_array(), Err(JsonErrorKind::Invalid.into()));
+
+    builder.update("e, ").unwrap();
+    builder.value("1").unwrap();
+
+    assert_eq!(builder.completed_string().unwrap(), "[true, 1]");
+}
+
+#[test]
+fn partial_punctuation() {
+    let mut builder = JsonBuilder::lenient();
+
+    // the first two bytes of a left double quotation mark, `“`.
+    builder.update(&b"[\xe2\x80"[..]).unwrap();
+
+    assert_eq!(builder.value("1"), Err(JsonErrorKind::Invalid.into()));
+    assert_eq!(builder.begin_array(), Err(JsonErrorKind::Invalid.into()));
+
+    builder.update(&b"\x9ca\xe2\x80\x9d]"[..]).unwrap();
+
+    assert_eq!(builder.completed_string().unwrap(), r#"["a"]"#);
+}
+
+#[test]
+fn partial_whitespace() {
+    let mut options = JsonBuilderOptions::default();
+
+    options.whitespace = JsonWhitespace::Normalize;
+
+    let mut builder = JsonBuilder::with_options(options);
+
+    // the first byte of a no-break space.
+    builder.update(&b"[1,\xc2"[..]).unwrap();
+
+    assert_eq!(builder.value("2"), Err(JsonErrorKind::Invalid.into()));
+    assert_eq!(builder.begin_object(), Err(JsonErrorKind::Invalid.into()));
+
+    builder.update(&b"\xa02]"[..]).unwrap();
+
+    assert_eq!(builder.completed_string().unwrap(), "[1, 2]");
+}